@@ -0,0 +1,101 @@
+//! Compares component storage backends on the PositionIntegrator workload
+//!
+//! Run with `cargo bench -p antigen`
+
+use std::time::Duration;
+
+use antigen::{
+    components::{Name, Position, Velocity},
+    core::profiler::Profiler,
+    entity_component_system::{
+        ArchetypeDirectory, ColumnComponentStorage, ComponentHooks, ComponentStorage,
+        EntityComponentDirectory, HeapComponentStorage, SingleThreadedDirectory,
+        SparseSetComponentStorage, SparseSetDirectory, SystemInterface, SystemTrait,
+    },
+    primitive_types::Vector2I,
+    systems::PositionIntegrator,
+};
+
+const MOVING_ENTITIES: i64 = 10_000;
+const STATIC_ENTITIES: i64 = 5_000;
+const WARMUP_FRAMES: u32 = 10;
+const FRAMES: u32 = 100;
+
+fn bench<CS, CD>(name: &str, mut component_storage: CS, mut entity_component_directory: CD)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    let component_hooks = ComponentHooks::new();
+    let mut db = SystemInterface::new(
+        &mut component_storage,
+        &mut entity_component_directory,
+        &component_hooks,
+    );
+
+    let setup_profiler = Profiler::start();
+    for i in 0..MOVING_ENTITIES {
+        let entity_id = db.create_entity(None).unwrap();
+        db.insert_entity_component(entity_id, Position(Vector2I(i, i)))
+            .unwrap();
+        let velocity = db
+            .insert_entity_component(entity_id, Velocity::default())
+            .unwrap();
+        **velocity = Vector2I(1, -1);
+    }
+
+    for i in 0..STATIC_ENTITIES {
+        let entity_id = db.create_entity(Some("Static")).unwrap();
+        db.insert_entity_component(entity_id, Position(Vector2I(i, i)))
+            .unwrap();
+    }
+    let setup = setup_profiler.finish();
+
+    let mut position_integrator = PositionIntegrator;
+    for _ in 0..WARMUP_FRAMES {
+        position_integrator.run(&mut db).unwrap();
+    }
+
+    let mut total = Duration::default();
+    for _ in 0..FRAMES {
+        let frame_profiler = Profiler::start();
+        position_integrator.run(&mut db).unwrap();
+        total += frame_profiler.finish();
+    }
+
+    // Make sure the workload wasn't optimized away
+    let names = db.query::<&Name>().unwrap().len();
+    assert_eq!(names as i64, STATIC_ENTITIES);
+
+    println!(
+        "{:<40} setup {:>10.3?}  frame {:>10.3?}",
+        name,
+        setup,
+        total / FRAMES
+    );
+}
+
+fn main() {
+    println!(
+        "PositionIntegrator: {} moving entities, {} static entities, {} frames",
+        MOVING_ENTITIES, STATIC_ENTITIES, FRAMES
+    );
+
+    bench(
+        "HeapComponentStorage + SingleThreaded",
+        HeapComponentStorage::new(),
+        SingleThreadedDirectory::new(),
+    );
+
+    bench(
+        "ColumnComponentStorage + Archetype",
+        ColumnComponentStorage::new(),
+        ArchetypeDirectory::new(),
+    );
+
+    bench(
+        "SparseSetComponentStorage + SparseSet",
+        SparseSetComponentStorage::new(),
+        SparseSetDirectory::new(),
+    );
+}
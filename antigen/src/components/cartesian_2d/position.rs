@@ -1,7 +1,10 @@
 use crate::primitive_types::Vector2I;
+use serde::{Deserialize, Serialize};
 use std::{fmt::Debug, ops::Deref, ops::DerefMut};
 
-#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(
+    Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
 pub struct Position(pub Vector2I);
 
 impl Deref for Position {
@@ -1,8 +1,9 @@
 use std::ops::{Deref, DerefMut};
 
 use crate::primitive_types::Vector2I;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
 pub struct Size(pub Vector2I);
 
 impl Deref for Size {
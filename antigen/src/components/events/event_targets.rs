@@ -1,9 +1,10 @@
 use std::{fmt::Debug, ops::Deref, ops::DerefMut};
 
-use crate::entity_component_system::EntityID;
+use crate::entity_component_system::{EcsError, EntityID, EntityIDMap, MapEntities};
+use serde::{Deserialize, Serialize};
 
 /// Holds a list of entities to be used as targets for emitted events
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EventTargets(Vec<EntityID>);
 
 impl EventTargets {
@@ -31,3 +32,12 @@ impl Default for EventTargets {
         EventTargets::new(Vec::new())
     }
 }
+
+impl MapEntities for EventTargets {
+    fn map_entities(&mut self, entity_id_map: &EntityIDMap) -> Result<(), EcsError> {
+        for entity_id in self.0.iter_mut() {
+            *entity_id = entity_id_map.map(*entity_id)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use crate::core::events::AntigenInputEvent;
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub enum InputRecordingMode {
+    #[default]
+    Idle,
+    Recording,
+    Playback { frame: usize },
+}
+
+/// Per-frame copies of the input event queue, captured and replayed by the InputRecorder system
+#[derive(Debug, Default, Clone)]
+pub struct InputRecordingData {
+    mode: InputRecordingMode,
+    frames: Vec<Vec<AntigenInputEvent>>,
+}
+
+impl InputRecordingData {
+    pub fn new() -> Self {
+        InputRecordingData::default()
+    }
+
+    /// Discard any existing recording and start capturing input
+    pub fn start_recording(&mut self) {
+        self.frames.clear();
+        self.mode = InputRecordingMode::Recording;
+    }
+
+    /// Replay the recording from its first frame, replacing live input until it ends
+    pub fn start_playback(&mut self) {
+        self.mode = InputRecordingMode::Playback { frame: 0 };
+    }
+
+    pub fn stop(&mut self) {
+        self.mode = InputRecordingMode::Idle;
+    }
+
+    pub fn get_mode(&self) -> InputRecordingMode {
+        self.mode
+    }
+
+    pub fn get_frames(&self) -> &[Vec<AntigenInputEvent>] {
+        &self.frames
+    }
+
+    pub(crate) fn push_frame(&mut self, events: Vec<AntigenInputEvent>) {
+        self.frames.push(events);
+    }
+
+    /// Returns the events for the current playback frame and advances to the next,
+    /// going idle once the recording is exhausted
+    pub(crate) fn next_frame(&mut self) -> Option<&[AntigenInputEvent]> {
+        let frame = match self.mode {
+            InputRecordingMode::Playback { frame } if frame < self.frames.len() => frame,
+            InputRecordingMode::Playback { .. } => {
+                self.mode = InputRecordingMode::Idle;
+                return None;
+            }
+            _ => return None,
+        };
+
+        self.mode = InputRecordingMode::Playback { frame: frame + 1 };
+        Some(&self.frames[frame])
+    }
+}
@@ -1,7 +1,9 @@
 mod event_queue;
 mod event_targets;
+mod input_recording;
 mod timer;
 
 pub use event_queue::*;
 pub use event_targets::*;
+pub use input_recording::*;
 pub use timer::*;
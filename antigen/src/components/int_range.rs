@@ -1,7 +1,8 @@
 use std::ops::Range;
+use serde::{Deserialize, Serialize};
 
 /// Integer clamped to a range
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IntRange {
     index: i64,
     range: Range<i64>,
@@ -0,0 +1,81 @@
+use std::ops::BitOr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::Glyph,
+    primitive_types::{ColorRGB, ColorRGBF},
+};
+
+/// Text attributes applied to a cell's glyph
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CellAttributes {
+    pub bold: bool,
+    pub underline: bool,
+    pub reverse: bool,
+    pub dim: bool,
+}
+
+/// Combines two sets of attributes, keeping those set in either
+impl BitOr for CellAttributes {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        CellAttributes {
+            bold: self.bold || rhs.bold,
+            underline: self.underline || rhs.underline,
+            reverse: self.reverse || rhs.reverse,
+            dim: self.dim || rhs.dim,
+        }
+    }
+}
+
+/// How a backend colors text in cells that have no foreground color of their own
+#[derive(Debug, Copy, Clone)]
+pub enum TextColorMode {
+    BlackWhite,
+    Invert,
+    Color(ColorRGBF),
+}
+
+impl TextColorMode {
+    pub fn get_text_color(&self, background: ColorRGBF) -> ColorRGBF {
+        let white = ColorRGB(1.0f32, 1.0f32, 1.0f32);
+        let black = ColorRGB(0.0f32, 0.0f32, 0.0f32);
+
+        match self {
+            TextColorMode::Color(color) => *color,
+            TextColorMode::BlackWhite => {
+                if ColorRGB::distance(&background, &white) > ColorRGB::distance(&background, &black)
+                {
+                    white
+                } else {
+                    black
+                }
+            }
+            TextColorMode::Invert => white - background,
+        }
+    }
+}
+
+/// A single composited character cell
+///
+/// A foreground of None leaves the text color up to the backend, ex. via its TextColorMode.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Cell {
+    pub glyph: Glyph,
+    pub foreground: Option<ColorRGBF>,
+    pub background: ColorRGBF,
+    pub attributes: CellAttributes,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Cell {
+            glyph: Glyph::SPACE,
+            foreground: None,
+            background: ColorRGB(0.0, 0.0, 0.0),
+            attributes: CellAttributes::default(),
+        }
+    }
+}
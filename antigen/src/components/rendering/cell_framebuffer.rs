@@ -0,0 +1,240 @@
+use std::fmt::Debug;
+
+use crate::{
+    components::{Cell, CellAttributes, Glyph},
+    primitive_types::ColorRGBF,
+};
+
+/// Framebuffer of composited cells, written by both rect and text rendering
+///
+/// Backgrounds and glyphs are depth-tested separately so they can be drawn in either order:
+/// a glyph is visible if its Z is at least that of the background beneath it.
+#[derive(Clone, PartialEq)]
+pub struct CellFramebuffer {
+    clear_cell: Cell,
+    cells: Vec<Cell>,
+    background_z_buffer: Vec<Option<i64>>,
+    glyph_z_buffer: Vec<Option<i64>>,
+}
+
+impl Debug for CellFramebuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CellFramebuffer").finish()
+    }
+}
+
+impl Default for CellFramebuffer {
+    fn default() -> Self {
+        CellFramebuffer::new(Cell::default())
+    }
+}
+
+impl CellFramebuffer {
+    pub fn new(clear_cell: Cell) -> CellFramebuffer {
+        CellFramebuffer {
+            clear_cell,
+            cells: Vec::new(),
+            background_z_buffer: Vec::new(),
+            glyph_z_buffer: Vec::new(),
+        }
+    }
+
+    /// Returns the composited cells
+    ///
+    /// Wide glyphs are drawn as a glyph followed by a continuation cell.
+    /// Any half left without its partner, ex. by an overlapping draw, is replaced with a space.
+    pub fn get_cells(&self) -> Vec<Cell> {
+        let mut cells = self.cells.clone();
+
+        for (idx, cell) in cells.iter_mut().enumerate() {
+            let orphaned = if cell.glyph.is_continuation() {
+                match idx.checked_sub(1) {
+                    Some(prev_idx) => self.cells[prev_idx].glyph.width() != 2,
+                    None => true,
+                }
+            } else if cell.glyph.width() == 2 {
+                match self.cells.get(idx + 1) {
+                    Some(next) => !next.glyph.is_continuation(),
+                    None => true,
+                }
+            } else {
+                false
+            };
+
+            if orphaned {
+                cell.glyph = Glyph::SPACE;
+            }
+        }
+
+        cells
+    }
+
+    pub fn resize(&mut self, new_size: usize) {
+        if self.cells.len() != new_size {
+            self.cells.resize(new_size, self.clear_cell);
+        }
+
+        if self.background_z_buffer.len() != new_size {
+            self.background_z_buffer.resize(new_size, None);
+        }
+
+        if self.glyph_z_buffer.len() != new_size {
+            self.glyph_z_buffer.resize(new_size, None);
+        }
+    }
+
+    pub fn clear_backgrounds(&mut self) {
+        let background = self.clear_cell.background;
+        self.cells
+            .iter_mut()
+            .for_each(|cell| cell.background = background);
+        self.background_z_buffer.iter_mut().for_each(|z| *z = None);
+    }
+
+    pub fn clear_glyphs(&mut self) {
+        for idx in 0..self.cells.len() {
+            self.clear_glyph(idx);
+        }
+    }
+
+    pub fn draw_background(
+        &mut self,
+        x: i64,
+        y: i64,
+        window_width: i64,
+        background: ColorRGBF,
+        z: i64,
+    ) {
+        let idx = (y * window_width + x) as usize;
+
+        if let Some(existing_z) = self.background_z_buffer[idx] {
+            if existing_z > z {
+                return;
+            }
+        }
+
+        self.cells[idx].background = background;
+        self.background_z_buffer[idx] = Some(z);
+
+        // Hide any glyph this background was drawn over
+        if let Some(glyph_z) = self.glyph_z_buffer[idx] {
+            if glyph_z < z {
+                self.clear_glyph(idx);
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn draw_glyph(
+        &mut self,
+        x: i64,
+        y: i64,
+        window_width: i64,
+        glyph: Glyph,
+        foreground: Option<ColorRGBF>,
+        attributes: CellAttributes,
+        z: i64,
+    ) {
+        let idx = (y * window_width + x) as usize;
+
+        let occluded = |existing_z: Option<i64>| match existing_z {
+            Some(existing_z) => existing_z > z,
+            None => false,
+        };
+
+        if occluded(self.background_z_buffer[idx]) || occluded(self.glyph_z_buffer[idx]) {
+            return;
+        }
+
+        let cell = &mut self.cells[idx];
+        cell.glyph = glyph;
+        cell.foreground = foreground;
+        cell.attributes = attributes;
+        self.glyph_z_buffer[idx] = Some(z);
+    }
+
+    fn clear_glyph(&mut self, idx: usize) {
+        let cell = &mut self.cells[idx];
+        cell.glyph = self.clear_cell.glyph;
+        cell.foreground = self.clear_cell.foreground;
+        cell.attributes = self.clear_cell.attributes;
+        self.glyph_z_buffer[idx] = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Cell, CellAttributes, Glyph},
+        primitive_types::ColorRGB,
+    };
+
+    use super::CellFramebuffer;
+
+    #[test]
+    fn cell_framebuffer() {
+        let red = ColorRGB(1.0, 0.0, 0.0);
+        let blue = ColorRGB(0.0, 0.0, 1.0);
+        let bold = CellAttributes {
+            bold: true,
+            ..CellAttributes::default()
+        };
+
+        let mut framebuffer = CellFramebuffer::default();
+        framebuffer.resize(3);
+
+        // Glyphs drawn before a higher background are hidden by it, regardless of order
+        framebuffer.draw_glyph(0, 0, 3, 'a'.into(), Some(blue), bold, 0);
+        framebuffer.draw_glyph(1, 0, 3, 'b'.into(), None, bold, 1);
+        framebuffer.draw_background(0, 0, 3, red, 1);
+        framebuffer.draw_background(1, 0, 3, red, 1);
+        framebuffer.draw_background(2, 0, 3, red, 1);
+        framebuffer.draw_glyph(2, 0, 3, 'c'.into(), None, CellAttributes::default(), 0);
+
+        let cells = framebuffer.get_cells();
+        assert_eq!(
+            cells[0],
+            Cell {
+                background: red,
+                ..Cell::default()
+            }
+        );
+        assert_eq!(
+            cells[1],
+            Cell {
+                glyph: 'b'.into(),
+                background: red,
+                attributes: bold,
+                ..Cell::default()
+            }
+        );
+        assert_eq!(cells[2].glyph, ' ');
+
+        // Layers are cleared independently
+        framebuffer.clear_backgrounds();
+        assert_eq!(framebuffer.get_cells()[1].glyph, 'b');
+        assert_eq!(
+            framebuffer.get_cells()[1].background,
+            Cell::default().background
+        );
+
+        framebuffer.clear_glyphs();
+        assert_eq!(framebuffer.get_cells(), vec![Cell::default(); 3]);
+
+        // Wide glyphs that lose either half are replaced with spaces
+        let wide = Glyph::new("日");
+        let default = CellAttributes::default();
+        framebuffer.draw_glyph(0, 0, 3, wide, None, default, 0);
+        framebuffer.draw_glyph(1, 0, 3, Glyph::CONTINUATION, None, default, 0);
+        assert_eq!(framebuffer.get_cells()[0].glyph, wide);
+
+        framebuffer.draw_glyph(1, 0, 3, wide, None, default, 1);
+        framebuffer.draw_glyph(2, 0, 3, Glyph::CONTINUATION, None, default, 1);
+        let cells = framebuffer.get_cells();
+        assert_eq!(cells[0].glyph, ' ');
+        assert_eq!(cells[1].glyph, wide);
+
+        framebuffer.draw_background(2, 0, 3, red, 2);
+        assert_eq!(framebuffer.get_cells()[1].glyph, ' ');
+    }
+}
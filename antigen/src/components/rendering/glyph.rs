@@ -0,0 +1,114 @@
+use std::fmt::{Debug, Display};
+
+use unicode_width::UnicodeWidthStr;
+
+/// Maximum encoded length of a glyph, longer grapheme clusters are truncated at a char boundary
+const GLYPH_CAPACITY: usize = 15;
+
+/// A single grapheme cluster, stored inline so that cells remain Copy
+///
+/// An empty glyph is a continuation, marking the cell covered by the right half of a wide glyph.
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Glyph {
+    len: u8,
+    bytes: [u8; GLYPH_CAPACITY],
+}
+
+impl Glyph {
+    pub const SPACE: Glyph = Glyph {
+        len: 1,
+        bytes: [b' ', 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    };
+
+    pub const CONTINUATION: Glyph = Glyph {
+        len: 0,
+        bytes: [0; GLYPH_CAPACITY],
+    };
+
+    pub fn new(grapheme: &str) -> Self {
+        let mut len = grapheme.len().min(GLYPH_CAPACITY);
+        while !grapheme.is_char_boundary(len) {
+            len -= 1;
+        }
+
+        let mut bytes = [0; GLYPH_CAPACITY];
+        bytes[..len].copy_from_slice(&grapheme.as_bytes()[..len]);
+
+        Glyph {
+            len: len as u8,
+            bytes,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.bytes[..self.len as usize])
+            .expect("Glyph bytes are copied from a str at a char boundary")
+    }
+
+    pub fn is_continuation(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of terminal cells this glyph covers
+    ///
+    /// Zero-width glyphs, ex. lone combining marks, don't occupy a cell of their own.
+    /// Control characters are also zero-width, as they would move the cursor when written.
+    pub fn width(&self) -> usize {
+        if self.as_str().chars().any(char::is_control) {
+            return 0;
+        }
+
+        self.as_str().width().min(2)
+    }
+}
+
+impl Default for Glyph {
+    fn default() -> Self {
+        Glyph::SPACE
+    }
+}
+
+impl From<char> for Glyph {
+    fn from(char: char) -> Self {
+        Glyph::new(char.encode_utf8(&mut [0; 4]))
+    }
+}
+
+impl PartialEq<char> for Glyph {
+    fn eq(&self, other: &char) -> bool {
+        let mut chars = self.as_str().chars();
+        chars.next() == Some(*other) && chars.next().is_none()
+    }
+}
+
+impl Debug for Glyph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for Glyph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Glyph;
+
+    #[test]
+    fn glyph() {
+        assert_eq!(Glyph::from(' '), Glyph::SPACE);
+        assert_eq!(Glyph::from('a'), 'a');
+        assert_eq!(Glyph::new("e\u{301}").as_str(), "e\u{301}");
+        assert_eq!(Glyph::new("e\u{301}").width(), 1);
+        assert_eq!(Glyph::new("日").width(), 2);
+        assert_eq!(Glyph::new("\u{301}").width(), 0);
+        assert_eq!(Glyph::new("\r").width(), 0);
+
+        // Oversized clusters are cut at a char boundary
+        let flags = "🏳️‍🌈🏳️‍🌈";
+        assert_eq!(Glyph::new(flags).as_str(), &flags[..14]);
+    }
+}
@@ -1,5 +1,17 @@
+mod cell;
+mod cell_framebuffer;
 mod cpu_shader;
-mod software_framebuffer;
+mod glyph;
+mod rich_text;
+mod text_color;
+mod text_layout;
+mod text_style;
 
+pub use cell::*;
+pub use cell_framebuffer::*;
 pub use cpu_shader::*;
-pub use software_framebuffer::*;
+pub use glyph::*;
+pub use rich_text::*;
+pub use text_color::*;
+pub use text_layout::*;
+pub use text_style::*;
@@ -0,0 +1,297 @@
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    components::{CellAttributes, Glyph},
+    primitive_types::{ColorRGB, ColorRGBF},
+};
+
+/// A run of text sharing a single color and set of attributes
+///
+/// A color of None falls back to the entity's TextColor, if any.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub text: String,
+    pub color: Option<ColorRGBF>,
+    pub attributes: CellAttributes,
+}
+
+impl TextSpan {
+    pub fn new(text: &str) -> Self {
+        TextSpan {
+            text: text.into(),
+            ..TextSpan::default()
+        }
+    }
+}
+
+/// Styled text, rendered by StringRenderer in place of a String component
+///
+/// Usually built from inline markup via RichText::parse.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RichText(pub Vec<TextSpan>);
+
+impl Deref for RichText {
+    type Target = Vec<TextSpan>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for RichText {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<&str> for RichText {
+    fn from(text: &str) -> Self {
+        RichText(vec![TextSpan::new(text)])
+    }
+}
+
+impl RichText {
+    /// Parse inline markup into styled spans
+    ///
+    /// Tags contain space-separated styles, ex. `[bold red]error[/]`, and apply until the matching `[/]`.
+    /// Styles are `bold`, `underline`, `reverse`, `dim`, color names such as `red` or `gray`, and `#rrggbb` colors.
+    /// `[[` is a literal `[`, and brackets that don't form a valid tag are kept as text.
+    pub fn parse(markup: &str) -> Self {
+        let mut spans: Vec<TextSpan> = Vec::new();
+        let mut styles: Vec<(Option<ColorRGBF>, CellAttributes)> = Vec::new();
+
+        let push_text = |spans: &mut Vec<TextSpan>,
+                         styles: &[(Option<ColorRGBF>, CellAttributes)],
+                         text: &str| {
+            let (color, attributes) = styles.last().copied().unwrap_or_default();
+            match spans.last_mut() {
+                Some(span) if span.color == color && span.attributes == attributes => {
+                    span.text += text
+                }
+                _ => spans.push(TextSpan {
+                    text: text.into(),
+                    color,
+                    attributes,
+                }),
+            }
+        };
+
+        let mut rest = markup;
+        while let Some(start) = rest.find('[') {
+            push_text(&mut spans, &styles, &rest[..start]);
+            rest = &rest[start..];
+
+            if let Some(escaped) = rest.strip_prefix("[[") {
+                push_text(&mut spans, &styles, "[");
+                rest = escaped;
+                continue;
+            }
+
+            let end = match rest.find(']') {
+                Some(end) => end,
+                None => break,
+            };
+
+            let tag = &rest[1..end];
+            if tag == "/" {
+                styles.pop();
+            } else if let Some(style) = parse_tag(tag, styles.last().copied().unwrap_or_default()) {
+                styles.push(style);
+            } else {
+                // Not a tag, so keep the bracket as text
+                push_text(&mut spans, &styles, "[");
+                rest = &rest[1..];
+                continue;
+            }
+
+            rest = &rest[end + 1..];
+        }
+        push_text(&mut spans, &styles, rest);
+
+        spans.retain(|span| !span.text.is_empty());
+        RichText(spans)
+    }
+
+    /// Escape text so that it parses back to itself when embedded in markup
+    pub fn escape(text: &str) -> String {
+        text.replace('[', "[[")
+    }
+
+    /// The text of all spans, without styling
+    pub fn to_plain_string(&self) -> String {
+        self.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    /// Split into one RichText per line, preserving span styles across the split
+    pub fn lines(&self) -> Vec<RichText> {
+        let mut lines = vec![RichText::default()];
+
+        for span in self.iter() {
+            for (i, text) in span.text.split('\n').enumerate() {
+                if i > 0 {
+                    lines.push(RichText::default());
+                }
+
+                if !text.is_empty() {
+                    lines.last_mut().unwrap().push(TextSpan {
+                        text: text.into(),
+                        ..span.clone()
+                    });
+                }
+            }
+        }
+
+        lines
+    }
+
+    /// Shorten to at most the given display width, in cells
+    ///
+    /// Text is cut between grapheme clusters, so a wide glyph that would straddle the limit is dropped whole.
+    pub fn truncate(&mut self, width: usize) {
+        let mut remaining = width;
+        for span in self.iter_mut() {
+            let mut end = span.text.len();
+            for (idx, grapheme) in span.text.grapheme_indices(true) {
+                let grapheme_width = Glyph::new(grapheme).width();
+                if grapheme_width > remaining {
+                    end = idx;
+                    remaining = 0;
+                    break;
+                }
+                remaining -= grapheme_width;
+            }
+            span.text.truncate(end);
+        }
+
+        self.retain(|span| !span.text.is_empty());
+    }
+}
+
+/// Resolve a tag's styles on top of the enclosing style, or None if any are unrecognized
+fn parse_tag(
+    tag: &str,
+    (mut color, mut attributes): (Option<ColorRGBF>, CellAttributes),
+) -> Option<(Option<ColorRGBF>, CellAttributes)> {
+    let mut styles = tag.split_whitespace().peekable();
+    styles.peek()?;
+
+    for style in styles {
+        match style {
+            "bold" => attributes.bold = true,
+            "underline" => attributes.underline = true,
+            "reverse" => attributes.reverse = true,
+            "dim" => attributes.dim = true,
+            _ => color = Some(parse_color(style)?),
+        }
+    }
+
+    Some((color, attributes))
+}
+
+fn parse_color(color: &str) -> Option<ColorRGBF> {
+    let color = match color {
+        "black" => ColorRGB(0.0, 0.0, 0.0),
+        "red" => ColorRGB(1.0, 0.0, 0.0),
+        "green" => ColorRGB(0.0, 1.0, 0.0),
+        "yellow" => ColorRGB(1.0, 1.0, 0.0),
+        "blue" => ColorRGB(0.0, 0.0, 1.0),
+        "magenta" => ColorRGB(1.0, 0.0, 1.0),
+        "cyan" => ColorRGB(0.0, 1.0, 1.0),
+        "white" => ColorRGB(1.0, 1.0, 1.0),
+        "gray" | "grey" => ColorRGB(0.5, 0.5, 0.5),
+        _ => {
+            let hex = color.strip_prefix('#')?;
+            if hex.len() != 6 {
+                return None;
+            }
+
+            let channel = |i: usize| {
+                u8::from_str_radix(hex.get(i..i + 2)?, 16)
+                    .ok()
+                    .map(|channel| channel as f32 / 255.0)
+            };
+            ColorRGB(channel(0)?, channel(2)?, channel(4)?)
+        }
+    };
+
+    Some(color)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{components::CellAttributes, primitive_types::ColorRGB};
+
+    use super::{RichText, TextSpan};
+
+    #[test]
+    fn rich_text_markup() {
+        let bold = CellAttributes {
+            bold: true,
+            ..CellAttributes::default()
+        };
+
+        let rich_text = RichText::parse("[bold]a [red]b[/] c[/] [[d] [nope] [#00ff00]e");
+        assert_eq!(
+            *rich_text,
+            vec![
+                TextSpan {
+                    text: "a ".into(),
+                    color: None,
+                    attributes: bold,
+                },
+                TextSpan {
+                    text: "b".into(),
+                    color: Some(ColorRGB(1.0, 0.0, 0.0)),
+                    attributes: bold,
+                },
+                TextSpan {
+                    text: " c".into(),
+                    color: None,
+                    attributes: bold,
+                },
+                TextSpan::new(" [d] [nope] "),
+                TextSpan {
+                    text: "e".into(),
+                    color: Some(ColorRGB(0.0, 1.0, 0.0)),
+                    attributes: CellAttributes::default(),
+                },
+            ]
+        );
+        assert_eq!(rich_text.to_plain_string(), "a b c [d] [nope] e");
+
+        // Escaped text round-trips
+        let text = "Vec [1, 2] [/]";
+        assert_eq!(
+            RichText::parse(&RichText::escape(text)).to_plain_string(),
+            text
+        );
+
+        // Unterminated tags are kept as text
+        assert_eq!(RichText::parse("a [red"), RichText::from("a [red"));
+    }
+
+    #[test]
+    fn rich_text_lines() {
+        let mut lines = RichText::parse("[red]ab\ncd[/]ef").lines();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].to_plain_string(), "ab");
+        assert_eq!(lines[1].len(), 2);
+
+        lines[1].truncate(3);
+        assert_eq!(lines[1].to_plain_string(), "cde");
+        assert_eq!(lines[1][1].text, "e");
+
+        lines[1].truncate(1);
+        assert_eq!(lines[1].to_plain_string(), "c");
+        assert_eq!(lines[1].len(), 1);
+        assert_eq!(lines[1][0].color, Some(ColorRGB(1.0, 0.0, 0.0)));
+
+        // Truncation counts display width and keeps grapheme clusters whole
+        let mut wide = RichText::from("e\u{301}日本");
+        wide.truncate(4);
+        assert_eq!(wide.to_plain_string(), "e\u{301}日");
+    }
+}
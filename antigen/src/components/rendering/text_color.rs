@@ -0,0 +1,25 @@
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Serialize};
+
+use crate::primitive_types::ColorRGBF;
+
+/// Foreground color for an entity's text
+///
+/// Text without one is colored by the backend, ex. via its TextColorMode.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextColor(pub ColorRGBF);
+
+impl Deref for TextColor {
+    type Target = ColorRGBF;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for TextColor {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
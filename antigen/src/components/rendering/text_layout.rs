@@ -0,0 +1,378 @@
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::{
+    components::{Glyph, RichText, TextSpan},
+    primitive_types::Vector2I,
+};
+
+/// Distance between tab stops, in cells
+pub const TAB_WIDTH: i64 = 4;
+
+/// How lines wider than a control are fit within it
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TextOverflow {
+    /// Cut lines at the control's edge
+    Clip,
+    /// Break lines between words, or mid-word if a word is wider than the control
+    WordWrap,
+    /// Break lines at the last glyph that fits
+    CharWrap,
+    /// Cut lines short and end them with an ellipsis
+    Ellipsis,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum HorizontalAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum VerticalAlignment {
+    Top,
+    Center,
+    Bottom,
+}
+
+/// Fits an entity's text within its Size, with the given overflow handling and alignment
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct TextLayout {
+    overflow: TextOverflow,
+    horizontal_alignment: HorizontalAlignment,
+    vertical_alignment: VerticalAlignment,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        TextLayout {
+            overflow: TextOverflow::Clip,
+            horizontal_alignment: HorizontalAlignment::Left,
+            vertical_alignment: VerticalAlignment::Top,
+        }
+    }
+}
+
+/// A grapheme cluster along with its display width and the span it came from
+#[derive(Copy, Clone)]
+struct LayoutGlyph<'a> {
+    grapheme: &'a str,
+    width: usize,
+    span: &'a TextSpan,
+}
+
+impl<'a> LayoutGlyph<'a> {
+    fn is_whitespace(&self) -> bool {
+        self.grapheme.chars().all(char::is_whitespace)
+    }
+}
+
+impl TextLayout {
+    pub fn new(
+        overflow: TextOverflow,
+        horizontal_alignment: HorizontalAlignment,
+        vertical_alignment: VerticalAlignment,
+    ) -> Self {
+        TextLayout {
+            overflow,
+            horizontal_alignment,
+            vertical_alignment,
+        }
+    }
+
+    pub fn get_overflow(&self) -> TextOverflow {
+        self.overflow
+    }
+
+    pub fn set_overflow(&mut self, overflow: TextOverflow) {
+        self.overflow = overflow
+    }
+
+    pub fn get_horizontal_alignment(&self) -> HorizontalAlignment {
+        self.horizontal_alignment
+    }
+
+    pub fn set_horizontal_alignment(&mut self, horizontal_alignment: HorizontalAlignment) {
+        self.horizontal_alignment = horizontal_alignment
+    }
+
+    pub fn get_vertical_alignment(&self) -> VerticalAlignment {
+        self.vertical_alignment
+    }
+
+    pub fn set_vertical_alignment(&mut self, vertical_alignment: VerticalAlignment) {
+        self.vertical_alignment = vertical_alignment
+    }
+
+    /// Fit text within the given size, returning each visible line and its offset from the top-left corner
+    ///
+    /// Tabs are expanded to spaces so lines can be measured, and lines beyond the size's height are dropped.
+    pub fn layout(&self, text: &RichText, size: Vector2I) -> Vec<(Vector2I, RichText)> {
+        let Vector2I(width, height) = size;
+        if width <= 0 || height <= 0 {
+            return vec![];
+        }
+
+        let lines = text.lines();
+        let mut rows: Vec<Vec<LayoutGlyph>> = Vec::new();
+        for line in &lines {
+            let glyphs = layout_glyphs(line);
+            let width = width as usize;
+
+            match self.overflow {
+                TextOverflow::Clip => rows.push(fit(glyphs, width)),
+                TextOverflow::WordWrap => rows.extend(wrap(glyphs, width, true)),
+                TextOverflow::CharWrap => rows.extend(wrap(glyphs, width, false)),
+                TextOverflow::Ellipsis => rows.push(ellipsize(glyphs, width)),
+            }
+        }
+        rows.truncate(height as usize);
+
+        let top = match self.vertical_alignment {
+            VerticalAlignment::Top => 0,
+            VerticalAlignment::Center => (height - rows.len() as i64) / 2,
+            VerticalAlignment::Bottom => height - rows.len() as i64,
+        };
+
+        rows.into_iter()
+            .enumerate()
+            .map(|(y, row)| {
+                let row_width: usize = row.iter().map(|glyph| glyph.width).sum();
+                let left = match self.horizontal_alignment {
+                    HorizontalAlignment::Left => 0,
+                    HorizontalAlignment::Center => (width - row_width as i64) / 2,
+                    HorizontalAlignment::Right => width - row_width as i64,
+                };
+
+                (Vector2I(left.max(0), top + y as i64), to_rich_text(&row))
+            })
+            .collect()
+    }
+}
+
+/// Split a single line into measured grapheme clusters, expanding tabs to the next tab stop
+fn layout_glyphs(line: &RichText) -> Vec<LayoutGlyph<'_>> {
+    let mut glyphs = Vec::new();
+    let mut x = 0;
+
+    for span in line.iter() {
+        for grapheme in span.text.graphemes(true) {
+            if grapheme == "\t" {
+                let tab_width = TAB_WIDTH as usize - (x % TAB_WIDTH as usize);
+                glyphs.extend((0..tab_width).map(|_| LayoutGlyph {
+                    grapheme: " ",
+                    width: 1,
+                    span,
+                }));
+                x += tab_width;
+                continue;
+            }
+
+            let width = Glyph::new(grapheme).width();
+            glyphs.push(LayoutGlyph {
+                grapheme,
+                width,
+                span,
+            });
+            x += width;
+        }
+    }
+
+    glyphs
+}
+
+/// Keep as many glyphs as fit within the given width
+fn fit(glyphs: Vec<LayoutGlyph>, width: usize) -> Vec<LayoutGlyph> {
+    let mut remaining = width;
+    glyphs
+        .into_iter()
+        .take_while(|glyph| match remaining.checked_sub(glyph.width) {
+            Some(new_remaining) => {
+                remaining = new_remaining;
+                true
+            }
+            None => false,
+        })
+        .collect()
+}
+
+/// Cut glyphs wider than the given width short, replacing the last visible cell with an ellipsis
+fn ellipsize(glyphs: Vec<LayoutGlyph>, width: usize) -> Vec<LayoutGlyph> {
+    let glyphs_width: usize = glyphs.iter().map(|glyph| glyph.width).sum();
+    if glyphs_width <= width {
+        return glyphs;
+    }
+
+    // The ellipsis takes the style of the text it follows
+    let span = glyphs[0].span;
+    let mut row = fit(glyphs, width - 1);
+    row.push(LayoutGlyph {
+        grapheme: "…",
+        width: 1,
+        span: row.last().map_or(span, |glyph| glyph.span),
+    });
+
+    row
+}
+
+/// Break glyphs into rows no wider than the given width, between words if `words` is set
+///
+/// Whitespace at word-wrapped breaks is dropped, so that rows align cleanly.
+fn wrap(glyphs: Vec<LayoutGlyph>, width: usize, words: bool) -> Vec<Vec<LayoutGlyph>> {
+    let mut rows = Vec::new();
+    let mut row: Vec<LayoutGlyph> = Vec::new();
+    let mut row_width = 0;
+
+    for glyph in glyphs {
+        if row_width + glyph.width > width && !row.is_empty() {
+            let mut next_row = Vec::new();
+
+            if words {
+                // Carry a partial word over to the next row, unless it's the only word in this one
+                if !glyph.is_whitespace() {
+                    if let Some(idx) = row.iter().rposition(LayoutGlyph::is_whitespace) {
+                        next_row = row.split_off(idx + 1);
+                    }
+                }
+
+                while matches!(row.last(), Some(glyph) if glyph.is_whitespace()) {
+                    row.pop();
+                }
+            }
+
+            rows.push(row);
+            row = next_row;
+            row_width = row.iter().map(|glyph| glyph.width).sum();
+
+            if words && glyph.is_whitespace() {
+                continue;
+            }
+        }
+
+        row_width += glyph.width;
+        row.push(glyph);
+    }
+
+    rows.push(row);
+    rows
+}
+
+/// Rejoin glyphs into spans, merging those that came from the same span
+fn to_rich_text(row: &[LayoutGlyph]) -> RichText {
+    let mut spans: Vec<TextSpan> = Vec::new();
+
+    for glyph in row {
+        match spans.last_mut() {
+            Some(last)
+                if last.color == glyph.span.color && last.attributes == glyph.span.attributes =>
+            {
+                last.text += glyph.grapheme
+            }
+            _ => spans.push(TextSpan {
+                text: glyph.grapheme.to_string(),
+                ..glyph.span.clone()
+            }),
+        }
+    }
+
+    RichText(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{components::RichText, primitive_types::Vector2I};
+
+    use super::{HorizontalAlignment, TextLayout, TextOverflow, VerticalAlignment};
+
+    fn layout_lines(layout: TextLayout, text: &str, size: Vector2I) -> Vec<(Vector2I, String)> {
+        layout
+            .layout(&RichText::from(text), size)
+            .into_iter()
+            .map(|(offset, line)| (offset, line.to_plain_string()))
+            .collect()
+    }
+
+    #[test]
+    fn text_layout() {
+        let text = "The quick brown fox";
+        let size = Vector2I(8, 3);
+        let layout =
+            |overflow| TextLayout::new(overflow, HorizontalAlignment::Left, VerticalAlignment::Top);
+
+        assert_eq!(
+            layout_lines(layout(TextOverflow::WordWrap), text, size),
+            vec![
+                (Vector2I(0, 0), "The".to_string()),
+                (Vector2I(0, 1), "quick".to_string()),
+                (Vector2I(0, 2), "brown".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            layout_lines(layout(TextOverflow::CharWrap), text, size),
+            vec![
+                (Vector2I(0, 0), "The quic".to_string()),
+                (Vector2I(0, 1), "k brown ".to_string()),
+                (Vector2I(0, 2), "fox".to_string()),
+            ]
+        );
+
+        assert_eq!(
+            layout_lines(
+                layout(TextOverflow::Ellipsis),
+                "日本語のテキスト\nfox",
+                size
+            ),
+            vec![
+                (Vector2I(0, 0), "日本語…".to_string()),
+                (Vector2I(0, 1), "fox".to_string()),
+            ]
+        );
+
+        // Words wider than the control are broken mid-word
+        assert_eq!(
+            layout_lines(
+                layout(TextOverflow::WordWrap),
+                "a abcdefghij",
+                Vector2I(4, 4)
+            ),
+            vec![
+                (Vector2I(0, 0), "a".to_string()),
+                (Vector2I(0, 1), "abcd".to_string()),
+                (Vector2I(0, 2), "efgh".to_string()),
+                (Vector2I(0, 3), "ij".to_string()),
+            ]
+        );
+
+        let centered = TextLayout::new(
+            TextOverflow::Clip,
+            HorizontalAlignment::Center,
+            VerticalAlignment::Center,
+        );
+        assert_eq!(
+            layout_lines(centered, "Title\n=====", Vector2I(10, 4)),
+            vec![
+                (Vector2I(2, 1), "Title".to_string()),
+                (Vector2I(2, 2), "=====".to_string()),
+            ]
+        );
+
+        let right = TextLayout::new(
+            TextOverflow::Clip,
+            HorizontalAlignment::Right,
+            VerticalAlignment::Bottom,
+        );
+        assert_eq!(
+            layout_lines(right, "ab\tc", Vector2I(6, 2)),
+            vec![(Vector2I(1, 1), "ab  c".to_string())]
+        );
+
+        // Styles are kept across wrapped lines
+        let rich_text = RichText::parse("[bold]ab cd[/] ef");
+        let lines = layout(TextOverflow::WordWrap).layout(&rich_text, Vector2I(4, 3));
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].1[0].attributes.bold);
+        assert!(!lines[2].1[0].attributes.bold);
+    }
+}
@@ -0,0 +1,23 @@
+use std::ops::{Deref, DerefMut};
+
+use serde::{Deserialize, Serialize};
+
+use crate::components::CellAttributes;
+
+/// Text attributes applied to an entity's text, ex. bold or dim
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TextStyle(pub CellAttributes);
+
+impl Deref for TextStyle {
+    type Target = CellAttributes;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for TextStyle {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -1,8 +1,9 @@
 use std::ops::{Deref, DerefMut};
 
-use crate::entity_component_system::EntityID;
+use crate::entity_component_system::{EcsError, EntityID, EntityIDMap, MapEntities};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChildEntitiesData(Vec<EntityID>);
 
 impl Deref for ChildEntitiesData {
@@ -24,3 +25,12 @@ impl Default for ChildEntitiesData {
         ChildEntitiesData(Vec::new())
     }
 }
+
+impl MapEntities for ChildEntitiesData {
+    fn map_entities(&mut self, entity_id_map: &EntityIDMap) -> Result<(), EcsError> {
+        for entity_id in self.0.iter_mut() {
+            *entity_id = entity_id_map.map(*entity_id)?;
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
 use std::ops::{Deref, DerefMut};
 
-use crate::entity_component_system::EntityID;
+use crate::entity_component_system::{EcsError, EntityID, EntityIDMap, MapEntities};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Copy, Clone)]
+#[derive(Debug, Default, Copy, Clone, Serialize, Deserialize)]
 pub struct ParentEntity(pub EntityID);
 
 impl Deref for ParentEntity {
@@ -18,3 +19,10 @@ impl DerefMut for ParentEntity {
         &mut self.0
     }
 }
+
+impl MapEntities for ParentEntity {
+    fn map_entities(&mut self, entity_id_map: &EntityIDMap) -> Result<(), EcsError> {
+        self.0 = entity_id_map.map(self.0)?;
+        Ok(())
+    }
+}
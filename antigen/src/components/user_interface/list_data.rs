@@ -1,10 +1,15 @@
-use crate::entity_component_system::EntityID;
+use crate::entity_component_system::{EcsError, EntityID, EntityIDMap, MapEntities};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[derive(
+    Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Serialize, Deserialize,
+)]
 pub struct ListData {
     string_list_entity: Option<EntityID>,
     selected_index: Option<usize>,
     scroll_offset: usize,
+    #[serde(default)]
+    rich_text: bool,
 }
 
 impl ListData {
@@ -13,6 +18,7 @@ impl ListData {
             string_list_entity,
             selected_index: None,
             scroll_offset: 0,
+            rich_text: false,
         }
     }
 
@@ -28,6 +34,15 @@ impl ListData {
         self.scroll_offset
     }
 
+    /// Whether list strings are parsed as RichText markup
+    pub fn get_rich_text(&self) -> bool {
+        self.rich_text
+    }
+
+    pub fn set_rich_text(&mut self, rich_text: bool) {
+        self.rich_text = rich_text
+    }
+
     pub fn set_selected_index(&mut self, selected_index: Option<usize>) {
         self.selected_index = selected_index
     }
@@ -37,3 +52,12 @@ impl ListData {
         self.scroll_offset = new_offset as usize;
     }
 }
+
+impl MapEntities for ListData {
+    fn map_entities(&mut self, entity_id_map: &EntityIDMap) -> Result<(), EcsError> {
+        if let Some(string_list_entity) = self.string_list_entity {
+            self.string_list_entity = Some(entity_id_map.map(string_list_entity)?);
+        }
+        Ok(())
+    }
+}
@@ -169,3 +169,111 @@ pub enum Key {
 pub trait IntoKey {
     fn into_key(self) -> Key;
 }
+
+/// Maps printable ASCII and the control characters terminals send for common keys
+impl IntoKey for char {
+    fn into_key(self) -> Key {
+        match self {
+            ' ' => Key::Space,
+            '!' => Key::ExclamationMark,
+            '"' => Key::DoubleQuote,
+            '#' => Key::Number,
+            '$' => Key::Dollar,
+            '%' => Key::Percent,
+            '&' => Key::Ampersand,
+            '\'' => Key::SingleQuote,
+            '(' => Key::OpenBracket,
+            ')' => Key::CloseBracket,
+            '*' => Key::Asterisk,
+            '+' => Key::Plus,
+            ',' => Key::Comma,
+            '-' => Key::Hyphen,
+            '.' => Key::Period,
+            '/' => Key::Slash,
+            '0' => Key::N0,
+            '1' => Key::N1,
+            '2' => Key::N2,
+            '3' => Key::N3,
+            '4' => Key::N4,
+            '5' => Key::N5,
+            '6' => Key::N6,
+            '7' => Key::N7,
+            '8' => Key::N8,
+            '9' => Key::N9,
+            ':' => Key::Colon,
+            ';' => Key::Semicolon,
+            '<' => Key::LessThan,
+            '=' => Key::Equals,
+            '>' => Key::GreaterThan,
+            '?' => Key::QuestionMark,
+            '@' => Key::At,
+            'A' => Key::A,
+            'B' => Key::B,
+            'C' => Key::C,
+            'D' => Key::D,
+            'E' => Key::E,
+            'F' => Key::F,
+            'G' => Key::G,
+            'H' => Key::H,
+            'I' => Key::I,
+            'J' => Key::J,
+            'K' => Key::K,
+            'L' => Key::L,
+            'M' => Key::M,
+            'N' => Key::N,
+            'O' => Key::O,
+            'P' => Key::P,
+            'Q' => Key::Q,
+            'R' => Key::R,
+            'S' => Key::S,
+            'T' => Key::T,
+            'U' => Key::U,
+            'V' => Key::V,
+            'W' => Key::W,
+            'X' => Key::X,
+            'Y' => Key::Y,
+            'Z' => Key::Z,
+            '[' => Key::OpeningBracket,
+            '\\' => Key::Backslash,
+            ']' => Key::ClosingBracket,
+            '^' => Key::Caret,
+            '_' => Key::Underscore,
+            '`' => Key::Grave,
+            'a' => Key::A,
+            'b' => Key::B,
+            'c' => Key::C,
+            'd' => Key::D,
+            'e' => Key::E,
+            'f' => Key::F,
+            'g' => Key::G,
+            'h' => Key::H,
+            'i' => Key::I,
+            'j' => Key::J,
+            'k' => Key::K,
+            'l' => Key::L,
+            'm' => Key::M,
+            'n' => Key::N,
+            'o' => Key::O,
+            'p' => Key::P,
+            'q' => Key::Q,
+            'r' => Key::R,
+            's' => Key::S,
+            't' => Key::T,
+            'u' => Key::U,
+            'v' => Key::V,
+            'w' => Key::W,
+            'x' => Key::X,
+            'y' => Key::Y,
+            'z' => Key::Z,
+            '{' => Key::OpeningBrace,
+            '|' => Key::VerticalBar,
+            '}' => Key::ClosingBrace,
+            '~' => Key::Tilde,
+            '\u{8}' | '\u{7f}' => Key::Backspace,
+            '\u{1b}' => Key::Escape,
+            '\t' => Key::Tab,
+            '\n' | '\r' => Key::Enter,
+            _ => Key::Unknown,
+        }
+    }
+}
@@ -1,14 +1,16 @@
 use std::{
     collections::HashMap,
     ops::{Add, AddAssign},
+    rc::Rc,
 };
 
 use crate::{
-    core::uid::UID, entity_component_system::ComponentID, entity_component_system::ComponentTrait,
+    components::ParentEntity, core::uid::UID, entity_component_system::ComponentID,
+    entity_component_system::ComponentTrait, entity_component_system::EcsError,
     entity_component_system::EntityID,
 };
 
-use super::{ComponentStorage, EntityComponentDirectory, SystemInterface};
+use super::{ComponentBundle, ComponentStorage, EntityComponentDirectory, SystemInterface};
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct AssemblageID(pub UID);
@@ -35,7 +37,10 @@ where
     CD: EntityComponentDirectory + 'static,
 {
     assemblage: Assemblage<CS, CD>,
+    inherited_component_data: HashMap<ComponentID, ComponentConstructor<CS, CD>>,
     component_data: HashMap<ComponentID, ComponentConstructor<CS, CD>>,
+    inherited_children: Vec<ChildAssemblage<CS, CD>>,
+    children: Vec<ChildAssemblage<CS, CD>>,
 }
 
 impl<CS, CD> AssemblageBuilder<CS, CD>
@@ -46,18 +51,40 @@ where
     pub fn new(assemblage: Assemblage<CS, CD>) -> Self {
         AssemblageBuilder {
             assemblage,
+            inherited_component_data: HashMap::new(),
             component_data: HashMap::new(),
+            inherited_children: Vec::new(),
+            children: Vec::new(),
         }
     }
 
-    pub fn add_component<C>(mut self, component_data: C) -> Result<Self, String>
+    /// Inherit the components and children of a parent assemblage,
+    /// replacing its values and same-named children with any added to this builder
+    pub fn extend(mut self, parent: &Assemblage<CS, CD>) -> Self {
+        for (component_id, component_constructor) in &parent.component_constructors {
+            self.inherited_component_data
+                .insert(*component_id, component_constructor.clone());
+        }
+        self.inherited_children
+            .extend(parent.children.iter().cloned());
+        self
+    }
+
+    /// Add a named child entity, which is assembled from the given assemblage and parented to this one
+    pub fn add_child(mut self, name: &str, child: Assemblage<CS, CD>) -> Self {
+        self.children.retain(|(child_name, _)| child_name != name);
+        self.children.push((name.into(), Rc::new(child)));
+        self
+    }
+
+    pub fn add_component<C>(mut self, component_data: C) -> Result<Self, EcsError>
     where
         C: ComponentTrait + Clone + 'static,
     {
         self.component_data.insert(
             ComponentID::get::<C>(),
-            Box::new(
-                move |db: &mut SystemInterface<CS, CD>, entity_id| -> Result<(), String> {
+            Rc::new(
+                move |db: &mut SystemInterface<CS, CD>, entity_id| -> Result<(), EcsError> {
                     match db.insert_entity_component(entity_id, component_data.clone()) {
                         Ok(_) => Ok(()),
                         Err(err) => Err(err),
@@ -69,15 +96,42 @@ where
     }
 
     pub fn finish(mut self) -> Assemblage<CS, CD> {
-        self.assemblage.component_constructors = self.component_data;
+        let mut component_constructors = self.inherited_component_data;
+        component_constructors.extend(self.component_data);
+        self.assemblage.component_constructors = component_constructors;
+
+        let mut children = self.inherited_children;
+        for (name, child) in self.children {
+            children.retain(|(child_name, _)| *child_name != name);
+            children.push((name, child));
+        }
+        self.assemblage.children = children;
+
         self.assemblage
     }
 }
 
 type ComponentConstructor<CS, CD> =
-    Box<dyn FnMut(&mut SystemInterface<CS, CD>, EntityID) -> Result<(), String>>;
+    Rc<dyn Fn(&mut SystemInterface<CS, CD>, EntityID) -> Result<(), EcsError>>;
+
+type ChildAssemblage<CS, CD> = (String, Rc<Assemblage<CS, CD>>);
+
+/// Entities created from an assemblage and its children
+#[derive(Debug, Clone)]
+pub struct AssembledEntity {
+    pub entity_id: EntityID,
+    /// Child entities keyed by name, with descendants keyed by slash-separated paths such as `Border/Title`
+    pub children: HashMap<String, EntityID>,
+}
 
-/// An object template as defined by a set of components with given default values
+impl AssembledEntity {
+    pub fn get_child(&self, name: &str) -> Option<EntityID> {
+        self.children.get(name).copied()
+    }
+}
+
+/// An object template as defined by a set of components with given default values,
+/// and optionally a tree of named child entities
 pub struct Assemblage<S, D>
 where
     S: ComponentStorage + 'static,
@@ -86,6 +140,7 @@ where
     pub name: String,
     pub description: String,
     component_constructors: HashMap<ComponentID, ComponentConstructor<S, D>>,
+    children: Vec<ChildAssemblage<S, D>>,
 }
 
 impl<CS, CD> Assemblage<CS, CD>
@@ -98,6 +153,7 @@ where
             name: name.into(),
             description: description.into(),
             component_constructors: HashMap::new(),
+            children: Vec::new(),
         }
     }
 
@@ -109,32 +165,263 @@ where
         AssemblageBuilder::new(Assemblage::new(name, description))
     }
 
-    pub fn create_and_assemble_entity<'a>(
-        &mut self,
-        db: &'a mut SystemInterface<CS, CD>,
+    pub fn create_and_assemble_entity(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
         debug_label: Option<&str>,
-    ) -> Result<EntityID, String>
+    ) -> Result<EntityID, EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
+    {
+        self.create_and_assemble_entity_with(db, debug_label, ())
+    }
+
+    /// Create and assemble an entity, replacing template values with those in the given bundle
+    ///
+    /// ex. `assemblage.create_and_assemble_entity_with(db, None, (Position(Vector2I(1, 1)),))`
+    pub fn create_and_assemble_entity_with<B>(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        debug_label: Option<&str>,
+        overrides: B,
+    ) -> Result<EntityID, EcsError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+        B: ComponentBundle,
+    {
+        Ok(self
+            .create_and_assemble_entity_tree(db, debug_label, overrides)?
+            .entity_id)
+    }
+
+    /// Create and assemble an entity along with its children,
+    /// returning the root entity and a mapping of child names to their entities
+    pub fn create_and_assemble_entity_tree<B>(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        debug_label: Option<&str>,
+        overrides: B,
+    ) -> Result<AssembledEntity, EcsError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+        B: ComponentBundle,
     {
         let entity_id = db.create_entity(debug_label)?;
-        self.assemble_entity(db, entity_id)
+        self.assemble_entity_tree(db, entity_id, overrides)
     }
 
-    pub fn assemble_entity<'a>(
-        &mut self,
-        db: &'a mut SystemInterface<CS, CD>,
+    pub fn assemble_entity(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
         entity_id: EntityID,
-    ) -> Result<EntityID, String>
+    ) -> Result<EntityID, EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
     {
-        for component_constructor in &mut self.component_constructors.values_mut() {
-            component_constructor(db, entity_id)?;
+        self.assemble_entity_with(db, entity_id, ())
+    }
+
+    /// Assemble an existing entity, replacing template values with those in the given bundle
+    pub fn assemble_entity_with<B>(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        entity_id: EntityID,
+        overrides: B,
+    ) -> Result<EntityID, EcsError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+        B: ComponentBundle,
+    {
+        Ok(self
+            .assemble_entity_tree(db, entity_id, overrides)?
+            .entity_id)
+    }
+
+    /// Assemble an existing entity and create its children, parenting them via ParentEntity
+    pub fn assemble_entity_tree<B>(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        entity_id: EntityID,
+        overrides: B,
+    ) -> Result<AssembledEntity, EcsError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+        B: ComponentBundle,
+    {
+        let mut override_ids = Vec::new();
+        B::component_ids(&mut override_ids);
+
+        for (component_id, component_constructor) in &self.component_constructors {
+            if !override_ids.contains(component_id) {
+                component_constructor(db, entity_id)?;
+            }
+        }
+
+        overrides.insert(db, entity_id)?;
+
+        let mut children = HashMap::new();
+        for (name, child) in &self.children {
+            let assembled_child = child.create_and_assemble_entity_tree(
+                db,
+                Some(name),
+                (ParentEntity(entity_id),),
+            )?;
+
+            for (descendant_name, descendant_id) in assembled_child.children {
+                children.insert(format!("{}/{}", name, descendant_name), descendant_id);
+            }
+            children.insert(name.clone(), assembled_child.entity_id);
         }
 
-        Ok(entity_id)
+        Ok(AssembledEntity {
+            entity_id,
+            children,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{ChildEntitiesData, Control, Name, ParentEntity, Position, Size},
+        entity_component_system::{
+            Assemblage, ComponentHooks, HeapComponentStorage, SingleThreadedDirectory,
+            SystemInterface, SystemTrait,
+        },
+        primitive_types::Vector2I,
+        systems::ChildEntities,
+    };
+
+    #[test]
+    fn assemblage() {
+        let component_hooks = ComponentHooks::new();
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let rect_assemblage = Assemblage::build("Rect", "")
+            .add_component(Position(Vector2I(1, 1)))
+            .unwrap()
+            .add_component(Size(Vector2I(2, 2)))
+            .unwrap()
+            .finish();
+
+        // Child values replace inherited ones regardless of call order
+        let control_assemblage = Assemblage::build("Control", "")
+            .add_component(Size(Vector2I(3, 3)))
+            .unwrap()
+            .extend(&rect_assemblage)
+            .add_component(Control)
+            .unwrap()
+            .finish();
+
+        let entity_id = control_assemblage
+            .create_and_assemble_entity_with(&mut db, None, (Position(Vector2I(4, 4)),))
+            .unwrap();
+
+        assert_eq!(
+            **db.get_entity_component::<Position>(entity_id).unwrap(),
+            Vector2I(4, 4)
+        );
+        assert_eq!(
+            **db.get_entity_component::<Size>(entity_id).unwrap(),
+            Vector2I(3, 3)
+        );
+        assert!(db.get_entity_component::<Control>(entity_id).is_ok());
+
+        // Parents are unaffected by their children
+        let entity_id = rect_assemblage
+            .create_and_assemble_entity(&mut db, None)
+            .unwrap();
+        assert_eq!(
+            **db.get_entity_component::<Size>(entity_id).unwrap(),
+            Vector2I(2, 2)
+        );
+        assert!(db.get_entity_component::<Control>(entity_id).is_err());
+    }
+
+    #[test]
+    fn assemblage_tree() {
+        let mut component_hooks = ComponentHooks::new();
+        ChildEntities::new().register_component_hooks(&mut component_hooks);
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let title_assemblage = Assemblage::build("Title", "")
+            .add_component(String::from("Title"))
+            .unwrap()
+            .finish();
+
+        let border_assemblage = Assemblage::build("Border", "")
+            .add_component(Control)
+            .unwrap()
+            .add_child("Title", title_assemblage)
+            .finish();
+
+        let panel_assemblage = Assemblage::build("Panel", "")
+            .add_component(Size(Vector2I(8, 8)))
+            .unwrap()
+            .add_child("Border", border_assemblage)
+            .finish();
+
+        // Extending a hierarchical assemblage inherits its children
+        let wide_panel_assemblage = Assemblage::build("Wide Panel", "")
+            .extend(&panel_assemblage)
+            .add_component(Size(Vector2I(16, 8)))
+            .unwrap()
+            .finish();
+
+        let panel = wide_panel_assemblage
+            .create_and_assemble_entity_tree(&mut db, Some("Panel"), ())
+            .unwrap();
+        db.apply_commands().unwrap();
+
+        let border_entity = panel.get_child("Border").unwrap();
+        let title_entity = panel.get_child("Border/Title").unwrap();
+        assert_eq!(panel.children.len(), 2);
+
+        assert_eq!(
+            **db.get_entity_component::<Size>(panel.entity_id).unwrap(),
+            Vector2I(16, 8)
+        );
+        assert_eq!(
+            **db.get_entity_component::<Name>(border_entity).unwrap(),
+            "Border"
+        );
+        assert_eq!(
+            db.get_entity_component::<String>(title_entity).unwrap(),
+            "Title"
+        );
+
+        assert_eq!(
+            **db.get_entity_component::<ParentEntity>(title_entity)
+                .unwrap(),
+            border_entity
+        );
+        assert_eq!(
+            **db.get_entity_component::<ChildEntitiesData>(panel.entity_id)
+                .unwrap(),
+            vec![border_entity]
+        );
+        assert_eq!(
+            **db.get_entity_component::<ChildEntitiesData>(border_entity)
+                .unwrap(),
+            vec![title_entity]
+        );
     }
 }
@@ -0,0 +1,60 @@
+use crate::entity_component_system::{
+    ComponentID, ComponentStorage, ComponentTrait, EcsError, EntityComponentDirectory, EntityID,
+    SystemInterface,
+};
+
+/// A set of component values inserted into an entity together
+///
+/// Implemented for tuples of components, ex. `(Position(Vector2I(1, 1)), ZIndex(2))`
+pub trait ComponentBundle {
+    /// Register the component types contained in this bundle
+    fn component_ids(component_ids: &mut Vec<ComponentID>);
+
+    fn insert<CS, CD>(
+        self,
+        db: &mut SystemInterface<CS, CD>,
+        entity_id: EntityID,
+    ) -> Result<(), EcsError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory;
+}
+
+macro_rules! impl_component_bundle_tuple {
+    ($($component:ident),*) => {
+        impl<$($component),*> ComponentBundle for ($($component,)*)
+        where
+            $($component: ComponentTrait + 'static),*
+        {
+            #[allow(unused_variables)]
+            fn component_ids(component_ids: &mut Vec<ComponentID>) {
+                $(component_ids.push(ComponentID::get::<$component>());)*
+            }
+
+            #[allow(non_snake_case, unused_variables)]
+            fn insert<CS, CD>(
+                self,
+                db: &mut SystemInterface<CS, CD>,
+                entity_id: EntityID,
+            ) -> Result<(), EcsError>
+            where
+                CS: ComponentStorage,
+                CD: EntityComponentDirectory,
+            {
+                let ($($component,)*) = self;
+                $(db.insert_entity_component(entity_id, $component)?;)*
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_component_bundle_tuple!();
+impl_component_bundle_tuple!(A);
+impl_component_bundle_tuple!(A, B);
+impl_component_bundle_tuple!(A, B, C);
+impl_component_bundle_tuple!(A, B, C, D);
+impl_component_bundle_tuple!(A, B, C, D, E);
+impl_component_bundle_tuple!(A, B, C, D, E, F);
+impl_component_bundle_tuple!(A, B, C, D, E, F, G);
+impl_component_bundle_tuple!(A, B, C, D, E, F, G, H);
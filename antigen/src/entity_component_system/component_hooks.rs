@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use super::{
+    Commands, ComponentDataID, ComponentID, ComponentStorage, ComponentTrait, EcsError,
+    EntityComponentDirectory, EntityID,
+};
+
+/// The point in a component's lifecycle at which a hook fires
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ComponentEvent {
+    /// Fired after the component is inserted into an entity that didn't have it
+    Add,
+    /// Fired after the component is inserted into an entity that already had it, replacing the old data
+    Replace,
+    /// Fired before the component is removed from an entity, including when the entity is destroyed
+    Remove,
+}
+
+type ComponentHook<CS, CD> = Box<
+    dyn Fn(&CS, &ComponentDataID, EntityID, &Commands<CS, CD>) -> Result<(), EcsError>
+        + Send
+        + Sync,
+>;
+
+/// Per-component-type callbacks fired as component data is added to, replaced on, or removed from entities
+///
+/// Hooks receive the affected entity, its component data, and a command buffer
+/// for making structural changes once the triggering operation has finished.
+/// Writes made in place through Mut don't fire any hooks, so systems that need to see them should use a Changed filter.
+pub struct ComponentHooks<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    hooks: HashMap<(ComponentID, ComponentEvent), Vec<ComponentHook<CS, CD>>>,
+}
+
+impl<CS, CD> ComponentHooks<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    pub fn new() -> Self {
+        ComponentHooks {
+            hooks: HashMap::new(),
+        }
+    }
+
+    pub fn on_add<T, F>(&mut self, hook: F) -> &mut Self
+    where
+        T: ComponentTrait + 'static,
+        F: Fn(EntityID, &T, &Commands<CS, CD>) + Send + Sync + 'static,
+    {
+        self.insert_hook(ComponentEvent::Add, hook)
+    }
+
+    pub fn on_replace<T, F>(&mut self, hook: F) -> &mut Self
+    where
+        T: ComponentTrait + 'static,
+        F: Fn(EntityID, &T, &Commands<CS, CD>) + Send + Sync + 'static,
+    {
+        self.insert_hook(ComponentEvent::Replace, hook)
+    }
+
+    pub fn on_remove<T, F>(&mut self, hook: F) -> &mut Self
+    where
+        T: ComponentTrait + 'static,
+        F: Fn(EntityID, &T, &Commands<CS, CD>) + Send + Sync + 'static,
+    {
+        self.insert_hook(ComponentEvent::Remove, hook)
+    }
+
+    fn insert_hook<T, F>(&mut self, event: ComponentEvent, hook: F) -> &mut Self
+    where
+        T: ComponentTrait + 'static,
+        F: Fn(EntityID, &T, &Commands<CS, CD>) + Send + Sync + 'static,
+    {
+        // Erase the component type, downcasting the data back when the hook fires
+        let hook: ComponentHook<CS, CD> = Box::new(
+            move |component_storage, component_data_id, entity_id, commands| {
+                let component_data =
+                    component_storage.get_component_data::<T>(component_data_id)?;
+                hook(entity_id, component_data, commands);
+                Ok(())
+            },
+        );
+
+        self.hooks
+            .entry((ComponentID::get::<T>(), event))
+            .or_default()
+            .push(hook);
+
+        self
+    }
+
+    /// Fire every hook registered for the given component type and event
+    pub fn run(
+        &self,
+        event: ComponentEvent,
+        component_id: &ComponentID,
+        component_storage: &CS,
+        component_data_id: &ComponentDataID,
+        entity_id: EntityID,
+        commands: &Commands<CS, CD>,
+    ) -> Result<(), EcsError> {
+        if let Some(hooks) = self.hooks.get(&(*component_id, event)) {
+            for hook in hooks {
+                hook(component_storage, component_data_id, entity_id, commands)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<CS, CD> Default for ComponentHooks<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn default() -> Self {
+        ComponentHooks::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Position, Size},
+        entity_component_system::{
+            ComponentHooks, HeapComponentStorage, SingleThreadedDirectory, SystemInterface,
+        },
+        primitive_types::Vector2I,
+    };
+
+    #[test]
+    fn component_hooks() {
+        let mut component_hooks = ComponentHooks::new();
+        component_hooks
+            .on_add::<Position, _>(|entity_id, position, commands| {
+                commands.insert_entity_component(entity_id, Size(**position));
+            })
+            .on_replace::<Position, _>(|entity_id, position, commands| {
+                let position = **position;
+                commands.push(move |db| {
+                    **db.get_entity_component_mut::<Size>(entity_id)? = position;
+                    Ok(())
+                });
+            })
+            .on_remove::<Position, _>(|entity_id, _, commands| {
+                commands.remove_component_from_entity::<Size>(entity_id);
+            });
+
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let entity_id = db.create_entity(None).unwrap();
+        db.insert_entity_component(entity_id, Position(Vector2I(1, 2)))
+            .unwrap();
+
+        // Hook side effects are deferred until commands are applied
+        assert!(db.get_entity_component::<Size>(entity_id).is_err());
+        db.apply_commands().unwrap();
+        assert_eq!(
+            **db.get_entity_component::<Size>(entity_id).unwrap(),
+            Vector2I(1, 2)
+        );
+
+        db.insert_entity_component(entity_id, Position(Vector2I(3, 4)))
+            .unwrap();
+        db.apply_commands().unwrap();
+        assert_eq!(
+            **db.get_entity_component::<Size>(entity_id).unwrap(),
+            Vector2I(3, 4)
+        );
+
+        db.remove_component_from_entity::<Position>(entity_id)
+            .unwrap();
+        db.apply_commands().unwrap();
+        assert!(db.get_entity_component::<Size>(entity_id).is_err());
+    }
+}
@@ -0,0 +1,615 @@
+use std::{any::Any, cell::UnsafeCell, collections::HashMap};
+
+use crate::entity_component_system::{ComponentID, ComponentTrait, EcsError};
+
+use super::{ComponentDataID, ComponentStorage, ComponentTicks};
+
+/// Type-erased interface over a single component type's column within a table
+trait ComponentColumn: Send {
+    fn get(&self, row: usize) -> Option<&dyn ComponentTrait>;
+    fn get_ticks(&self, row: usize) -> Option<&ComponentTicks>;
+    fn get_ticks_mut(&mut self, row: usize) -> Option<&mut ComponentTicks>;
+
+    /// Create an empty column of the same component type, for a new table
+    fn new_empty(&self) -> Box<dyn ComponentColumn>;
+
+    /// Swap-remove a row, pushing its data onto the end of a column of the same type
+    fn move_row(&mut self, row: usize, target: &mut dyn ComponentColumn);
+
+    /// Swap-remove a row, dropping its data
+    fn remove_row(&mut self, row: usize);
+
+    fn as_any(&self) -> &dyn Any;
+    fn as_mut_any(&mut self) -> &mut dyn Any;
+}
+
+/// Contiguous storage for a single component type
+///
+/// Rows are held in UnsafeCells so that ComponentStorage::get_component_data_ptr can hand out
+/// pointers to distinct rows through a shared reference.
+struct Column<T> {
+    data: Vec<UnsafeCell<T>>,
+    ticks: Vec<UnsafeCell<ComponentTicks>>,
+}
+
+impl<T> Column<T> {
+    fn new() -> Self {
+        Column {
+            data: Vec::new(),
+            ticks: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, component_data: T, component_ticks: ComponentTicks) {
+        self.data.push(UnsafeCell::new(component_data));
+        self.ticks.push(UnsafeCell::new(component_ticks));
+    }
+
+    fn get_ptr(&self, row: usize) -> Option<(*mut T, *mut ComponentTicks)> {
+        Some((self.data.get(row)?.get(), self.ticks[row].get()))
+    }
+}
+
+impl<T> ComponentColumn for Column<T>
+where
+    T: ComponentTrait + 'static,
+{
+    fn get(&self, row: usize) -> Option<&dyn ComponentTrait> {
+        // Safety: Callers of get_component_data_ptr guarantee that nothing else references a row they write to
+        let (component_data, _) = self.get_ptr(row)?;
+        Some(unsafe { &*component_data })
+    }
+
+    fn get_ticks(&self, row: usize) -> Option<&ComponentTicks> {
+        // Safety: See get
+        let (_, component_ticks) = self.get_ptr(row)?;
+        Some(unsafe { &*component_ticks })
+    }
+
+    fn get_ticks_mut(&mut self, row: usize) -> Option<&mut ComponentTicks> {
+        self.ticks.get_mut(row).map(UnsafeCell::get_mut)
+    }
+
+    fn new_empty(&self) -> Box<dyn ComponentColumn> {
+        Box::new(Column::<T>::new())
+    }
+
+    fn move_row(&mut self, row: usize, target: &mut dyn ComponentColumn) {
+        let target = target
+            .as_mut_any()
+            .downcast_mut::<Column<T>>()
+            .expect("Column type mismatch");
+
+        target.push(
+            self.data.swap_remove(row).into_inner(),
+            self.ticks.swap_remove(row).into_inner(),
+        );
+    }
+
+    fn remove_row(&mut self, row: usize) {
+        self.data.swap_remove(row);
+        self.ticks.swap_remove(row);
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// Component data for entities that share the same set of component types, with one column per type
+///
+/// Each row holds a single entity's data, alongside the IDs of that data so moved rows can be located.
+struct Table {
+    component_ids: Vec<ComponentID>,
+    columns: Vec<Box<dyn ComponentColumn>>,
+    component_data_ids: Vec<Vec<ComponentDataID>>,
+}
+
+impl Table {
+    fn new(component_ids: Vec<ComponentID>, columns: Vec<Box<dyn ComponentColumn>>) -> Self {
+        let component_data_ids = component_ids.iter().map(|_| Vec::new()).collect();
+        Table {
+            component_ids,
+            columns,
+            component_data_ids,
+        }
+    }
+
+    fn get_column(&self, component_id: &ComponentID) -> Option<usize> {
+        self.component_ids.binary_search(component_id).ok()
+    }
+
+    fn len(&self) -> usize {
+        self.component_data_ids.first().map_or(0, Vec::len)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct DataLocation {
+    table: usize,
+    column: usize,
+    row: usize,
+}
+
+/// A recyclable handle to component data, which follows the data as it moves between tables
+#[derive(Debug, Copy, Clone)]
+struct DataSlot {
+    // The current ID for this slot, live or not
+    component_data_id: ComponentDataID,
+    location: Option<DataLocation>,
+}
+
+/// Stores component data in per-archetype tables, keeping each entity's data together in a single row
+///
+/// Adding or removing a component moves the entity's row into the table for its new set of components.
+/// ComponentDataIDs address a slot that tracks the data's current table and row,
+/// so lookups are a pair of vector indexes instead of a hash, and IDs remain valid across moves.
+/// Intended to be paired with ArchetypeDirectory.
+pub struct ColumnComponentStorage {
+    tables: Vec<Table>,
+    table_indices: HashMap<Vec<ComponentID>, usize>,
+    slots: Vec<DataSlot>,
+    free_slots: Vec<usize>,
+}
+
+impl ColumnComponentStorage {
+    pub fn new() -> Self {
+        ColumnComponentStorage {
+            tables: Vec::new(),
+            table_indices: HashMap::new(),
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+        }
+    }
+
+    fn get_location(&self, component_data_id: &ComponentDataID) -> Result<DataLocation, EcsError> {
+        self.slots
+            .get(component_data_id.get_index())
+            .filter(|slot| slot.component_data_id == *component_data_id)
+            .and_then(|slot| slot.location)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn get_column(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(&dyn ComponentColumn, usize), EcsError> {
+        let location = self.get_location(component_data_id)?;
+        let column = self.tables[location.table].columns[location.column].as_ref();
+        Ok((column, location.row))
+    }
+
+    fn get_typed_column<T>(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(&Column<T>, usize), EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let (column, row) = self.get_column(component_data_id)?;
+
+        let column =
+            column
+                .as_any()
+                .downcast_ref::<Column<T>>()
+                .ok_or_else(|| EcsError::Downcast {
+                    component_data_id: *component_data_id,
+                    component_id: ComponentID::get::<T>(),
+                })?;
+
+        Ok((column, row))
+    }
+
+    fn allocate_slot(&mut self) -> Result<ComponentDataID, EcsError> {
+        match self.free_slots.pop() {
+            Some(index) => Ok(self.slots[index].component_data_id),
+            None => {
+                let component_data_id =
+                    ComponentDataID::from_index_generation(self.slots.len(), 0)?;
+                self.slots.push(DataSlot {
+                    component_data_id,
+                    location: None,
+                });
+                Ok(component_data_id)
+            }
+        }
+    }
+
+    fn free_slot(&mut self, component_data_id: &ComponentDataID) {
+        let index = component_data_id.get_index();
+        let slot = &mut self.slots[index];
+        slot.component_data_id = component_data_id.next_generation();
+        slot.location = None;
+        self.free_slots.push(index);
+    }
+
+    /// Fetch the table for a set of component types, creating it with the given columns if it doesn't exist
+    fn get_or_create_table(
+        &mut self,
+        component_ids: Vec<ComponentID>,
+        columns: impl FnOnce(&[Table]) -> Vec<Box<dyn ComponentColumn>>,
+    ) -> usize {
+        if let Some(table) = self.table_indices.get(&component_ids) {
+            return *table;
+        }
+
+        let columns = columns(&self.tables);
+        let table = self.tables.len();
+        self.tables.push(Table::new(component_ids.clone(), columns));
+        self.table_indices.insert(component_ids, table);
+        table
+    }
+
+    /// Move a row into the end of another table, dropping any data the target table has no column for
+    ///
+    /// The target table may be part-way through receiving a row, so the row index to move into is given explicitly.
+    fn move_row(&mut self, table: usize, row: usize, target: usize, target_row: usize) {
+        let ColumnComponentStorage {
+            tables,
+            slots,
+            free_slots,
+            ..
+        } = self;
+
+        let (source, target_table) = get_tables_mut(tables, table, target);
+
+        for (column, component_id) in source.component_ids.iter().enumerate() {
+            let component_data_id = source.component_data_ids[column].swap_remove(row);
+            let slot = &mut slots[component_data_id.get_index()];
+
+            match target_table.get_column(component_id) {
+                Some(target_column) => {
+                    source.columns[column]
+                        .move_row(row, target_table.columns[target_column].as_mut());
+                    target_table.component_data_ids[target_column].push(component_data_id);
+                    slot.location = Some(DataLocation {
+                        table: target,
+                        column: target_column,
+                        row: target_row,
+                    });
+                }
+                None => {
+                    source.columns[column].remove_row(row);
+                    slot.component_data_id = component_data_id.next_generation();
+                    slot.location = None;
+                    free_slots.push(component_data_id.get_index());
+                }
+            }
+
+            // Patch the location of the data swapped into the vacated row
+            if let Some(moved_data_id) = source.component_data_ids[column].get(row) {
+                if let Some(location) = &mut slots[moved_data_id.get_index()].location {
+                    location.row = row;
+                }
+            }
+        }
+    }
+
+    /// Swap-remove a row without moving it to another table, dropping its data
+    fn remove_row(&mut self, table: usize, row: usize) {
+        let columns = self.tables[table].columns.len();
+        for column in 0..columns {
+            let table = &mut self.tables[table];
+            let component_data_id = table.component_data_ids[column].swap_remove(row);
+            table.columns[column].remove_row(row);
+
+            let moved_data_id = table.component_data_ids[column].get(row).copied();
+            self.free_slot(&component_data_id);
+
+            if let Some(moved_data_id) = moved_data_id {
+                if let Some(location) = &mut self.slots[moved_data_id.get_index()].location {
+                    location.row = row;
+                }
+            }
+        }
+    }
+}
+
+/// Mutably borrow two distinct tables at once
+fn get_tables_mut(tables: &mut [Table], lhs: usize, rhs: usize) -> (&mut Table, &mut Table) {
+    assert_ne!(lhs, rhs, "Can't borrow a table twice");
+    if lhs < rhs {
+        let (head, tail) = tables.split_at_mut(rhs);
+        (&mut head[lhs], &mut tail[0])
+    } else {
+        let (head, tail) = tables.split_at_mut(lhs);
+        (&mut tail[0], &mut head[rhs])
+    }
+}
+
+impl Default for ColumnComponentStorage {
+    fn default() -> Self {
+        ColumnComponentStorage::new()
+    }
+}
+
+// Safety: Columns only ever hold Send + Sync component data, and table layout is only changed through &mut self.
+// Rows are written through a shared reference only via get_component_data_ptr, whose callers don't alias them.
+unsafe impl Sync for ColumnComponentStorage {}
+
+impl ComponentStorage for ColumnComponentStorage {
+    fn insert_component<T>(&mut self, component_data: T) -> Result<ComponentDataID, EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let component_data_id = self.allocate_slot()?;
+
+        // Data starts out in a table of its own, until it's grouped with the rest of its entity's data
+        let table = self.get_or_create_table(vec![ComponentID::get::<T>()], |_| {
+            vec![Box::new(Column::<T>::new())]
+        });
+
+        let table_data = &mut self.tables[table];
+        let row = table_data.len();
+        table_data.columns[0]
+            .as_mut_any()
+            .downcast_mut::<Column<T>>()
+            .expect("Column type mismatch")
+            .push(component_data, ComponentTicks::default());
+        table_data.component_data_ids[0].push(component_data_id);
+
+        self.slots[component_data_id.get_index()].location = Some(DataLocation {
+            table,
+            column: 0,
+            row,
+        });
+
+        Ok(component_data_id)
+    }
+
+    fn get_component_data<T>(&self, component_data_id: &ComponentDataID) -> Result<&T, EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let (component_data, _) = self.get_component_data_ptr::<T>(component_data_id)?;
+
+        // Safety: See Column::get
+        Ok(unsafe { &*component_data })
+    }
+
+    fn get_component_data_mut<T>(
+        &mut self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&mut T, EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let (component_data, _) = self.get_component_data_ptr::<T>(component_data_id)?;
+
+        // Safety: Data is only reachable through self, which is mutably borrowed for the reference's lifetime
+        Ok(unsafe { &mut *component_data })
+    }
+
+    fn get_component_data_ptr<T>(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(*mut T, *mut ComponentTicks), EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let (column, row) = self.get_typed_column::<T>(component_data_id)?;
+
+        column
+            .get_ptr(row)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn get_component_data_string(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<String, EcsError> {
+        let (column, row) = self.get_column(component_data_id)?;
+
+        let component_data = column
+            .get(row)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))?;
+
+        Ok(format!("{:#?}", component_data))
+    }
+
+    fn get_component_ticks(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&ComponentTicks, EcsError> {
+        let (column, row) = self.get_column(component_data_id)?;
+
+        column
+            .get_ticks(row)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn get_component_ticks_mut(
+        &mut self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&mut ComponentTicks, EcsError> {
+        let location = self.get_location(component_data_id)?;
+
+        self.tables[location.table].columns[location.column]
+            .get_ticks_mut(location.row)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn remove_component_data(
+        &mut self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(), EcsError> {
+        let location = self.get_location(component_data_id)?;
+
+        let mut component_ids = self.tables[location.table].component_ids.clone();
+        if component_ids.len() == 1 {
+            self.remove_row(location.table, location.row);
+            return Ok(());
+        }
+
+        // Move the rest of the row into the table without this component, dropping its data along the way
+        component_ids.remove(location.column);
+
+        let columns = |tables: &[Table]| {
+            tables[location.table]
+                .columns
+                .iter()
+                .enumerate()
+                .filter(|(column, _)| *column != location.column)
+                .map(|(_, column)| column.new_empty())
+                .collect()
+        };
+
+        let target = self.get_or_create_table(component_ids, columns);
+        let target_row = self.tables[target].len();
+        self.move_row(location.table, location.row, target, target_row);
+
+        Ok(())
+    }
+
+    fn group_component_data(
+        &mut self,
+        component_data_id: &ComponentDataID,
+        sibling_data_id: &ComponentDataID,
+    ) -> Result<(), EcsError> {
+        let location = self.get_location(component_data_id)?;
+        let sibling_location = self.get_location(sibling_data_id)?;
+
+        if location.table == sibling_location.table {
+            if location.row == sibling_location.row {
+                return Ok(());
+            }
+
+            // Distinct rows in the same table hold data of the same types
+            return Err(EcsError::AliasedAccess(
+                self.tables[location.table].component_ids[location.column],
+            ));
+        }
+
+        let table = &self.tables[location.table];
+        let sibling_table = &self.tables[sibling_location.table];
+
+        if let Some(component_id) = table
+            .component_ids
+            .iter()
+            .find(|component_id| sibling_table.get_column(component_id).is_some())
+        {
+            return Err(EcsError::AliasedAccess(*component_id));
+        }
+
+        let mut component_ids = table.component_ids.clone();
+        component_ids.extend(sibling_table.component_ids.iter().copied());
+        component_ids.sort();
+
+        let columns = |tables: &[Table]| {
+            let table = &tables[location.table];
+            let sibling_table = &tables[sibling_location.table];
+            let mut columns = table
+                .component_ids
+                .iter()
+                .zip(&table.columns)
+                .chain(
+                    sibling_table
+                        .component_ids
+                        .iter()
+                        .zip(&sibling_table.columns),
+                )
+                .map(|(component_id, column)| (*component_id, column.new_empty()))
+                .collect::<Vec<_>>();
+            columns.sort_by_key(|(component_id, _)| *component_id);
+            columns.into_iter().map(|(_, column)| column).collect()
+        };
+
+        let target = self.get_or_create_table(component_ids, columns);
+        let target_row = self.tables[target].len();
+        self.move_row(location.table, location.row, target, target_row);
+        self.move_row(
+            sibling_location.table,
+            sibling_location.row,
+            target,
+            target_row,
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Name, Position, Velocity},
+        entity_component_system::{ComponentID, ComponentStorage, EcsError},
+        primitive_types::Vector2I,
+    };
+
+    use super::ColumnComponentStorage;
+
+    #[test]
+    fn column_component_storage() {
+        let mut component_storage = ColumnComponentStorage::new();
+
+        let entities = (0..3)
+            .map(|i| {
+                let position = component_storage
+                    .insert_component(Position(Vector2I(i, i)))
+                    .unwrap();
+                let name = component_storage
+                    .insert_component(Name(i.to_string()))
+                    .unwrap();
+                component_storage
+                    .group_component_data(&name, &position)
+                    .unwrap();
+                (position, name)
+            })
+            .collect::<Vec<_>>();
+
+        // Grouped data shares a single table, row by row
+        let mut component_ids = vec![ComponentID::get::<Position>(), ComponentID::get::<Name>()];
+        component_ids.sort();
+        let table = component_storage.table_indices[&component_ids];
+        assert_eq!(component_storage.tables.len(), 3);
+        assert_eq!(component_storage.tables[table].len(), 3);
+
+        // Removing a component moves the rest of the row, and patches the row swapped into its place
+        let (first_position, first_name) = entities[0];
+        component_storage
+            .remove_component_data(&first_name)
+            .unwrap();
+        assert_eq!(component_storage.tables[table].len(), 2);
+        assert_eq!(
+            component_storage.get_component_data::<Name>(&first_name),
+            Err(EcsError::MissingComponentData(first_name))
+        );
+
+        for (i, (position, name)) in entities.iter().enumerate() {
+            assert_eq!(
+                **component_storage
+                    .get_component_data::<Position>(position)
+                    .unwrap(),
+                Vector2I(i as i64, i as i64)
+            );
+
+            if i > 0 {
+                assert_eq!(
+                    **component_storage.get_component_data::<Name>(name).unwrap(),
+                    i.to_string()
+                );
+            }
+        }
+
+        // Data can't be fetched as the wrong type, even if its row holds that type
+        let (_, second_name) = entities[1];
+        assert!(matches!(
+            component_storage.get_component_data::<Position>(&second_name),
+            Err(EcsError::Downcast { .. })
+        ));
+
+        // Freed slots are recycled with a new generation
+        let velocity = component_storage
+            .insert_component(Velocity::default())
+            .unwrap();
+        assert_eq!(velocity.get_index(), first_name.get_index());
+        assert_ne!(velocity, first_name);
+        assert!(component_storage
+            .get_component_data::<Position>(&first_position)
+            .is_ok());
+    }
+}
@@ -1,15 +1,52 @@
 use std::{fmt::Display, sync::atomic::AtomicUsize, sync::atomic::Ordering};
 
-use crate::core::uid::UID;
+use crate::{core::uid::UID, entity_component_system::EcsError};
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 pub struct ComponentDataID(pub UID);
 
 impl ComponentDataID {
+    /// Number of low bits used to store the index of a packed ID; the remaining high bits store the generation
+    const INDEX_BITS: u32 = UID::BITS / 2;
+    const INDEX_MASK: UID = (1 << Self::INDEX_BITS) - 1;
+    const GENERATION_MASK: UID = UID::MAX >> Self::INDEX_BITS;
+
     pub fn next() -> Self {
         static COUNTER: AtomicUsize = AtomicUsize::new(0);
         ComponentDataID(COUNTER.fetch_add(1, Ordering::Relaxed))
     }
+
+    /// Pack a slot index and generation into an ID, for storages that recycle slots
+    ///
+    /// The generation is bumped each time a slot is reused, so that stale IDs can be detected.
+    pub fn from_index_generation(index: usize, generation: usize) -> Result<Self, EcsError> {
+        if index > Self::INDEX_MASK {
+            return Err(EcsError::IDCapacity {
+                id_type: "ComponentDataID",
+                index,
+            });
+        }
+
+        Ok(ComponentDataID(
+            ((generation & Self::GENERATION_MASK) << Self::INDEX_BITS) | index,
+        ))
+    }
+
+    pub fn get_index(&self) -> usize {
+        let ComponentDataID(component_data_id) = *self;
+        component_data_id & Self::INDEX_MASK
+    }
+
+    pub fn get_generation(&self) -> usize {
+        let ComponentDataID(component_data_id) = *self;
+        component_data_id >> Self::INDEX_BITS
+    }
+
+    /// The ID that will refer to the next data stored in this slot
+    pub fn next_generation(&self) -> Self {
+        let generation = (self.get_generation() + 1) & Self::GENERATION_MASK;
+        ComponentDataID((generation << Self::INDEX_BITS) | self.get_index())
+    }
 }
 
 impl Display for ComponentDataID {
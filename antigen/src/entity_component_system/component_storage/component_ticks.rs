@@ -0,0 +1,29 @@
+/// The change ticks at which a piece of component data was added and last written to
+///
+/// Ticks are compared against the tick a system last ran at to detect changes made since.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct ComponentTicks {
+    pub added: u64,
+    pub changed: u64,
+}
+
+impl ComponentTicks {
+    pub fn new(change_tick: u64) -> Self {
+        ComponentTicks {
+            added: change_tick,
+            changed: change_tick,
+        }
+    }
+
+    pub fn is_added(&self, last_change_tick: u64) -> bool {
+        self.added > last_change_tick
+    }
+
+    pub fn is_changed(&self, last_change_tick: u64) -> bool {
+        self.changed > last_change_tick
+    }
+
+    pub fn set_changed(&mut self, change_tick: u64) {
+        self.changed = change_tick;
+    }
+}
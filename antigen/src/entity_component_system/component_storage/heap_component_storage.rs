@@ -1,20 +1,41 @@
-use crate::entity_component_system::{traits::DowncastComponentTrait, ComponentID, ComponentTrait};
+use crate::entity_component_system::{
+    traits::DowncastComponentTrait, ComponentID, ComponentTrait, EcsError,
+};
 
-use super::{ComponentDataID, ComponentDropCallback, ComponentStorage};
-use std::collections::HashMap;
+use super::{ComponentDataID, ComponentStorage, ComponentTicks};
+use std::{cell::UnsafeCell, collections::HashMap};
 
 pub struct HeapComponentStorage {
-    component_data: HashMap<ComponentDataID, Box<dyn ComponentTrait>>,
-    component_drop_callbacks: HashMap<ComponentID, Vec<ComponentDropCallback>>,
+    component_data: HashMap<ComponentDataID, Box<UnsafeCell<dyn ComponentTrait>>>,
+    component_ticks: HashMap<ComponentDataID, UnsafeCell<ComponentTicks>>,
 }
 
 impl HeapComponentStorage {
     pub fn new() -> Self {
         HeapComponentStorage {
             component_data: HashMap::new(),
-            component_drop_callbacks: HashMap::new(),
+            component_ticks: HashMap::new(),
         }
     }
+
+    fn get_data_cell(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&UnsafeCell<dyn ComponentTrait>, EcsError> {
+        self.component_data
+            .get(component_data_id)
+            .map(Box::as_ref)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn get_ticks_cell(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&UnsafeCell<ComponentTicks>, EcsError> {
+        self.component_ticks
+            .get(component_data_id)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
 }
 
 impl Default for HeapComponentStorage {
@@ -23,95 +44,117 @@ impl Default for HeapComponentStorage {
     }
 }
 
-impl ComponentStorage for HeapComponentStorage {
-    fn register_component_drop_callback<T>(&mut self, callback: ComponentDropCallback)
-    where
-        T: ComponentTrait + 'static,
-    {
-        let component_id = ComponentID::get::<T>();
-        if let Some(component_drop_callbacks) = self.component_drop_callbacks.get_mut(&component_id)
-        {
-            component_drop_callbacks.push(callback);
-        } else {
-            self.component_drop_callbacks
-                .insert(component_id, vec![callback]);
-        }
-    }
+// Safety: Components are Send + Sync, and the only writes made through a shared reference
+// are to cells handed out by get_component_data_ptr, whose callers guarantee exclusive access to them
+unsafe impl Sync for HeapComponentStorage {}
 
-    fn insert_component<T>(&mut self, component_data: T) -> Result<ComponentDataID, String>
+impl ComponentStorage for HeapComponentStorage {
+    fn insert_component<T>(&mut self, component_data: T) -> Result<ComponentDataID, EcsError>
     where
         T: ComponentTrait + 'static,
     {
         let id = ComponentDataID::next();
-        self.component_data.insert(id, Box::new(component_data));
+        self.component_data
+            .insert(id, Box::new(UnsafeCell::new(component_data)));
+        self.component_ticks
+            .insert(id, UnsafeCell::new(ComponentTicks::default()));
         Ok(id)
     }
 
-    fn get_component_data<T>(&self, component_data_id: &ComponentDataID) -> Result<&T, String>
+    fn get_component_data<T>(&self, component_data_id: &ComponentDataID) -> Result<&T, EcsError>
     where
         T: ComponentTrait + 'static,
     {
-        let component_data = self.component_data.get(component_data_id).ok_or(format!(
-            "Error getting component data: No such data {}",
-            component_data_id
-        ))?;
+        let (component_data, _) = self.get_component_data_ptr::<T>(component_data_id)?;
 
-        let component_data = component_data.as_ref();
-        let component_data = T::as_data(component_data);
-
-        Ok(component_data)
+        // Safety: Writes through the cell are only made by callers of get_component_data_ptr,
+        // which guarantee that no shared references to the same data are live
+        Ok(unsafe { &*component_data })
     }
 
     fn get_component_data_mut<T>(
         &mut self,
         component_data_id: &ComponentDataID,
-    ) -> Result<&mut T, String>
+    ) -> Result<&mut T, EcsError>
     where
         T: ComponentTrait + 'static,
     {
         let component_data = self
             .component_data
             .get_mut(component_data_id)
-            .ok_or(format!(
-                "Error getting component data: No such data {}",
-                component_data_id
-            ))?;
+            .ok_or(EcsError::MissingComponentData(*component_data_id))?;
 
-        let component_data = component_data.as_mut();
-        let component_data = T::as_mut_data(component_data);
+        let component_data = component_data.get_mut();
+        let component_data = T::as_mut_data(component_data).ok_or_else(|| EcsError::Downcast {
+            component_data_id: *component_data_id,
+            component_id: ComponentID::get::<T>(),
+        })?;
 
         Ok(component_data)
     }
 
+    fn get_component_data_ptr<T>(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(*mut T, *mut ComponentTicks), EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let component_data = self.get_data_cell(component_data_id)?.get();
+
+        // Safety: The caller only writes through this pointer while holding no other references to it
+        let is_type = unsafe { T::as_data(&*component_data).is_some() };
+        if !is_type {
+            return Err(EcsError::Downcast {
+                component_data_id: *component_data_id,
+                component_id: ComponentID::get::<T>(),
+            });
+        }
+
+        let component_ticks = self.get_ticks_cell(component_data_id)?.get();
+
+        Ok((component_data as *mut T, component_ticks))
+    }
+
     fn get_component_data_string(
         &self,
         component_data_id: &ComponentDataID,
-    ) -> Result<String, String> {
-        let component_data = self.component_data.get(component_data_id).ok_or(format!(
-            "Error getting component data: No such data {}",
-            component_data_id
-        ))?;
+    ) -> Result<String, EcsError> {
+        let component_data = self.get_data_cell(component_data_id)?;
+
+        // Safety: See get_component_data
+        let component_data = unsafe { &*component_data.get() };
         Ok(format!("{:#?}", component_data))
     }
 
+    fn get_component_ticks(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&ComponentTicks, EcsError> {
+        let component_ticks = self.get_ticks_cell(component_data_id)?;
+
+        // Safety: See get_component_data
+        Ok(unsafe { &*component_ticks.get() })
+    }
+
+    fn get_component_ticks_mut(
+        &mut self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&mut ComponentTicks, EcsError> {
+        self.component_ticks
+            .get_mut(component_data_id)
+            .map(UnsafeCell::get_mut)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
     fn remove_component_data(
         &mut self,
-        component_id: &ComponentID,
         component_data_id: &ComponentDataID,
-    ) -> Result<(), String> {
-        let mut component_data = self
-            .component_data
+    ) -> Result<(), EcsError> {
+        self.component_data
             .remove(component_data_id)
-            .ok_or(format!(
-                "Error removing component data: No such data {}",
-                component_data_id
-            ))?;
-
-        if let Some(component_drop_callbacks) = self.component_drop_callbacks.get(component_id) {
-            for callback in component_drop_callbacks {
-                callback(component_data.as_mut());
-            }
-        }
+            .ok_or(EcsError::MissingComponentData(*component_data_id))?;
+        self.component_ticks.remove(component_data_id);
 
         Ok(())
     }
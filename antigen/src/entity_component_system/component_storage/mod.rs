@@ -1,40 +1,70 @@
+mod column_component_storage;
 mod component_data_id;
+mod component_ticks;
 mod heap_component_storage;
+mod sparse_set_component_storage;
+pub use column_component_storage::ColumnComponentStorage;
 pub use component_data_id::ComponentDataID;
+pub use component_ticks::ComponentTicks;
 pub use heap_component_storage::HeapComponentStorage;
+pub use sparse_set_component_storage::SparseSetComponentStorage;
 
-use crate::entity_component_system::{ComponentID, ComponentTrait};
+use crate::entity_component_system::{ComponentTrait, EcsError};
 
-pub type ComponentDropCallback = fn(&mut dyn ComponentTrait);
-
-pub trait ComponentStorage {
-    fn register_component_drop_callback<T>(&mut self, callback: ComponentDropCallback)
+/// Storages are Sync so that concurrently-running systems can share them,
+/// with each system only reading and writing the component types it declares access to
+pub trait ComponentStorage: Sync {
+    fn insert_component<T>(&mut self, component_data: T) -> Result<ComponentDataID, EcsError>
     where
         T: ComponentTrait + 'static;
 
-    fn insert_component<T>(&mut self, component_data: T) -> Result<ComponentDataID, String>
+    fn get_component_data<T>(&self, component_data_id: &ComponentDataID) -> Result<&T, EcsError>
     where
         T: ComponentTrait + 'static;
 
-    fn get_component_data<T>(&self, component_data_id: &ComponentDataID) -> Result<&T, String>
+    fn get_component_data_mut<T>(
+        &mut self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&mut T, EcsError>
     where
         T: ComponentTrait + 'static;
 
-    fn get_component_data_mut<T>(
-        &mut self,
+    /// Fetch pointers to component data and its change ticks through a shared reference
+    ///
+    /// Storages keep data in UnsafeCells, so writing through these pointers doesn't require `&mut self`.
+    /// Callers must ensure that nothing else references the same data while they write through them.
+    fn get_component_data_ptr<T>(
+        &self,
         component_data_id: &ComponentDataID,
-    ) -> Result<&mut T, String>
+    ) -> Result<(*mut T, *mut ComponentTicks), EcsError>
     where
         T: ComponentTrait + 'static;
 
-    fn remove_component_data(
+    fn get_component_ticks(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&ComponentTicks, EcsError>;
+
+    fn get_component_ticks_mut(
         &mut self,
-        component_id: &ComponentID,
         component_data_id: &ComponentDataID,
-    ) -> Result<(), String>;
+    ) -> Result<&mut ComponentTicks, EcsError>;
+
+    fn remove_component_data(&mut self, component_data_id: &ComponentDataID) -> Result<(), EcsError>;
+
+    /// Store component data alongside another piece of data belonging to the same entity
+    ///
+    /// Called after an entity gains a component, for storages that lay out each entity's data together.
+    fn group_component_data(
+        &mut self,
+        _component_data_id: &ComponentDataID,
+        _sibling_data_id: &ComponentDataID,
+    ) -> Result<(), EcsError> {
+        Ok(())
+    }
 
     fn get_component_data_string(
         &self,
         component_data_id: &ComponentDataID,
-    ) -> Result<String, String>;
+    ) -> Result<String, EcsError>;
 }
@@ -0,0 +1,403 @@
+use std::{any::Any, cell::UnsafeCell, collections::HashMap};
+
+use crate::entity_component_system::{ComponentID, ComponentTrait, EcsError};
+
+use super::{ComponentDataID, ComponentStorage, ComponentTicks};
+
+/// Type-erased interface over a single component type's dense array
+trait ComponentSparseSet: Send {
+    fn get(&self, index: usize) -> Option<&dyn ComponentTrait>;
+    fn get_ticks(&self, index: usize) -> Option<&ComponentTicks>;
+    fn get_ticks_mut(&mut self, index: usize) -> Option<&mut ComponentTicks>;
+
+    /// Swap-remove the data at the given dense index, returning the slot of the data moved into its place
+    fn swap_remove(&mut self, index: usize) -> Option<usize>;
+
+    fn as_any(&self) -> &dyn Any;
+    fn as_mut_any(&mut self) -> &mut dyn Any;
+}
+
+/// Densely-packed storage for a single component type
+///
+/// Each piece of data remembers the sparse slot that addresses it, so the slot can be patched when the data moves.
+/// Dense data is held in UnsafeCells so that ComponentStorage::get_component_data_ptr
+/// can hand out pointers to distinct entries through a shared reference.
+struct SparseSet<T> {
+    dense: Vec<UnsafeCell<T>>,
+    dense_ticks: Vec<UnsafeCell<ComponentTicks>>,
+    dense_slots: Vec<usize>,
+}
+
+impl<T> SparseSet<T> {
+    fn new() -> Self {
+        SparseSet {
+            dense: Vec::new(),
+            dense_ticks: Vec::new(),
+            dense_slots: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, component_data: T, slot: usize) -> usize {
+        self.dense.push(UnsafeCell::new(component_data));
+        self.dense_ticks
+            .push(UnsafeCell::new(ComponentTicks::default()));
+        self.dense_slots.push(slot);
+        self.dense.len() - 1
+    }
+
+    fn get_ptr(&self, index: usize) -> Option<(*mut T, *mut ComponentTicks)> {
+        Some((self.dense.get(index)?.get(), self.dense_ticks[index].get()))
+    }
+
+    fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        self.dense.get_mut(index).map(UnsafeCell::get_mut)
+    }
+}
+
+impl<T> ComponentSparseSet for SparseSet<T>
+where
+    T: ComponentTrait + 'static,
+{
+    fn get(&self, index: usize) -> Option<&dyn ComponentTrait> {
+        // Safety: Callers of get_component_data_ptr guarantee that nothing else references an entry they write to
+        let (component_data, _) = self.get_ptr(index)?;
+        Some(unsafe { &*component_data })
+    }
+
+    fn get_ticks(&self, index: usize) -> Option<&ComponentTicks> {
+        // Safety: See get
+        let (_, component_ticks) = self.get_ptr(index)?;
+        Some(unsafe { &*component_ticks })
+    }
+
+    fn get_ticks_mut(&mut self, index: usize) -> Option<&mut ComponentTicks> {
+        self.dense_ticks.get_mut(index).map(UnsafeCell::get_mut)
+    }
+
+    fn swap_remove(&mut self, index: usize) -> Option<usize> {
+        self.dense.swap_remove(index);
+        self.dense_ticks.swap_remove(index);
+        self.dense_slots.swap_remove(index);
+        self.dense_slots.get(index).copied()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_mut_any(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// A recyclable handle to component data, which tracks the data's position in its dense array
+#[derive(Debug, Copy, Clone)]
+struct SparseSlot {
+    // The current ID for this slot, live or not
+    component_data_id: ComponentDataID,
+    sparse_set: usize,
+    dense_index: Option<usize>,
+}
+
+/// Stores each component type in its own dense array, addressed through a generational sparse slot
+///
+/// Insertion and removal are O(1) and never move other component types' data.
+/// Slots are recycled with a new generation, so stale ComponentDataIDs are rejected rather than reading reused data.
+/// Intended to be paired with SparseSetDirectory.
+pub struct SparseSetComponentStorage {
+    sparse_sets: Vec<Box<dyn ComponentSparseSet>>,
+    sparse_set_indices: HashMap<ComponentID, usize>,
+    slots: Vec<SparseSlot>,
+    free_slots: Vec<usize>,
+}
+
+impl SparseSetComponentStorage {
+    pub fn new() -> Self {
+        SparseSetComponentStorage {
+            sparse_sets: Vec::new(),
+            sparse_set_indices: HashMap::new(),
+            slots: Vec::new(),
+            free_slots: Vec::new(),
+        }
+    }
+
+    /// Look up the sparse set and dense index of the given data, rejecting stale IDs
+    fn get_dense_index(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(usize, usize), EcsError> {
+        self.slots
+            .get(component_data_id.get_index())
+            .filter(|slot| slot.component_data_id == *component_data_id)
+            .and_then(|slot| Some((slot.sparse_set, slot.dense_index?)))
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn get_sparse_set(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(&dyn ComponentSparseSet, usize), EcsError> {
+        let (sparse_set, index) = self.get_dense_index(component_data_id)?;
+        Ok((self.sparse_sets[sparse_set].as_ref(), index))
+    }
+
+    fn get_typed_sparse_set<T>(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(&SparseSet<T>, usize), EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let (sparse_set, index) = self.get_sparse_set(component_data_id)?;
+
+        let sparse_set = sparse_set
+            .as_any()
+            .downcast_ref::<SparseSet<T>>()
+            .ok_or_else(|| EcsError::Downcast {
+                component_data_id: *component_data_id,
+                component_id: ComponentID::get::<T>(),
+            })?;
+
+        Ok((sparse_set, index))
+    }
+
+    fn allocate_slot(&mut self) -> Result<ComponentDataID, EcsError> {
+        match self.free_slots.pop() {
+            Some(index) => Ok(self.slots[index].component_data_id),
+            None => {
+                let component_data_id =
+                    ComponentDataID::from_index_generation(self.slots.len(), 0)?;
+                self.slots.push(SparseSlot {
+                    component_data_id,
+                    sparse_set: 0,
+                    dense_index: None,
+                });
+                Ok(component_data_id)
+            }
+        }
+    }
+}
+
+impl Default for SparseSetComponentStorage {
+    fn default() -> Self {
+        SparseSetComponentStorage::new()
+    }
+}
+
+// Safety: Dense arrays only ever hold Send + Sync component data, and are only resized through &mut self.
+// Entries are written through a shared reference only via get_component_data_ptr, whose callers don't alias them.
+unsafe impl Sync for SparseSetComponentStorage {}
+
+impl ComponentStorage for SparseSetComponentStorage {
+    fn insert_component<T>(&mut self, component_data: T) -> Result<ComponentDataID, EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let component_id = ComponentID::get::<T>();
+
+        let sparse_set = match self.sparse_set_indices.get(&component_id) {
+            Some(sparse_set) => *sparse_set,
+            None => {
+                let sparse_set = self.sparse_sets.len();
+                self.sparse_sets.push(Box::new(SparseSet::<T>::new()));
+                self.sparse_set_indices.insert(component_id, sparse_set);
+                sparse_set
+            }
+        };
+
+        let component_data_id = self.allocate_slot()?;
+        let dense_index = self.sparse_sets[sparse_set]
+            .as_mut_any()
+            .downcast_mut::<SparseSet<T>>()
+            .expect("Sparse set type mismatch")
+            .push(component_data, component_data_id.get_index());
+
+        let slot = &mut self.slots[component_data_id.get_index()];
+        slot.sparse_set = sparse_set;
+        slot.dense_index = Some(dense_index);
+
+        Ok(component_data_id)
+    }
+
+    fn get_component_data<T>(&self, component_data_id: &ComponentDataID) -> Result<&T, EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let (component_data, _) = self.get_component_data_ptr::<T>(component_data_id)?;
+
+        // Safety: See SparseSet::get
+        Ok(unsafe { &*component_data })
+    }
+
+    fn get_component_data_mut<T>(
+        &mut self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&mut T, EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let (sparse_set, index) = self.get_dense_index(component_data_id)?;
+
+        self.sparse_sets[sparse_set]
+            .as_mut_any()
+            .downcast_mut::<SparseSet<T>>()
+            .ok_or_else(|| EcsError::Downcast {
+                component_data_id: *component_data_id,
+                component_id: ComponentID::get::<T>(),
+            })?
+            .get_mut(index)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn get_component_data_ptr<T>(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(*mut T, *mut ComponentTicks), EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let (sparse_set, index) = self.get_typed_sparse_set::<T>(component_data_id)?;
+
+        sparse_set
+            .get_ptr(index)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn get_component_data_string(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<String, EcsError> {
+        let (sparse_set, index) = self.get_sparse_set(component_data_id)?;
+
+        let component_data = sparse_set
+            .get(index)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))?;
+
+        Ok(format!("{:#?}", component_data))
+    }
+
+    fn get_component_ticks(
+        &self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&ComponentTicks, EcsError> {
+        let (sparse_set, index) = self.get_sparse_set(component_data_id)?;
+
+        sparse_set
+            .get_ticks(index)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn get_component_ticks_mut(
+        &mut self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<&mut ComponentTicks, EcsError> {
+        let (sparse_set, index) = self.get_dense_index(component_data_id)?;
+
+        self.sparse_sets[sparse_set]
+            .get_ticks_mut(index)
+            .ok_or(EcsError::MissingComponentData(*component_data_id))
+    }
+
+    fn remove_component_data(
+        &mut self,
+        component_data_id: &ComponentDataID,
+    ) -> Result<(), EcsError> {
+        let (sparse_set, index) = self.get_dense_index(component_data_id)?;
+
+        // Patch the slot of the data swapped into the vacated dense index
+        if let Some(moved_slot) = self.sparse_sets[sparse_set].swap_remove(index) {
+            self.slots[moved_slot].dense_index = Some(index);
+        }
+
+        let slot_index = component_data_id.get_index();
+        let slot = &mut self.slots[slot_index];
+        slot.component_data_id = component_data_id.next_generation();
+        slot.dense_index = None;
+        self.free_slots.push(slot_index);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Name, Position},
+        entity_component_system::{ComponentStorage, EcsError},
+        primitive_types::Vector2I,
+    };
+
+    use super::SparseSetComponentStorage;
+
+    #[test]
+    fn sparse_set_component_storage() {
+        let mut component_storage = SparseSetComponentStorage::new();
+
+        let positions = (0..3)
+            .map(|i| {
+                component_storage
+                    .insert_component(Position(Vector2I(i, i)))
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        let name = component_storage
+            .insert_component(Name("Name".into()))
+            .unwrap();
+
+        // Removing from the front swaps the last entry into its place, which must remain addressable
+        component_storage
+            .remove_component_data(&positions[0])
+            .unwrap();
+        for (i, position) in positions.iter().enumerate().skip(1) {
+            assert_eq!(
+                **component_storage
+                    .get_component_data::<Position>(position)
+                    .unwrap(),
+                Vector2I(i as i64, i as i64)
+            );
+        }
+        component_storage
+            .get_component_data_mut::<Position>(&positions[2])
+            .unwrap()
+            .0 = Vector2I(5, 5);
+        assert_eq!(
+            **component_storage
+                .get_component_data::<Position>(&positions[2])
+                .unwrap(),
+            Vector2I(5, 5)
+        );
+
+        // Freed slots are reused with a new generation, so stale IDs don't read the new data
+        let reused = component_storage
+            .insert_component(Position(Vector2I(9, 9)))
+            .unwrap();
+        assert_eq!(reused.get_index(), positions[0].get_index());
+        assert_ne!(reused, positions[0]);
+        assert_eq!(
+            component_storage
+                .get_component_data::<Position>(&positions[0])
+                .unwrap_err(),
+            EcsError::MissingComponentData(positions[0])
+        );
+        assert_eq!(
+            component_storage.remove_component_data(&positions[0]),
+            Err(EcsError::MissingComponentData(positions[0]))
+        );
+        assert_eq!(
+            **component_storage
+                .get_component_data::<Position>(&reused)
+                .unwrap(),
+            Vector2I(9, 9)
+        );
+
+        // Other component types are unaffected, and can't be fetched as the wrong type
+        assert_eq!(
+            **component_storage.get_component_data::<Name>(&name).unwrap(),
+            "Name"
+        );
+        assert!(matches!(
+            component_storage.get_component_data::<Position>(&name),
+            Err(EcsError::Downcast { .. })
+        ));
+    }
+}
@@ -0,0 +1,481 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    ComponentDataID, ComponentID, ComponentTrait, EcsError, EntityAllocator,
+    EntityComponentDirectory, EntityID, EntityReserver,
+};
+
+/// Table of entities that share the same set of components
+struct Archetype {
+    component_ids: Vec<ComponentID>,
+    entities: Vec<EntityID>,
+    component_data_ids: Vec<Vec<ComponentDataID>>,
+    add_edges: HashMap<ComponentID, usize>,
+    remove_edges: HashMap<ComponentID, usize>,
+}
+
+impl Archetype {
+    fn new(component_ids: Vec<ComponentID>) -> Self {
+        let component_data_ids = component_ids.iter().map(|_| Vec::new()).collect();
+        Archetype {
+            component_ids,
+            entities: Vec::new(),
+            component_data_ids,
+            add_edges: HashMap::new(),
+            remove_edges: HashMap::new(),
+        }
+    }
+
+    fn get_column(&self, component_id: &ComponentID) -> Option<usize> {
+        self.component_ids.binary_search(component_id).ok()
+    }
+
+    fn push(
+        &mut self,
+        entity_id: EntityID,
+        component_data: &[(ComponentID, ComponentDataID)],
+    ) -> usize {
+        for (component_id, column) in self.component_ids.iter().zip(&mut self.component_data_ids) {
+            let (_, component_data_id) = component_data
+                .iter()
+                .find(|(candidate_id, _)| candidate_id == component_id)
+                .expect("Missing component data for archetype column");
+            column.push(*component_data_id);
+        }
+
+        self.entities.push(entity_id);
+        self.entities.len() - 1
+    }
+
+    /// Remove the given row, returning its component data and the entity that was moved into its place
+    fn swap_remove(
+        &mut self,
+        row: usize,
+    ) -> (Vec<(ComponentID, ComponentDataID)>, Option<EntityID>) {
+        self.entities.swap_remove(row);
+
+        let component_data = self
+            .component_ids
+            .iter()
+            .copied()
+            .zip(
+                self.component_data_ids
+                    .iter_mut()
+                    .map(|column| column.swap_remove(row)),
+            )
+            .collect();
+
+        (component_data, self.entities.get(row).copied())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct EntityLocation {
+    archetype: usize,
+    row: usize,
+}
+
+/// Groups entities into archetype tables keyed by their set of components
+///
+/// Adding or removing a component moves the entity into the matching archetype,
+/// with transitions cached as edges between archetypes.
+pub struct ArchetypeDirectory {
+    entity_allocator: EntityAllocator,
+    components: HashSet<ComponentID>,
+    entity_locations: HashMap<EntityID, EntityLocation>,
+    archetypes: Vec<Archetype>,
+    archetype_indices: HashMap<Vec<ComponentID>, usize>,
+}
+
+impl ArchetypeDirectory {
+    pub fn new() -> Self {
+        let mut archetype_indices = HashMap::new();
+        archetype_indices.insert(Vec::new(), 0);
+
+        ArchetypeDirectory {
+            entity_allocator: EntityAllocator::new(),
+            components: HashSet::new(),
+            entity_locations: HashMap::new(),
+            archetypes: vec![Archetype::new(Vec::new())],
+            archetype_indices,
+        }
+    }
+
+    fn get_entity_location(&self, entity_id: &EntityID) -> Result<EntityLocation, EcsError> {
+        self.entity_locations
+            .get(entity_id)
+            .copied()
+            .ok_or(EcsError::StaleEntity(*entity_id))
+    }
+
+    fn get_or_create_archetype(&mut self, component_ids: Vec<ComponentID>) -> usize {
+        if let Some(archetype) = self.archetype_indices.get(&component_ids) {
+            return *archetype;
+        }
+
+        let archetype = self.archetypes.len();
+        self.archetypes.push(Archetype::new(component_ids.clone()));
+        self.archetype_indices.insert(component_ids, archetype);
+        archetype
+    }
+
+    fn get_add_edge(&mut self, archetype: usize, component_id: ComponentID) -> usize {
+        if let Some(target) = self.archetypes[archetype].add_edges.get(&component_id) {
+            return *target;
+        }
+
+        let mut component_ids = self.archetypes[archetype].component_ids.clone();
+        if let Err(index) = component_ids.binary_search(&component_id) {
+            component_ids.insert(index, component_id);
+        }
+
+        let target = self.get_or_create_archetype(component_ids);
+        self.archetypes[archetype]
+            .add_edges
+            .insert(component_id, target);
+        self.archetypes[target]
+            .remove_edges
+            .insert(component_id, archetype);
+        target
+    }
+
+    fn get_remove_edge(&mut self, archetype: usize, component_id: ComponentID) -> usize {
+        if let Some(target) = self.archetypes[archetype].remove_edges.get(&component_id) {
+            return *target;
+        }
+
+        let mut component_ids = self.archetypes[archetype].component_ids.clone();
+        if let Ok(index) = component_ids.binary_search(&component_id) {
+            component_ids.remove(index);
+        }
+
+        let target = self.get_or_create_archetype(component_ids);
+        self.archetypes[archetype]
+            .remove_edges
+            .insert(component_id, target);
+        self.archetypes[target]
+            .add_edges
+            .insert(component_id, archetype);
+        target
+    }
+
+    /// Remove an entity's row from its archetype, patching the location of the entity swapped into its place
+    fn take_entity_row(&mut self, location: EntityLocation) -> Vec<(ComponentID, ComponentDataID)> {
+        let (component_data, moved_entity) =
+            self.archetypes[location.archetype].swap_remove(location.row);
+
+        if let Some(moved_entity) = moved_entity {
+            if let Some(moved_location) = self.entity_locations.get_mut(&moved_entity) {
+                moved_location.row = location.row;
+            }
+        }
+
+        component_data
+    }
+
+    fn move_entity(
+        &mut self,
+        entity_id: EntityID,
+        location: EntityLocation,
+        target: usize,
+        added_component: Option<(ComponentID, ComponentDataID)>,
+    ) {
+        let mut component_data = self.take_entity_row(location);
+        component_data.extend(added_component);
+
+        let row = self.archetypes[target].push(entity_id, &component_data);
+        self.entity_locations.insert(
+            entity_id,
+            EntityLocation {
+                archetype: target,
+                row,
+            },
+        );
+    }
+}
+
+impl Default for ArchetypeDirectory {
+    fn default() -> Self {
+        ArchetypeDirectory::new()
+    }
+}
+
+impl EntityComponentDirectory for ArchetypeDirectory {
+    // CREATE
+    fn create_entity(&mut self) -> Result<EntityID, EcsError> {
+        let entity_id: EntityID = self.entity_allocator.allocate()?;
+        let row = self.archetypes[0].push(entity_id, &[]);
+        self.entity_locations
+            .insert(entity_id, EntityLocation { archetype: 0, row });
+
+        Ok(entity_id)
+    }
+
+    fn create_reserved_entity(&mut self, entity_id: EntityID) -> Result<(), EcsError> {
+        self.entity_allocator.claim(entity_id)?;
+        let row = self.archetypes[0].push(entity_id, &[]);
+        self.entity_locations
+            .insert(entity_id, EntityLocation { archetype: 0, row });
+
+        Ok(())
+    }
+
+    fn get_entity_reserver(&self) -> EntityReserver {
+        self.entity_allocator.get_reserver()
+    }
+
+    fn get_entity_allocator(&self) -> &EntityAllocator {
+        &self.entity_allocator
+    }
+
+    fn restore_entity_allocator(&mut self, entity_allocator: &EntityAllocator) {
+        for entity_id in entity_allocator.iter() {
+            if !self.entity_allocator.is_alive(&entity_id) {
+                let row = self.archetypes[0].push(entity_id, &[]);
+                self.entity_locations
+                    .insert(entity_id, EntityLocation { archetype: 0, row });
+            }
+        }
+        self.entity_allocator.restore(entity_allocator);
+    }
+
+    // INSERT
+    fn insert_component<T: ComponentTrait + 'static>(&mut self) -> Result<ComponentID, EcsError> {
+        let component_id = ComponentID::get::<T>();
+        self.components.insert(component_id);
+
+        Ok(component_id)
+    }
+
+    fn insert_entity_component<T>(
+        &mut self,
+        entity_id: &EntityID,
+        component_data_id: ComponentDataID,
+    ) -> Result<ComponentDataID, EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let location = self.get_entity_location(entity_id)?;
+        let component_id = ComponentID::get::<T>();
+
+        let archetype = &mut self.archetypes[location.archetype];
+        if let Some(column) = archetype.get_column(&component_id) {
+            archetype.component_data_ids[column][location.row] = component_data_id;
+        } else {
+            let target = self.get_add_edge(location.archetype, component_id);
+            self.move_entity(
+                *entity_id,
+                location,
+                target,
+                Some((component_id, component_data_id)),
+            );
+        }
+
+        Ok(component_data_id)
+    }
+
+    // DESTROY
+    fn destroy_entity(&mut self, entity_id: EntityID) -> Result<(), EcsError> {
+        let location = self.get_entity_location(&entity_id)?;
+
+        self.entity_locations.remove(&entity_id);
+        self.take_entity_row(location);
+        self.entity_allocator.free(&entity_id);
+
+        Ok(())
+    }
+
+    fn destroy_component<T: ComponentTrait + 'static>(&mut self) -> Result<(), EcsError> {
+        let component_id = ComponentID::get::<T>();
+        if self.components.remove(&component_id) {
+            Ok(())
+        } else {
+            Err(EcsError::UnknownComponent(component_id))
+        }
+    }
+
+    fn destroy_entity_component(
+        &mut self,
+        entity_id: &EntityID,
+        component_id: &ComponentID,
+    ) -> Result<(), EcsError> {
+        let location = self.get_entity_location(entity_id)?;
+
+        if self.archetypes[location.archetype]
+            .get_column(component_id)
+            .is_none()
+        {
+            return Err(EcsError::MissingComponent {
+                entity_id: *entity_id,
+                component_id: *component_id,
+            });
+        }
+
+        let target = self.get_remove_edge(location.archetype, *component_id);
+        self.move_entity(*entity_id, location, target, None);
+
+        Ok(())
+    }
+
+    // EXIST
+    fn is_alive(&self, entity_id: &EntityID) -> bool {
+        self.entity_allocator.is_alive(entity_id)
+    }
+
+    fn is_valid_component<T: ComponentTrait + 'static>(&self) -> bool {
+        self.components.contains(&ComponentID::get::<T>())
+    }
+
+    fn entity_has_component_by_id(&self, entity_id: &EntityID, component_id: &ComponentID) -> bool {
+        match self.entity_locations.get(entity_id) {
+            Some(location) => self.archetypes[location.archetype]
+                .get_column(component_id)
+                .is_some(),
+            None => false,
+        }
+    }
+
+    // GET
+    fn get_entity_by_predicate(&self, predicate: impl Fn(&EntityID) -> bool) -> Option<EntityID> {
+        self.archetypes
+            .iter()
+            .flat_map(|archetype| archetype.entities.iter().copied())
+            .find(predicate)
+    }
+
+    fn get_entities_by_predicate(&self, predicate: impl Fn(&EntityID) -> bool) -> Vec<EntityID> {
+        self.archetypes
+            .iter()
+            .flat_map(|archetype| archetype.entities.iter().copied())
+            .filter(predicate)
+            .collect()
+    }
+
+    fn get_entities_with_components(
+        &self,
+        component_ids: &[ComponentID],
+        predicate: impl Fn(&EntityID) -> bool,
+    ) -> Vec<EntityID> {
+        self.archetypes
+            .iter()
+            .filter(|archetype| {
+                component_ids
+                    .iter()
+                    .all(|component_id| archetype.get_column(component_id).is_some())
+            })
+            .flat_map(|archetype| archetype.entities.iter().copied())
+            .filter(predicate)
+            .collect()
+    }
+
+    fn get_components_by_predicate(
+        &self,
+        predicate: impl Fn(&ComponentID) -> bool,
+    ) -> Vec<ComponentID> {
+        self.components.iter().copied().filter(predicate).collect()
+    }
+
+    fn get_entity_component_data_id(
+        &self,
+        entity_id: &EntityID,
+        component_id: &ComponentID,
+    ) -> Result<ComponentDataID, EcsError> {
+        let location = self.get_entity_location(entity_id)?;
+
+        let archetype = &self.archetypes[location.archetype];
+        match archetype.get_column(component_id) {
+            Some(column) => Ok(archetype.component_data_ids[column][location.row]),
+            None => Err(EcsError::MissingComponent {
+                entity_id: *entity_id,
+                component_id: *component_id,
+            }),
+        }
+    }
+
+    fn get_entity_component_data(
+        &self,
+        entity_id: &EntityID,
+    ) -> Result<HashMap<ComponentID, ComponentDataID>, EcsError> {
+        let location = self.get_entity_location(entity_id)?;
+
+        let archetype = &self.archetypes[location.archetype];
+        Ok(archetype
+            .component_ids
+            .iter()
+            .copied()
+            .zip(
+                archetype
+                    .component_data_ids
+                    .iter()
+                    .map(|column| column[location.row]),
+            )
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Position, Velocity},
+        entity_component_system::{
+            ArchetypeDirectory, ColumnComponentStorage, ComponentHooks, ComponentID, EcsError,
+            EntityComponentDirectory, SystemInterface,
+        },
+        primitive_types::Vector2I,
+    };
+
+    #[test]
+    fn archetype_directory() {
+        let mut component_storage = ColumnComponentStorage::new();
+        let mut entity_component_directory = ArchetypeDirectory::new();
+        let component_hooks = ComponentHooks::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let entities = (0..4)
+            .map(|i| {
+                let entity_id = db.create_entity(None).unwrap();
+                db.insert_entity_component(entity_id, Position(Vector2I(i, i)))
+                    .unwrap();
+                db.insert_entity_component(entity_id, Velocity::default())
+                    .unwrap();
+                entity_id
+            })
+            .collect::<Vec<_>>();
+
+        // Moving entities between archetypes must keep the remaining rows intact
+        db.remove_component_from_entity::<Velocity>(entities[0])
+            .unwrap();
+        db.destroy_entity(entities[1]).unwrap();
+
+        assert!(!db.entity_component_directory.is_alive(&entities[1]));
+        assert_eq!(
+            db.entity_component_directory
+                .get_entity_component_data(&entities[1])
+                .unwrap_err(),
+            EcsError::StaleEntity(entities[1])
+        );
+        assert!(!db
+            .entity_component_directory
+            .entity_has_component::<Velocity>(&entities[0]));
+
+        for (i, entity_id) in entities.iter().enumerate() {
+            if i == 1 {
+                continue;
+            }
+
+            let position = db.get_entity_component::<Position>(*entity_id).unwrap();
+            assert_eq!(**position, Vector2I(i as i64, i as i64));
+        }
+
+        let component_data = db
+            .entity_component_directory
+            .get_entity_component_data(&entities[3])
+            .unwrap();
+        assert!(component_data.contains_key(&ComponentID::get::<Velocity>()));
+        assert_eq!(component_data.len(), 2);
+    }
+}
@@ -0,0 +1,196 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use super::{EcsError, EntityID};
+
+/// Thread-safe handle for reserving fresh entity IDs without exclusive access to their allocator
+///
+/// Reserved IDs aren't alive until claimed by the allocator they came from.
+#[derive(Debug, Default, Clone)]
+pub struct EntityReserver {
+    next_index: Arc<AtomicUsize>,
+}
+
+impl EntityReserver {
+    pub fn reserve(&self) -> Result<EntityID, EcsError> {
+        let index = self.next_index.fetch_add(1, Ordering::Relaxed);
+        EntityID::from_index_generation(index, 0)
+    }
+}
+
+/// Hands out generational entity IDs, recycling the indices of destroyed entities
+#[derive(Debug, Default)]
+pub struct EntityAllocator {
+    // The current ID for each index, live or not
+    entities: Vec<EntityID>,
+    alive: Vec<bool>,
+    free_indices: Vec<usize>,
+    reserver: EntityReserver,
+}
+
+impl EntityAllocator {
+    pub fn new() -> Self {
+        EntityAllocator::default()
+    }
+
+    pub fn allocate(&mut self) -> Result<EntityID, EcsError> {
+        match self.free_indices.pop() {
+            Some(index) => {
+                self.alive[index] = true;
+                Ok(self.entities[index])
+            }
+            None => {
+                let entity_id = self.reserver.reserve()?;
+                self.claim(entity_id)?;
+                Ok(entity_id)
+            }
+        }
+    }
+
+    pub fn get_reserver(&self) -> EntityReserver {
+        self.reserver.clone()
+    }
+
+    /// Bring an ID handed out by this allocator's reserver to life
+    pub fn claim(&mut self, entity_id: EntityID) -> Result<(), EcsError> {
+        let index = entity_id.get_index();
+
+        // Skipped indices may still be held by other reservations, so they stay dead rather than free
+        while self.entities.len() <= index {
+            let entity_id = EntityID::from_index_generation(self.entities.len(), 0)?;
+            self.entities.push(entity_id);
+            self.alive.push(false);
+        }
+
+        if self.alive[index] || self.entities[index] != entity_id {
+            return Err(EcsError::StaleEntity(entity_id));
+        }
+
+        self.alive[index] = true;
+        Ok(())
+    }
+
+    /// Release an entity's index for reuse, returning false if the ID was already stale
+    pub fn free(&mut self, entity_id: &EntityID) -> bool {
+        if !self.is_alive(entity_id) {
+            return false;
+        }
+
+        let index = entity_id.get_index();
+        self.entities[index] = entity_id.next_generation();
+        self.alive[index] = false;
+        self.free_indices.push(index);
+        true
+    }
+
+    pub fn is_alive(&self, entity_id: &EntityID) -> bool {
+        let index = entity_id.get_index();
+        match self.entities.get(index) {
+            Some(current) => self.alive[index] && current == entity_id,
+            None => false,
+        }
+    }
+
+    /// Return to the state of an earlier clone of this allocator, keeping existing reservers valid
+    ///
+    /// The reserver never moves backwards, so IDs reserved since the clone was taken can't be handed out twice.
+    pub fn restore(&mut self, entity_allocator: &EntityAllocator) {
+        self.entities = entity_allocator.entities.clone();
+        self.alive = entity_allocator.alive.clone();
+        self.free_indices = entity_allocator.free_indices.clone();
+        self.reserver.next_index.fetch_max(
+            entity_allocator.reserver.next_index.load(Ordering::Relaxed),
+            Ordering::Relaxed,
+        );
+    }
+
+    /// Iterate over all live entities in index order
+    pub fn iter(&self) -> impl Iterator<Item = EntityID> + '_ {
+        self.entities
+            .iter()
+            .zip(&self.alive)
+            .filter(|(_, alive)| **alive)
+            .map(|(entity_id, _)| *entity_id)
+    }
+}
+
+impl Clone for EntityAllocator {
+    fn clone(&self) -> Self {
+        // Clones get their own reserver, so reservations against one don't skip indices in the other
+        EntityAllocator {
+            entities: self.entities.clone(),
+            alive: self.alive.clone(),
+            free_indices: self.free_indices.clone(),
+            reserver: EntityReserver {
+                next_index: Arc::new(AtomicUsize::new(
+                    self.reserver.next_index.load(Ordering::Relaxed),
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EntityAllocator;
+
+    #[test]
+    fn entity_allocator() {
+        let mut entity_allocator = EntityAllocator::new();
+
+        let first = entity_allocator.allocate().unwrap();
+        let second = entity_allocator.allocate().unwrap();
+        assert!(entity_allocator.is_alive(&first));
+
+        assert!(entity_allocator.free(&first));
+        assert!(!entity_allocator.is_alive(&first));
+        assert!(!entity_allocator.free(&first));
+
+        // The freed index is recycled with a new generation
+        let third = entity_allocator.allocate().unwrap();
+        assert_eq!(third.get_index(), first.get_index());
+        assert_eq!(third.get_generation(), first.get_generation() + 1);
+        assert!(entity_allocator.is_alive(&third));
+        assert!(!entity_allocator.is_alive(&first));
+
+        assert_eq!(
+            entity_allocator.iter().collect::<Vec<_>>(),
+            vec![third, second]
+        );
+
+        // Reserved IDs skip past allocated indices, and only come alive once claimed
+        let reserver = entity_allocator.get_reserver();
+        let reserved = reserver.reserve().unwrap();
+        assert_eq!(reserved.get_index(), 2);
+        assert!(!entity_allocator.is_alive(&reserved));
+
+        let fourth = entity_allocator.allocate().unwrap();
+        assert_eq!(fourth.get_index(), 3);
+
+        entity_allocator.claim(reserved).unwrap();
+        assert!(entity_allocator.is_alive(&reserved));
+        assert!(entity_allocator.claim(reserved).is_err());
+    }
+
+    #[test]
+    fn restore() {
+        let mut entity_allocator = EntityAllocator::new();
+        let first = entity_allocator.allocate().unwrap();
+        let snapshot = entity_allocator.clone();
+
+        // IDs reserved after the snapshot stay unique once it's restored
+        let reserver = entity_allocator.get_reserver();
+        let reserved = reserver.reserve().unwrap();
+        entity_allocator.restore(&snapshot);
+
+        let second = entity_allocator.allocate().unwrap();
+        assert_ne!(second.get_index(), reserved.get_index());
+        assert!(entity_allocator.is_alive(&first));
+
+        entity_allocator.claim(reserved).unwrap();
+        assert!(entity_allocator.is_alive(&reserved));
+        assert!(entity_allocator.is_alive(&second));
+    }
+}
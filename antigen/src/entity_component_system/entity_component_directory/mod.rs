@@ -1,38 +1,63 @@
+mod archetype_directory;
+mod entity_allocator;
 mod single_threaded_directory;
+mod sparse_set_directory;
 use std::collections::HashMap;
 
+pub use archetype_directory::ArchetypeDirectory;
+pub use entity_allocator::{EntityAllocator, EntityReserver};
 pub use single_threaded_directory::SingleThreadedDirectory;
+pub use sparse_set_directory::SparseSetDirectory;
 
-use crate::entity_component_system::{ComponentDataID, ComponentID, ComponentTrait, EntityID};
+use crate::entity_component_system::{
+    ComponentDataID, ComponentID, ComponentTrait, EcsError, EntityID,
+};
 
-pub trait EntityComponentDirectory {
+/// Directories are Sync so that concurrently-running systems can share read access to them
+pub trait EntityComponentDirectory: Sync {
     // CREATE
-    fn create_entity(&mut self) -> Result<EntityID, String>;
+    fn create_entity(&mut self) -> Result<EntityID, EcsError>;
+
+    /// Create an entity from an ID previously handed out by this directory's reserver
+    fn create_reserved_entity(&mut self, entity_id: EntityID) -> Result<(), EcsError>;
+
+    /// Returns a handle for reserving entity IDs while the directory is borrowed elsewhere
+    fn get_entity_reserver(&self) -> EntityReserver;
+
+    /// Returns the allocator tracking which entity IDs are alive, which can be cloned as a snapshot
+    fn get_entity_allocator(&self) -> &EntityAllocator;
+
+    /// Return entity allocation to a snapshot taken via get_entity_allocator,
+    /// reviving entities that were alive in it with no components
+    ///
+    /// Entities that are alive now but weren't in the snapshot must be destroyed beforehand.
+    fn restore_entity_allocator(&mut self, entity_allocator: &EntityAllocator);
 
     // INSERT
-    fn insert_component<T: ComponentTrait + 'static>(&mut self) -> Result<ComponentID, String>;
+    fn insert_component<T: ComponentTrait + 'static>(&mut self) -> Result<ComponentID, EcsError>;
 
     fn insert_entity_component<T>(
         &mut self,
         entity_id: &EntityID,
         component_data_id: ComponentDataID,
-    ) -> Result<ComponentDataID, String>
+    ) -> Result<ComponentDataID, EcsError>
     where
         T: ComponentTrait + 'static;
 
     // DESTROY
-    fn destroy_component<T: ComponentTrait + 'static>(&mut self) -> Result<(), String>;
+    fn destroy_component<T: ComponentTrait + 'static>(&mut self) -> Result<(), EcsError>;
 
-    fn destroy_entity(&mut self, entity_id: EntityID) -> Result<(), String>;
+    fn destroy_entity(&mut self, entity_id: EntityID) -> Result<(), EcsError>;
 
     fn destroy_entity_component(
         &mut self,
         entity_id: &EntityID,
         component_id: &ComponentID,
-    ) -> Result<(), String>;
+    ) -> Result<(), EcsError>;
 
     // EXIST
-    fn is_valid_entity(&self, entity_id: &EntityID) -> bool;
+    /// Returns false once the entity has been destroyed, even if its index has since been recycled
+    fn is_alive(&self, entity_id: &EntityID) -> bool;
     fn is_valid_component<T: ComponentTrait + 'static>(&self) -> bool;
 
     // GET
@@ -50,15 +75,38 @@ pub trait EntityComponentDirectory {
         &self,
         entity_id: &EntityID,
         component_id: &ComponentID,
-    ) -> Result<ComponentDataID, String>;
+    ) -> Result<ComponentDataID, EcsError>;
 
     fn get_entity_component_data(
         &self,
         entity_id: &EntityID,
-    ) -> Result<HashMap<ComponentID, ComponentDataID>, String>;
+    ) -> Result<HashMap<ComponentID, ComponentDataID>, EcsError>;
+
+    /// Returns every entity that has all of the given components and matches the given predicate
+    ///
+    /// Directories that index entities by component should override this to avoid visiting every entity.
+    fn get_entities_with_components(
+        &self,
+        component_ids: &[ComponentID],
+        predicate: impl Fn(&EntityID) -> bool,
+    ) -> Vec<EntityID> {
+        self.get_entities_by_predicate(|entity_id| {
+            component_ids
+                .iter()
+                .all(|component_id| self.entity_has_component_by_id(entity_id, component_id))
+                && predicate(entity_id)
+        })
+    }
 
     // Derived methods
     fn entity_has_component<T: ComponentTrait + 'static>(&self, entity_id: &EntityID) -> bool {
         self.entity_has_component_by_id(entity_id, &ComponentID::get::<T>())
     }
+
+    fn get_entities_with_component<T: ComponentTrait + 'static>(
+        &self,
+        predicate: impl Fn(&EntityID) -> bool,
+    ) -> Vec<EntityID> {
+        self.get_entities_with_components(&[ComponentID::get::<T>()], predicate)
+    }
 }
@@ -2,10 +2,13 @@ use std::collections::{HashMap, HashSet};
 
 use super::ComponentID;
 
-use super::{ComponentDataID, ComponentTrait, EntityComponentDirectory, EntityID};
+use super::{
+    ComponentDataID, ComponentTrait, EcsError, EntityAllocator, EntityComponentDirectory, EntityID,
+    EntityReserver,
+};
 
 pub struct SingleThreadedDirectory {
-    entities: HashSet<EntityID>,
+    entity_allocator: EntityAllocator,
     components: HashSet<ComponentID>,
     entity_components: HashMap<EntityID, HashMap<ComponentID, ComponentDataID>>,
 }
@@ -13,7 +16,7 @@ pub struct SingleThreadedDirectory {
 impl SingleThreadedDirectory {
     pub fn new() -> Self {
         SingleThreadedDirectory {
-            entities: HashSet::new(),
+            entity_allocator: EntityAllocator::new(),
             components: HashSet::new(),
             entity_components: HashMap::new(),
         }
@@ -22,7 +25,7 @@ impl SingleThreadedDirectory {
     pub fn get_entity_component_data_id_by_type<T>(
         &self,
         entity_id: EntityID,
-    ) -> Result<ComponentDataID, String>
+    ) -> Result<ComponentDataID, EcsError>
     where
         T: ComponentTrait + 'static,
     {
@@ -38,16 +41,39 @@ impl Default for SingleThreadedDirectory {
 
 impl EntityComponentDirectory for SingleThreadedDirectory {
     // CREATE
-    fn create_entity(&mut self) -> Result<EntityID, String> {
-        let entity_id: EntityID = EntityID::next();
-        self.entities.insert(entity_id);
+    fn create_entity(&mut self) -> Result<EntityID, EcsError> {
+        let entity_id: EntityID = self.entity_allocator.allocate()?;
         self.entity_components.insert(entity_id, HashMap::new());
 
         Ok(entity_id)
     }
 
+    fn create_reserved_entity(&mut self, entity_id: EntityID) -> Result<(), EcsError> {
+        self.entity_allocator.claim(entity_id)?;
+        self.entity_components.insert(entity_id, HashMap::new());
+
+        Ok(())
+    }
+
+    fn get_entity_reserver(&self) -> EntityReserver {
+        self.entity_allocator.get_reserver()
+    }
+
+    fn get_entity_allocator(&self) -> &EntityAllocator {
+        &self.entity_allocator
+    }
+
+    fn restore_entity_allocator(&mut self, entity_allocator: &EntityAllocator) {
+        for entity_id in entity_allocator.iter() {
+            if !self.entity_allocator.is_alive(&entity_id) {
+                self.entity_components.insert(entity_id, HashMap::new());
+            }
+        }
+        self.entity_allocator.restore(entity_allocator);
+    }
+
     // INSERT
-    fn insert_component<T: ComponentTrait + 'static>(&mut self) -> Result<ComponentID, String> {
+    fn insert_component<T: ComponentTrait + 'static>(&mut self) -> Result<ComponentID, EcsError> {
         let component_id = ComponentID::get::<T>();
         self.components.insert(component_id);
 
@@ -58,14 +84,14 @@ impl EntityComponentDirectory for SingleThreadedDirectory {
         &mut self,
         entity_id: &EntityID,
         component_data_id: ComponentDataID,
-    ) -> Result<ComponentDataID, String>
+    ) -> Result<ComponentDataID, EcsError>
     where
         T: ComponentTrait + 'static,
     {
         let entity_components = self
             .entity_components
             .get_mut(entity_id)
-            .unwrap_or_else(|| panic!("No such entity {}", entity_id));
+            .ok_or(EcsError::StaleEntity(*entity_id))?;
 
         let component_id = ComponentID::get::<T>();
 
@@ -75,22 +101,21 @@ impl EntityComponentDirectory for SingleThreadedDirectory {
     }
 
     // DESTROY
-    fn destroy_entity(&mut self, entity_id: EntityID) -> Result<(), String> {
+    fn destroy_entity(&mut self, entity_id: EntityID) -> Result<(), EcsError> {
+        if !self.entity_allocator.free(&entity_id) {
+            return Err(EcsError::StaleEntity(entity_id));
+        }
         self.entity_components.remove(&entity_id);
-        self.entities.remove(&entity_id);
 
         Ok(())
     }
 
-    fn destroy_component<T: ComponentTrait + 'static>(&mut self) -> Result<(), String> {
+    fn destroy_component<T: ComponentTrait + 'static>(&mut self) -> Result<(), EcsError> {
         let component_id = ComponentID::get::<T>();
         if self.components.remove(&component_id) {
             Ok(())
         } else {
-            Err(format!(
-                "Failed to destroy component {}: No such component",
-                component_id
-            ))
+            Err(EcsError::UnknownComponent(component_id))
         }
     }
 
@@ -98,20 +123,23 @@ impl EntityComponentDirectory for SingleThreadedDirectory {
         &mut self,
         entity_id: &EntityID,
         component_id: &ComponentID,
-    ) -> Result<(), String> {
+    ) -> Result<(), EcsError> {
         let entity_components = self
             .entity_components
             .get_mut(&entity_id)
-            .unwrap_or_else(|| panic!("No such entity {}", entity_id));
+            .ok_or(EcsError::StaleEntity(*entity_id))?;
         match entity_components.remove(component_id) {
             Some(_) => Ok(()),
-            None => Err("No such component".into()),
+            None => Err(EcsError::MissingComponent {
+                entity_id: *entity_id,
+                component_id: *component_id,
+            }),
         }
     }
 
     // EXIST
-    fn is_valid_entity(&self, entity_id: &EntityID) -> bool {
-        self.entities.contains(entity_id)
+    fn is_alive(&self, entity_id: &EntityID) -> bool {
+        self.entity_allocator.is_alive(entity_id)
     }
 
     fn is_valid_component<T: ComponentTrait + 'static>(&self) -> bool {
@@ -131,11 +159,11 @@ impl EntityComponentDirectory for SingleThreadedDirectory {
 
     // GET
     fn get_entity_by_predicate(&self, predicate: impl Fn(&EntityID) -> bool) -> Option<EntityID> {
-        self.entities.iter().copied().find(predicate)
+        self.entity_allocator.iter().find(predicate)
     }
 
     fn get_entities_by_predicate(&self, predicate: impl Fn(&EntityID) -> bool) -> Vec<EntityID> {
-        self.entities.iter().copied().filter(predicate).collect()
+        self.entity_allocator.iter().filter(predicate).collect()
     }
 
     fn get_components_by_predicate(
@@ -149,36 +177,29 @@ impl EntityComponentDirectory for SingleThreadedDirectory {
         &self,
         entity_id: &EntityID,
         component_id: &ComponentID,
-    ) -> Result<ComponentDataID, String> {
-        let entity_components = match self.entity_components.get(&entity_id) {
-            Some(entity_components) => entity_components,
-            None => panic!(
-                "Error getting entity component data ID: No such entity {}",
-                entity_id
-            ),
-        };
+    ) -> Result<ComponentDataID, EcsError> {
+        let entity_components = self
+            .entity_components
+            .get(&entity_id)
+            .ok_or(EcsError::StaleEntity(*entity_id))?;
 
         match entity_components.get(&component_id) {
             Some(component_data_id) => Ok(*component_data_id),
-            None => Err(format!(
-                "Error getting entity {} component data ID: No such component {}",
-                entity_id,
-                component_id
-            )),
+            None => Err(EcsError::MissingComponent {
+                entity_id: *entity_id,
+                component_id: *component_id,
+            }),
         }
     }
 
     fn get_entity_component_data(
         &self,
         entity_id: &EntityID,
-    ) -> Result<HashMap<ComponentID, ComponentDataID>, String> {
-        let entity_components = match self.entity_components.get(&entity_id) {
-            Some(entity_components) => entity_components,
-            None => panic!(
-                "Error getting entity component data ID: No such entity {}",
-                entity_id
-            ),
-        };
+    ) -> Result<HashMap<ComponentID, ComponentDataID>, EcsError> {
+        let entity_components = self
+            .entity_components
+            .get(&entity_id)
+            .ok_or(EcsError::StaleEntity(*entity_id))?;
 
         Ok(entity_components.clone())
     }
@@ -0,0 +1,398 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{
+    ComponentDataID, ComponentID, ComponentTrait, EcsError, EntityAllocator,
+    EntityComponentDirectory, EntityID, EntityReserver,
+};
+
+/// Densely-packed set of entities with an associated value, indexed sparsely by entity ID
+struct EntitySparseSet<V> {
+    dense_entities: Vec<EntityID>,
+    dense_values: Vec<V>,
+    sparse: Vec<Option<usize>>,
+}
+
+impl<V> EntitySparseSet<V> {
+    fn new() -> Self {
+        EntitySparseSet {
+            dense_entities: Vec::new(),
+            dense_values: Vec::new(),
+            sparse: Vec::new(),
+        }
+    }
+
+    /// Look up an entity's dense index, rejecting stale IDs that share its sparse index
+    fn get_dense_index(&self, entity_id: &EntityID) -> Option<usize> {
+        let index = self.sparse.get(entity_id.get_index()).copied().flatten()?;
+        if self.dense_entities[index] == *entity_id {
+            Some(index)
+        } else {
+            None
+        }
+    }
+
+    fn contains(&self, entity_id: &EntityID) -> bool {
+        self.get_dense_index(entity_id).is_some()
+    }
+
+    fn get(&self, entity_id: &EntityID) -> Option<&V> {
+        let index = self.get_dense_index(entity_id)?;
+        self.dense_values.get(index)
+    }
+
+    fn insert(&mut self, entity_id: EntityID, value: V) {
+        if let Some(index) = self.get_dense_index(&entity_id) {
+            self.dense_values[index] = value;
+            return;
+        }
+
+        let sparse_index = entity_id.get_index();
+        if sparse_index >= self.sparse.len() {
+            self.sparse.resize(sparse_index + 1, None);
+        }
+
+        self.sparse[sparse_index] = Some(self.dense_entities.len());
+        self.dense_entities.push(entity_id);
+        self.dense_values.push(value);
+    }
+
+    /// Swap-remove the given entity, patching the sparse index of the entity moved into its place
+    fn remove(&mut self, entity_id: &EntityID) -> Option<V> {
+        let index = self.get_dense_index(entity_id)?;
+        self.sparse[entity_id.get_index()] = None;
+
+        self.dense_entities.swap_remove(index);
+        let value = self.dense_values.swap_remove(index);
+        if let Some(moved_entity) = self.dense_entities.get(index) {
+            self.sparse[moved_entity.get_index()] = Some(index);
+        }
+
+        Some(value)
+    }
+
+    fn len(&self) -> usize {
+        self.dense_entities.len()
+    }
+}
+
+/// Keeps a sparse set of entities per component type
+///
+/// Adding or removing a component is O(1), and lookups by component only visit entities that have it.
+pub struct SparseSetDirectory {
+    entity_allocator: EntityAllocator,
+    components: HashSet<ComponentID>,
+    entity_components: HashMap<ComponentID, EntitySparseSet<ComponentDataID>>,
+}
+
+impl SparseSetDirectory {
+    pub fn new() -> Self {
+        SparseSetDirectory {
+            entity_allocator: EntityAllocator::new(),
+            components: HashSet::new(),
+            entity_components: HashMap::new(),
+        }
+    }
+}
+
+impl Default for SparseSetDirectory {
+    fn default() -> Self {
+        SparseSetDirectory::new()
+    }
+}
+
+impl EntityComponentDirectory for SparseSetDirectory {
+    // CREATE
+    fn create_entity(&mut self) -> Result<EntityID, EcsError> {
+        let entity_id: EntityID = self.entity_allocator.allocate()?;
+
+        Ok(entity_id)
+    }
+
+    fn create_reserved_entity(&mut self, entity_id: EntityID) -> Result<(), EcsError> {
+        self.entity_allocator.claim(entity_id)
+    }
+
+    fn get_entity_reserver(&self) -> EntityReserver {
+        self.entity_allocator.get_reserver()
+    }
+
+    fn get_entity_allocator(&self) -> &EntityAllocator {
+        &self.entity_allocator
+    }
+
+    fn restore_entity_allocator(&mut self, entity_allocator: &EntityAllocator) {
+        self.entity_allocator.restore(entity_allocator);
+    }
+
+    // INSERT
+    fn insert_component<T: ComponentTrait + 'static>(&mut self) -> Result<ComponentID, EcsError> {
+        let component_id = ComponentID::get::<T>();
+        self.components.insert(component_id);
+
+        Ok(component_id)
+    }
+
+    fn insert_entity_component<T>(
+        &mut self,
+        entity_id: &EntityID,
+        component_data_id: ComponentDataID,
+    ) -> Result<ComponentDataID, EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        if !self.entity_allocator.is_alive(entity_id) {
+            return Err(EcsError::StaleEntity(*entity_id));
+        }
+
+        self.entity_components
+            .entry(ComponentID::get::<T>())
+            .or_insert_with(EntitySparseSet::new)
+            .insert(*entity_id, component_data_id);
+
+        Ok(component_data_id)
+    }
+
+    // DESTROY
+    fn destroy_entity(&mut self, entity_id: EntityID) -> Result<(), EcsError> {
+        if !self.entity_allocator.free(&entity_id) {
+            return Err(EcsError::StaleEntity(entity_id));
+        }
+
+        for entity_components in self.entity_components.values_mut() {
+            entity_components.remove(&entity_id);
+        }
+
+        Ok(())
+    }
+
+    fn destroy_component<T: ComponentTrait + 'static>(&mut self) -> Result<(), EcsError> {
+        let component_id = ComponentID::get::<T>();
+        if self.components.remove(&component_id) {
+            Ok(())
+        } else {
+            Err(EcsError::UnknownComponent(component_id))
+        }
+    }
+
+    fn destroy_entity_component(
+        &mut self,
+        entity_id: &EntityID,
+        component_id: &ComponentID,
+    ) -> Result<(), EcsError> {
+        if !self.entity_allocator.is_alive(entity_id) {
+            return Err(EcsError::StaleEntity(*entity_id));
+        }
+
+        match self
+            .entity_components
+            .get_mut(component_id)
+            .and_then(|entity_components| entity_components.remove(entity_id))
+        {
+            Some(_) => Ok(()),
+            None => Err(EcsError::MissingComponent {
+                entity_id: *entity_id,
+                component_id: *component_id,
+            }),
+        }
+    }
+
+    // EXIST
+    fn is_alive(&self, entity_id: &EntityID) -> bool {
+        self.entity_allocator.is_alive(entity_id)
+    }
+
+    fn is_valid_component<T: ComponentTrait + 'static>(&self) -> bool {
+        self.components.contains(&ComponentID::get::<T>())
+    }
+
+    fn entity_has_component_by_id(&self, entity_id: &EntityID, component_id: &ComponentID) -> bool {
+        match self.entity_components.get(component_id) {
+            Some(entity_components) => entity_components.contains(entity_id),
+            None => false,
+        }
+    }
+
+    // GET
+    fn get_entity_by_predicate(&self, predicate: impl Fn(&EntityID) -> bool) -> Option<EntityID> {
+        self.entity_allocator.iter().find(predicate)
+    }
+
+    fn get_entities_by_predicate(&self, predicate: impl Fn(&EntityID) -> bool) -> Vec<EntityID> {
+        self.entity_allocator.iter().filter(predicate).collect()
+    }
+
+    fn get_entities_with_components(
+        &self,
+        component_ids: &[ComponentID],
+        predicate: impl Fn(&EntityID) -> bool,
+    ) -> Vec<EntityID> {
+        if component_ids.is_empty() {
+            return self.get_entities_by_predicate(predicate);
+        }
+
+        let entity_components = component_ids
+            .iter()
+            .map(|component_id| self.entity_components.get(component_id))
+            .collect::<Option<Vec<_>>>();
+
+        let entity_components = match entity_components {
+            Some(entity_components) => entity_components,
+            None => return Vec::new(),
+        };
+
+        // Iterate the smallest set, and check membership of the rest
+        let smallest = entity_components
+            .iter()
+            .min_by_key(|entity_components| entity_components.len())
+            .expect("Empty component set");
+
+        smallest
+            .dense_entities
+            .iter()
+            .copied()
+            .filter(|entity_id| {
+                entity_components
+                    .iter()
+                    .all(|entity_components| entity_components.contains(entity_id))
+                    && predicate(entity_id)
+            })
+            .collect()
+    }
+
+    fn get_components_by_predicate(
+        &self,
+        predicate: impl Fn(&ComponentID) -> bool,
+    ) -> Vec<ComponentID> {
+        self.components.iter().copied().filter(predicate).collect()
+    }
+
+    fn get_entity_component_data_id(
+        &self,
+        entity_id: &EntityID,
+        component_id: &ComponentID,
+    ) -> Result<ComponentDataID, EcsError> {
+        if !self.entity_allocator.is_alive(entity_id) {
+            return Err(EcsError::StaleEntity(*entity_id));
+        }
+
+        match self
+            .entity_components
+            .get(component_id)
+            .and_then(|entity_components| entity_components.get(entity_id))
+        {
+            Some(component_data_id) => Ok(*component_data_id),
+            None => Err(EcsError::MissingComponent {
+                entity_id: *entity_id,
+                component_id: *component_id,
+            }),
+        }
+    }
+
+    fn get_entity_component_data(
+        &self,
+        entity_id: &EntityID,
+    ) -> Result<HashMap<ComponentID, ComponentDataID>, EcsError> {
+        if !self.entity_allocator.is_alive(entity_id) {
+            return Err(EcsError::StaleEntity(*entity_id));
+        }
+
+        Ok(self
+            .entity_components
+            .iter()
+            .filter_map(|(component_id, entity_components)| {
+                entity_components
+                    .get(entity_id)
+                    .map(|component_data_id| (*component_id, *component_data_id))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Name, Position, Velocity},
+        entity_component_system::{
+            ComponentHooks, ComponentID, EcsError, EntityComponentDirectory,
+            SparseSetComponentStorage, SparseSetDirectory, SystemInterface,
+        },
+        primitive_types::Vector2I,
+    };
+
+    #[test]
+    fn sparse_set_directory() {
+        let mut component_storage = SparseSetComponentStorage::new();
+        let mut entity_component_directory = SparseSetDirectory::new();
+        let component_hooks = ComponentHooks::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let entities = (0..4)
+            .map(|i| {
+                let entity_id = db.create_entity(None).unwrap();
+                db.insert_entity_component(entity_id, Position(Vector2I(i, i)))
+                    .unwrap();
+                if i % 2 == 0 {
+                    db.insert_entity_component(entity_id, Velocity::default())
+                        .unwrap();
+                }
+                entity_id
+            })
+            .collect::<Vec<_>>();
+
+        let with_components = |db: &SystemInterface<_, SparseSetDirectory>,
+                               component_ids: &[ComponentID]| {
+            let mut entities = db
+                .entity_component_directory
+                .get_entities_with_components(component_ids, |_| true);
+            entities.sort();
+            entities
+        };
+
+        let position = ComponentID::get::<Position>();
+        let velocity = ComponentID::get::<Velocity>();
+        let name = ComponentID::get::<Name>();
+
+        assert_eq!(with_components(&db, &[position]), entities);
+        assert_eq!(
+            with_components(&db, &[position, velocity]),
+            vec![entities[0], entities[2]]
+        );
+        assert!(with_components(&db, &[position, name]).is_empty());
+        assert_eq!(with_components(&db, &[]), entities);
+
+        // Removing from the front of a sparse set swaps the last entity into its place
+        db.remove_component_from_entity::<Position>(entities[0])
+            .unwrap();
+        assert_eq!(with_components(&db, &[position]), entities[1..].to_vec());
+        assert_eq!(
+            with_components(&db, &[position, velocity]),
+            vec![entities[2]]
+        );
+        for (i, entity_id) in entities.iter().enumerate().skip(1) {
+            let position = db.get_entity_component::<Position>(*entity_id).unwrap();
+            assert_eq!(**position, Vector2I(i as i64, i as i64));
+        }
+
+        // A recycled entity index doesn't inherit the components of the stale entity that shared it
+        db.destroy_entity(entities[3]).unwrap();
+        let recycled = db.create_entity(None).unwrap();
+        assert_eq!(recycled.get_index(), entities[3].get_index());
+        assert!(!db
+            .entity_component_directory
+            .entity_has_component::<Position>(&recycled));
+        assert_eq!(
+            db.get_entity_component::<Position>(entities[3])
+                .unwrap_err(),
+            EcsError::StaleEntity(entities[3])
+        );
+        assert_eq!(
+            with_components(&db, &[]),
+            vec![entities[0], entities[1], entities[2], recycled]
+        );
+        assert!(!with_components(&db, &[position]).contains(&recycled));
+    }
+}
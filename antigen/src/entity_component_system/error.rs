@@ -0,0 +1,271 @@
+use std::fmt::Display;
+
+use super::{ComponentDataID, ComponentID, EntityID, SystemID, SystemLabel, SystemStage};
+
+/// Error returned by entity component system operations
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum EcsError {
+    /// The entity has been destroyed, or was never created
+    StaleEntity(EntityID),
+    /// The entity is alive, but doesn't have the requested component
+    MissingComponent {
+        entity_id: EntityID,
+        component_id: ComponentID,
+    },
+    /// The component type hasn't been registered
+    UnknownComponent(ComponentID),
+    /// Component storage has no data for the given ID
+    MissingComponentData(ComponentDataID),
+    /// Component data exists, but isn't of the requested type
+    Downcast {
+        component_data_id: ComponentDataID,
+        component_id: ComponentID,
+    },
+    /// A query or access set would alias a mutable reference to the given component type
+    AliasedAccess(ComponentID),
+    /// A concurrently running system accessed a component type its access set doesn't declare
+    UndeclaredAccess(ComponentID),
+    /// The operation needs exclusive access to the world, which concurrently running systems don't have
+    ExclusiveAccess,
+    /// A packed ID has run out of bits to store the given index
+    IDCapacity { id_type: &'static str, index: usize },
+    /// The system isn't present in storage
+    NoSuchSystem(SystemID),
+    /// A system's ordering constraint references a label no system has
+    UnknownLabel {
+        system_id: SystemID,
+        label: SystemLabel,
+    },
+    /// A system's ordering constraint references the system itself
+    SelfOrdering(SystemID),
+    /// An ordering constraint would run a system before one in an earlier stage
+    StageOrdering {
+        before: (SystemID, SystemStage),
+        after: (SystemID, SystemStage),
+    },
+    /// Ordering constraints form a cycle, listed from its first system back to itself
+    OrderingCycle(Vec<SystemID>),
+    /// A single-entity query matched none or several entities
+    QuerySingle { query: &'static str, count: usize },
+    /// World data couldn't be saved or loaded, with the underlying format's error message
+    Serialization(String),
+}
+
+impl Display for EcsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EcsError::StaleEntity(entity_id) => write!(f, "Stale entity {}", entity_id),
+            EcsError::MissingComponent {
+                entity_id,
+                component_id,
+            } => write!(
+                f,
+                "Entity {} has no component {}",
+                entity_id,
+                component_id.get_name()
+            ),
+            EcsError::UnknownComponent(component_id) => {
+                write!(f, "No such component {}", component_id.get_name())
+            }
+            EcsError::MissingComponentData(component_data_id) => {
+                write!(f, "No such component data {}", component_data_id)
+            }
+            EcsError::Downcast {
+                component_data_id,
+                component_id,
+            } => write!(
+                f,
+                "Failed to downcast component data {} to type {}",
+                component_data_id,
+                component_id.get_name()
+            ),
+            EcsError::AliasedAccess(component_id) => {
+                write!(f, "Conflicting borrows of {}", component_id.get_name())
+            }
+            EcsError::UndeclaredAccess(component_id) => {
+                write!(f, "Undeclared access to {}", component_id.get_name())
+            }
+            EcsError::ExclusiveAccess => write!(
+                f,
+                "Operation requires exclusive world access, queue it through commands instead"
+            ),
+            EcsError::IDCapacity { id_type, index } => {
+                write!(f, "Index {} exceeds {} capacity", index, id_type)
+            }
+            EcsError::NoSuchSystem(system_id) => {
+                write!(f, "No such system {}", system_id.get_name())
+            }
+            EcsError::UnknownLabel { system_id, label } => write!(
+                f,
+                "{} references unknown label \"{}\"",
+                system_id.get_name(),
+                label
+            ),
+            EcsError::SelfOrdering(system_id) => {
+                write!(f, "{} is ordered relative to itself", system_id.get_name())
+            }
+            EcsError::StageOrdering {
+                before: (before_id, before_stage),
+                after: (after_id, after_stage),
+            } => write!(
+                f,
+                "{} ({:?}) can't run before {} ({:?})",
+                before_id.get_name(),
+                before_stage,
+                after_id.get_name(),
+                after_stage
+            ),
+            EcsError::OrderingCycle(system_ids) => write!(
+                f,
+                "Cycle detected: {}",
+                system_ids
+                    .iter()
+                    .map(SystemID::get_name)
+                    .collect::<Vec<String>>()
+                    .join(" -> ")
+            ),
+            EcsError::QuerySingle { query, count } => write!(
+                f,
+                "Expected a single entity matching {}, found {}",
+                query, count
+            ),
+            EcsError::Serialization(message) => write!(f, "Serialization failed: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for EcsError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Name, Position},
+        entity_component_system::{
+            system_storage::HeapSystemStorage, ComponentHooks, ComponentID, ComponentStorage,
+            EcsError, EntityComponentDirectory, EntityID, HeapComponentStorage,
+            SingleThreadedDirectory, SystemBuilder, SystemError, SystemInterface, SystemStorage,
+            SystemTrait,
+        },
+        primitive_types::Vector2I,
+    };
+
+    struct System;
+
+    impl<CS, CD> SystemTrait<CS, CD> for System
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        fn run(&mut self, _: &mut SystemInterface<CS, CD>) -> Result<(), SystemError> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn entity_errors() {
+        let component_hooks = ComponentHooks::new();
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let named_entity = db.create_entity(Some("Named")).unwrap();
+        let entity_id = db.create_entity(None).unwrap();
+        db.insert_entity_component(entity_id, Position(Vector2I(0, 0)))
+            .unwrap();
+
+        assert_eq!(
+            db.get_entity_component::<Name>(entity_id).err(),
+            Some(EcsError::MissingComponent {
+                entity_id,
+                component_id: ComponentID::get::<Name>(),
+            })
+        );
+        assert_eq!(
+            db.remove_component_from_entity::<Name>(entity_id).err(),
+            Some(EcsError::MissingComponent {
+                entity_id,
+                component_id: ComponentID::get::<Name>(),
+            })
+        );
+
+        // Destroyed entities are rejected, even once their index is reused
+        db.destroy_entity(entity_id).unwrap();
+        let reused_entity = db.create_entity(None).unwrap();
+        assert_eq!(reused_entity.get_index(), entity_id.get_index());
+
+        assert_eq!(
+            db.get_entity_component::<Position>(entity_id).err(),
+            Some(EcsError::StaleEntity(entity_id))
+        );
+        assert_eq!(
+            db.insert_entity_component(entity_id, Position(Vector2I(1, 1)))
+                .err(),
+            Some(EcsError::StaleEntity(entity_id))
+        );
+        assert_eq!(
+            db.destroy_entity(entity_id).err(),
+            Some(EcsError::StaleEntity(entity_id))
+        );
+        assert!(db.get_entity_component::<Name>(named_entity).is_ok());
+
+        // Queries can't borrow the same component type mutably more than once
+        assert_eq!(
+            db.query::<(EntityID, &Position, &mut Position)>().err(),
+            Some(EcsError::AliasedAccess(ComponentID::get::<Position>()))
+        );
+        assert_eq!(
+            db.query::<(&mut Name, &mut Name)>().err(),
+            Some(EcsError::AliasedAccess(ComponentID::get::<Name>()))
+        );
+    }
+
+    #[test]
+    fn ordering_errors() {
+        let mut system_storage =
+            HeapSystemStorage::<HeapComponentStorage, SingleThreadedDirectory>::new();
+        let system_id = system_storage.insert_system(System);
+        SystemBuilder::new(&mut system_storage, system_id)
+            .label("System")
+            .after("System");
+
+        assert_eq!(
+            system_storage.get_systems().err(),
+            Some(EcsError::SelfOrdering(system_id))
+        );
+
+        let mut system_storage =
+            HeapSystemStorage::<HeapComponentStorage, SingleThreadedDirectory>::new();
+        let first = system_storage.insert_system(System);
+        let second = system_storage.insert_system(System);
+        SystemBuilder::new(&mut system_storage, first)
+            .label("First")
+            .before("Second");
+        SystemBuilder::new(&mut system_storage, second)
+            .label("Second")
+            .before("First");
+
+        match system_storage.get_systems().err() {
+            Some(EcsError::OrderingCycle(cycle)) => {
+                assert_eq!(cycle.len(), 3);
+                assert_eq!(cycle.first(), cycle.last());
+                assert!(cycle.contains(&first) && cycle.contains(&second));
+            }
+            err => panic!("Expected an ordering cycle, got {:?}", err),
+        }
+
+        // Configuring a system from another storage is reported once the builder is finished
+        let mut other_storage =
+            HeapSystemStorage::<HeapComponentStorage, SingleThreadedDirectory>::new();
+        let other = other_storage.insert_system(System);
+        assert_eq!(
+            SystemBuilder::new(&mut system_storage, other)
+                .label("Other")
+                .finish(),
+            Err(EcsError::NoSuchSystem(other))
+        );
+    }
+}
@@ -5,23 +5,45 @@ pub mod system_runner;
 pub mod system_storage;
 
 mod assemblage;
+mod component_bundle;
+mod component_hooks;
 mod component_storage;
 mod entity_component_directory;
+mod error;
+mod query;
+mod serialization;
+mod snapshot;
+
+pub use assemblage::{AssembledEntity, Assemblage, AssemblageID};
+pub use component_bundle::ComponentBundle;
+pub use component_hooks::{ComponentEvent, ComponentHooks};
+pub use component_storage::{
+    ColumnComponentStorage, ComponentDataID, ComponentStorage, ComponentTicks,
+    HeapComponentStorage, SparseSetComponentStorage,
+};
+pub use entity_component_directory::{
+    ArchetypeDirectory, EntityAllocator, EntityComponentDirectory, EntityReserver,
+    SingleThreadedDirectory, SparseSetDirectory,
+};
+pub use error::EcsError;
+pub use query::{Added, Changed, ComponentAccess, Mut, Query, QueryFilter, With, Without};
+pub use serialization::{
+    EntityIDMap, MapEntities, SceneDescription, SceneInstance, SerializationRegistry,
+};
+pub use snapshot::{SnapshotRegistry, WorldSnapshot};
 
-pub use assemblage::{Assemblage, AssemblageID};
-pub use component_storage::{ComponentDataID, ComponentStorage, HeapComponentStorage};
-pub use entity_component_directory::{EntityComponentDirectory, SingleThreadedDirectory};
-
-pub use system_interface::SystemInterface;
+pub use system_interface::{Commands, SystemInterface};
 pub use system_runner::SystemRunner;
-pub use system_storage::SystemStorage;
+pub use system_storage::{SystemBuilder, SystemLabel, SystemStage, SystemStorage};
 pub use traits::{
     ComponentID, ComponentTrait, EntityID, Scene, SystemError, SystemID, SystemTrait,
 };
 
+use std::path::Path;
+
 use crate::{
     components::SystemProfilingData, systems::ComponentDataDebug, systems::ComponentDebug,
-    systems::EntityDebug, systems::SceneTreeDebug, systems::SystemDebug,
+    systems::EntityDebug, systems::SceneReloader, systems::SceneTreeDebug, systems::SystemDebug,
 };
 
 pub struct EntityComponentSystem<CS, CD, SS, SR>
@@ -33,8 +55,12 @@ where
 {
     pub component_storage: CS,
     pub entity_component_directory: CD,
+    pub component_hooks: ComponentHooks<CS, CD>,
+    pub serialization_registry: SerializationRegistry<CS, CD>,
+    pub snapshot_registry: SnapshotRegistry<CS, CD>,
     pub system_storage: SS,
     pub system_runner: SR,
+    change_tick: u64,
 }
 
 impl<'a, CS, CD, SS, SR> EntityComponentSystem<CS, CD, SS, SR>
@@ -49,15 +75,19 @@ where
         entity_component_directory: CD,
         system_storage: SS,
         system_runner: SR,
-    ) -> Result<Self, String>
+    ) -> Result<Self, EcsError>
     where
         SR: SystemRunner + 'static,
     {
         let mut ecs = EntityComponentSystem {
             component_storage,
             entity_component_directory,
+            component_hooks: ComponentHooks::new(),
+            serialization_registry: SerializationRegistry::new(),
+            snapshot_registry: SnapshotRegistry::new(),
             system_storage,
             system_runner,
+            change_tick: 0,
         };
 
         {
@@ -69,6 +99,9 @@ where
             }
         }
 
+        ecs.serialization_registry.register_antigen_components();
+        ecs.snapshot_registry.register_antigen_components();
+
         ecs.push_system(EntityDebug);
         ecs.push_system(SceneTreeDebug);
         ecs.push_system(ComponentDebug);
@@ -78,28 +111,125 @@ where
         Ok(ecs)
     }
 
-    pub fn push_system<T>(&mut self, system: T)
+    /// Insert a system and register its component hooks,
+    /// returning a builder for configuring its stage and ordering constraints
+    pub fn push_system<T>(&mut self, system: T) -> SystemBuilder<'_, SS, CS, CD>
     where
         T: SystemTrait<CS, CD> + 'static,
     {
-        self.system_storage.insert_system(system);
+        system.register_component_hooks(&mut self.component_hooks);
+        let system_id = self.system_storage.insert_system(system);
+        SystemBuilder::new(&mut self.system_storage, system_id)
     }
 
-    pub fn run(&'a mut self) -> Result<(), SystemError> {
+    pub fn run(&mut self) -> Result<(), SystemError> {
         let mut entity_component_database = SystemInterface::new(
             &mut self.component_storage,
             &mut self.entity_component_directory,
+            &self.component_hooks,
         );
+        entity_component_database.set_change_ticks(self.change_tick, 0);
 
-        self.system_runner
-            .run(&mut self.system_storage, &mut entity_component_database)
+        let result = self
+            .system_runner
+            .run(&mut self.system_storage, &mut entity_component_database);
+
+        self.change_tick = entity_component_database.get_change_tick();
+        result
     }
 
+    /// Returns an interface for modifying the world outside of a system,
+    /// with changes recorded at a fresh tick so every system sees them on its next run
     pub fn get_system_interface(&'a mut self) -> SystemInterface<CS, CD> {
-        SystemInterface::new(
+        self.change_tick += 1;
+
+        let mut entity_component_database = SystemInterface::new(
+            &mut self.component_storage,
+            &mut self.entity_component_directory,
+            &self.component_hooks,
+        );
+        entity_component_database.set_change_ticks(self.change_tick, 0);
+        entity_component_database
+    }
+
+    /// Save every entity and its registered components to RON
+    pub fn save_world(&mut self) -> Result<String, EcsError> {
+        let entity_component_database = SystemInterface::new(
+            &mut self.component_storage,
+            &mut self.entity_component_directory,
+            &self.component_hooks,
+        );
+
+        self.serialization_registry
+            .save_ron(&entity_component_database)
+    }
+
+    /// Load entities saved by `save_world` alongside any existing entities,
+    /// returning the mapping from saved entity IDs to their newly-created counterparts
+    pub fn load_world(&mut self, ron: &str) -> Result<EntityIDMap, EcsError> {
+        self.change_tick += 1;
+
+        let mut entity_component_database = SystemInterface::new(
             &mut self.component_storage,
             &mut self.entity_component_directory,
-        )
+            &self.component_hooks,
+        );
+        entity_component_database.set_change_ticks(self.change_tick, 0);
+
+        let entity_id_map = self
+            .serialization_registry
+            .load_ron(&mut entity_component_database, ron)?;
+        entity_component_database.apply_commands()?;
+
+        Ok(entity_id_map)
+    }
+
+    /// Spawn the entities of a scene file, then push a SceneReloader in the input stage
+    /// to keep them in sync with it as it changes on disk
+    pub fn load_scene_file<P>(
+        &mut self,
+        path: P,
+        serialization_registry: SerializationRegistry<CS, CD>,
+    ) -> Result<SystemBuilder<'_, SS, CS, CD>, EcsError>
+    where
+        P: AsRef<Path>,
+    {
+        let mut scene_reloader = SceneReloader::new(path, serialization_registry);
+
+        {
+            let mut entity_component_database = self.get_system_interface();
+            scene_reloader.reload(&mut entity_component_database)?;
+            entity_component_database.apply_commands()?;
+        }
+
+        Ok(self.push_system(scene_reloader).stage(SystemStage::Input))
+    }
+
+    /// Clone every entity and its snapshot-registered components
+    pub fn take_snapshot(&mut self) -> Result<WorldSnapshot<CS, CD>, EcsError> {
+        let entity_component_database = SystemInterface::new(
+            &mut self.component_storage,
+            &mut self.entity_component_directory,
+            &self.component_hooks,
+        );
+
+        self.snapshot_registry
+            .take_snapshot(&entity_component_database)
+    }
+
+    /// Roll the world back to a snapshot taken by `take_snapshot`
+    pub fn restore_snapshot(&mut self, snapshot: &WorldSnapshot<CS, CD>) -> Result<(), EcsError> {
+        self.change_tick += 1;
+
+        let mut entity_component_database = SystemInterface::new(
+            &mut self.component_storage,
+            &mut self.entity_component_directory,
+            &self.component_hooks,
+        );
+        entity_component_database.set_change_ticks(self.change_tick, 0);
+
+        snapshot.restore(&mut entity_component_database)?;
+        entity_component_database.apply_commands()
     }
 }
 
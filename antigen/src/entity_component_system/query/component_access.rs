@@ -0,0 +1,116 @@
+use crate::entity_component_system::{ComponentID, ComponentTrait, EcsError};
+
+/// The set of component types read and written by a query or system
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ComponentAccess {
+    reads: Vec<ComponentID>,
+    writes: Vec<ComponentID>,
+}
+
+impl ComponentAccess {
+    pub fn new() -> Self {
+        ComponentAccess::default()
+    }
+
+    pub fn get_reads(&self) -> &[ComponentID] {
+        &self.reads
+    }
+
+    pub fn get_writes(&self) -> &[ComponentID] {
+        &self.writes
+    }
+
+    pub fn add_read<T>(&mut self) -> Result<(), EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        self.add_read_by_id(ComponentID::get::<T>())
+    }
+
+    pub fn add_write<T>(&mut self) -> Result<(), EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        self.add_write_by_id(ComponentID::get::<T>())
+    }
+
+    /// Declare a read of a component type, unless it is already declared as written
+    pub fn with_read<T>(mut self) -> Self
+    where
+        T: ComponentTrait + 'static,
+    {
+        let component_id = ComponentID::get::<T>();
+        if !self.writes.contains(&component_id) && !self.reads.contains(&component_id) {
+            self.reads.push(component_id);
+        }
+        self
+    }
+
+    /// Declare a write of a component type, superseding any declared read
+    pub fn with_write<T>(mut self) -> Self
+    where
+        T: ComponentTrait + 'static,
+    {
+        let component_id = ComponentID::get::<T>();
+        self.reads.retain(|read| *read != component_id);
+        if !self.writes.contains(&component_id) {
+            self.writes.push(component_id);
+        }
+        self
+    }
+
+    /// Register a shared reference to a component type, failing if it is already mutably borrowed
+    pub fn add_read_by_id(&mut self, component_id: ComponentID) -> Result<(), EcsError> {
+        if self.writes.contains(&component_id) {
+            return Err(EcsError::AliasedAccess(component_id));
+        }
+
+        if !self.reads.contains(&component_id) {
+            self.reads.push(component_id);
+        }
+
+        Ok(())
+    }
+
+    /// Register a mutable reference to a component type, failing if it is already borrowed
+    pub fn add_write_by_id(&mut self, component_id: ComponentID) -> Result<(), EcsError> {
+        if self.writes.contains(&component_id) || self.reads.contains(&component_id) {
+            return Err(EcsError::AliasedAccess(component_id));
+        }
+
+        self.writes.push(component_id);
+
+        Ok(())
+    }
+
+    /// Returns true if neither access set writes to a component the other reads or writes
+    pub fn is_compatible(&self, other: &ComponentAccess) -> bool {
+        !self.writes.iter().any(|component_id| {
+            other.writes.contains(component_id) || other.reads.contains(component_id)
+        }) && !other
+            .writes
+            .iter()
+            .any(|component_id| self.reads.contains(component_id))
+    }
+
+    /// Fail if this access set reads or writes a component type the declared set doesn't cover
+    ///
+    /// A declared write covers reads of the same component type.
+    pub fn check_covered_by(&self, declared: &ComponentAccess) -> Result<(), EcsError> {
+        if let Some(component_id) = self.reads.iter().find(|component_id| {
+            !declared.reads.contains(component_id) && !declared.writes.contains(component_id)
+        }) {
+            return Err(EcsError::UndeclaredAccess(*component_id));
+        }
+
+        if let Some(component_id) = self
+            .writes
+            .iter()
+            .find(|component_id| !declared.writes.contains(component_id))
+        {
+            return Err(EcsError::UndeclaredAccess(*component_id));
+        }
+
+        Ok(())
+    }
+}
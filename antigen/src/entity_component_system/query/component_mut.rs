@@ -0,0 +1,44 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::entity_component_system::ComponentTicks;
+
+/// Mutable access to a piece of component data, which only marks it as changed once written through
+///
+/// Returned by SystemInterface::get_entity_component_mut and &mut T queries,
+/// so that borrowing data mutably without modifying it doesn't trip Changed filters.
+/// Writes through it don't fire component hooks, which only see data being added, replaced or removed.
+#[derive(Debug)]
+pub struct Mut<'a, T> {
+    component_data: &'a mut T,
+    component_ticks: &'a mut ComponentTicks,
+    change_tick: u64,
+}
+
+impl<'a, T> Mut<'a, T> {
+    pub fn new(
+        component_data: &'a mut T,
+        component_ticks: &'a mut ComponentTicks,
+        change_tick: u64,
+    ) -> Self {
+        Mut {
+            component_data,
+            component_ticks,
+            change_tick,
+        }
+    }
+}
+
+impl<'a, T> Deref for Mut<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        self.component_data
+    }
+}
+
+impl<'a, T> DerefMut for Mut<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.component_ticks.set_changed(self.change_tick);
+        self.component_data
+    }
+}
@@ -0,0 +1,393 @@
+mod component_access;
+mod component_mut;
+mod query_filter;
+
+pub use component_access::ComponentAccess;
+pub use component_mut::Mut;
+pub use query_filter::{Added, Changed, QueryFilter, With, Without};
+
+use crate::entity_component_system::{
+    ComponentID, ComponentStorage, ComponentTrait, EcsError, EntityComponentDirectory, EntityID,
+};
+
+/// A typed view over the components of a single entity, fetched by SystemInterface::query
+///
+/// Implemented for EntityID, &T, &mut T, Option<Q> and tuples of queries.
+/// &mut T queries fetch a Mut<T>, which marks data as changed when it's written through.
+pub trait Query<'a> {
+    type Item;
+
+    /// Register the component types read and written by this query
+    fn access(access: &mut ComponentAccess) -> Result<(), EcsError>;
+
+    /// Register the component types an entity must have to match this query
+    fn required_components(_: &mut Vec<ComponentID>) {}
+
+    /// Returns true if the given entity has every component this query requires
+    fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory;
+
+    /// Fetch this query's data for the given entity, with mutable fetches marked as changed at change_tick once written
+    ///
+    /// # Safety
+    ///
+    /// The caller must have validated this query's access via Query::access,
+    /// and must ensure that nothing else references component data this query writes for as long as 'a.
+    unsafe fn fetch<CS, CD>(
+        component_storage: &'a CS,
+        entity_component_directory: &'a CD,
+        entity_id: EntityID,
+        change_tick: u64,
+    ) -> Result<Self::Item, EcsError>
+    where
+        CS: ComponentStorage + 'a,
+        CD: EntityComponentDirectory;
+}
+
+impl<'a> Query<'a> for EntityID {
+    type Item = EntityID;
+
+    fn access(_: &mut ComponentAccess) -> Result<(), EcsError> {
+        Ok(())
+    }
+
+    fn matches<CD>(_: &CD, _: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory,
+    {
+        true
+    }
+
+    unsafe fn fetch<CS, CD>(
+        _: &'a CS,
+        _: &'a CD,
+        entity_id: EntityID,
+        _: u64,
+    ) -> Result<EntityID, EcsError>
+    where
+        CS: ComponentStorage + 'a,
+        CD: EntityComponentDirectory,
+    {
+        Ok(entity_id)
+    }
+}
+
+impl<'a, T> Query<'a> for &'a T
+where
+    T: ComponentTrait + 'static,
+{
+    type Item = &'a T;
+
+    fn access(access: &mut ComponentAccess) -> Result<(), EcsError> {
+        access.add_read::<T>()
+    }
+
+    fn required_components(component_ids: &mut Vec<ComponentID>) {
+        component_ids.push(ComponentID::get::<T>());
+    }
+
+    fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory,
+    {
+        entity_component_directory.entity_has_component::<T>(entity_id)
+    }
+
+    unsafe fn fetch<CS, CD>(
+        component_storage: &'a CS,
+        entity_component_directory: &'a CD,
+        entity_id: EntityID,
+        _: u64,
+    ) -> Result<&'a T, EcsError>
+    where
+        CS: ComponentStorage + 'a,
+        CD: EntityComponentDirectory,
+    {
+        let component_data_id = entity_component_directory
+            .get_entity_component_data_id(&entity_id, &ComponentID::get::<T>())?;
+
+        component_storage.get_component_data::<T>(&component_data_id)
+    }
+}
+
+impl<'a, T> Query<'a> for &'a mut T
+where
+    T: ComponentTrait + 'static,
+{
+    type Item = Mut<'a, T>;
+
+    fn access(access: &mut ComponentAccess) -> Result<(), EcsError> {
+        access.add_write::<T>()
+    }
+
+    fn required_components(component_ids: &mut Vec<ComponentID>) {
+        component_ids.push(ComponentID::get::<T>());
+    }
+
+    fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory,
+    {
+        entity_component_directory.entity_has_component::<T>(entity_id)
+    }
+
+    unsafe fn fetch<CS, CD>(
+        component_storage: &'a CS,
+        entity_component_directory: &'a CD,
+        entity_id: EntityID,
+        change_tick: u64,
+    ) -> Result<Mut<'a, T>, EcsError>
+    where
+        CS: ComponentStorage + 'a,
+        CD: EntityComponentDirectory,
+    {
+        let component_data_id = entity_component_directory
+            .get_entity_component_data_id(&entity_id, &ComponentID::get::<T>())?;
+
+        let (component_data, component_ticks) =
+            component_storage.get_component_data_ptr::<T>(&component_data_id)?;
+
+        Ok(Mut::new(
+            &mut *component_data,
+            &mut *component_ticks,
+            change_tick,
+        ))
+    }
+}
+
+impl<'a, Q> Query<'a> for Option<Q>
+where
+    Q: Query<'a>,
+{
+    type Item = Option<Q::Item>;
+
+    fn access(access: &mut ComponentAccess) -> Result<(), EcsError> {
+        Q::access(access)
+    }
+
+    fn matches<CD>(_: &CD, _: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory,
+    {
+        true
+    }
+
+    unsafe fn fetch<CS, CD>(
+        component_storage: &'a CS,
+        entity_component_directory: &'a CD,
+        entity_id: EntityID,
+        change_tick: u64,
+    ) -> Result<Option<Q::Item>, EcsError>
+    where
+        CS: ComponentStorage + 'a,
+        CD: EntityComponentDirectory,
+    {
+        if Q::matches(entity_component_directory, &entity_id) {
+            Ok(Some(Q::fetch(
+                component_storage,
+                entity_component_directory,
+                entity_id,
+                change_tick,
+            )?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+macro_rules! impl_query_tuple {
+    ($($query:ident),*) => {
+        impl<'a, $($query),*> Query<'a> for ($($query,)*)
+        where
+            $($query: Query<'a>),*
+        {
+            type Item = ($($query::Item,)*);
+
+            fn access(access: &mut ComponentAccess) -> Result<(), EcsError> {
+                $($query::access(access)?;)*
+                Ok(())
+            }
+
+            fn required_components(component_ids: &mut Vec<ComponentID>) {
+                $($query::required_components(component_ids);)*
+            }
+
+            fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+            where
+                CD: EntityComponentDirectory,
+            {
+                true $(&& $query::matches(entity_component_directory, entity_id))*
+            }
+
+            unsafe fn fetch<CS, CD>(
+                component_storage: &'a CS,
+                entity_component_directory: &'a CD,
+                entity_id: EntityID,
+                change_tick: u64,
+            ) -> Result<Self::Item, EcsError>
+            where
+                CS: ComponentStorage + 'a,
+                CD: EntityComponentDirectory,
+            {
+                Ok(($($query::fetch(
+                    component_storage,
+                    entity_component_directory,
+                    entity_id,
+                    change_tick,
+                )?,)*))
+            }
+        }
+    };
+}
+
+impl_query_tuple!(A);
+impl_query_tuple!(A, B);
+impl_query_tuple!(A, B, C);
+impl_query_tuple!(A, B, C, D);
+impl_query_tuple!(A, B, C, D, E);
+impl_query_tuple!(A, B, C, D, E, F);
+impl_query_tuple!(A, B, C, D, E, F, G);
+impl_query_tuple!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Position, Velocity},
+        entity_component_system::{
+            Added, Changed, ComponentHooks, ComponentID, EcsError, EntityID, HeapComponentStorage,
+            SingleThreadedDirectory, SystemInterface, With, Without,
+        },
+        primitive_types::Vector2I,
+    };
+
+    #[test]
+    fn query() {
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let component_hooks = ComponentHooks::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let moving_entity = db.create_entity(None).unwrap();
+        db.insert_entity_component(moving_entity, Position(Vector2I(1, 1)))
+            .unwrap();
+        db.insert_entity_component(moving_entity, Velocity::default())
+            .unwrap();
+
+        let static_entity = db.create_entity(None).unwrap();
+        db.insert_entity_component(static_entity, Position(Vector2I(2, 2)))
+            .unwrap();
+
+        let positions = db.query::<(EntityID, &mut Position)>().unwrap();
+        assert_eq!(positions.len(), 2);
+
+        let moving = db
+            .query::<(EntityID, &Position, Option<&Velocity>)>()
+            .unwrap()
+            .into_iter()
+            .filter(|(_, _, velocity)| velocity.is_some())
+            .map(|(entity_id, _, _)| entity_id)
+            .collect::<Vec<EntityID>>();
+        assert_eq!(moving, vec![moving_entity]);
+
+        let statics = db.query_filtered::<EntityID, Without<Velocity>>().unwrap();
+        assert_eq!(statics, vec![static_entity]);
+
+        assert_eq!(
+            db.query_single_filtered::<EntityID, With<Velocity>>(),
+            Ok(moving_entity)
+        );
+        assert_eq!(
+            db.query_single::<&Position>().err(),
+            Some(EcsError::QuerySingle {
+                query: std::any::type_name::<&Position>(),
+                count: 2,
+            })
+        );
+
+        assert_eq!(
+            db.query::<(&Position, &mut Position)>().err(),
+            Some(EcsError::AliasedAccess(ComponentID::get::<Position>()))
+        );
+        assert!(db.query::<(&mut Position, &mut Position)>().is_err());
+    }
+
+    #[test]
+    fn change_detection() {
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let component_hooks = ComponentHooks::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let first_entity = db.create_entity(None).unwrap();
+        db.insert_entity_component(first_entity, Position::default())
+            .unwrap();
+
+        let second_entity = db.create_entity(None).unwrap();
+        db.insert_entity_component(second_entity, Position::default())
+            .unwrap();
+
+        // Everything is new to a system that hasn't run yet
+        db.set_change_ticks(2, 0);
+        assert_eq!(
+            db.query_filtered::<EntityID, Added<Position>>().unwrap(),
+            vec![first_entity, second_entity]
+        );
+
+        // Writes from another system mark data as changed, but not added
+        db.set_change_ticks(3, 0);
+        **db.get_entity_component_mut::<Position>(second_entity)
+            .unwrap() = Vector2I(1, 1);
+
+        db.set_change_ticks(4, 2);
+        assert!(db
+            .query_filtered::<EntityID, Added<Position>>()
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            db.query_filtered::<EntityID, Changed<Position>>().unwrap(),
+            vec![second_entity]
+        );
+
+        // Mutable queries only mark the entities they write to as changed
+        db.set_change_ticks(5, 3);
+        for (entity_id, mut position) in db.query::<(EntityID, &mut Position)>().unwrap() {
+            if entity_id == first_entity {
+                **position = Vector2I(2, 2);
+            }
+        }
+
+        db.set_change_ticks(6, 4);
+        assert_eq!(
+            db.query_filtered::<EntityID, Changed<Position>>().unwrap(),
+            vec![first_entity]
+        );
+        assert!(db
+            .query_filtered::<EntityID, (Changed<Position>, With<Velocity>)>()
+            .unwrap()
+            .is_empty());
+
+        // Borrowing mutably without writing isn't reported as a change
+        db.set_change_ticks(7, 6);
+        assert_eq!(
+            **db.get_entity_component_mut::<Position>(first_entity)
+                .unwrap(),
+            Vector2I(2, 2)
+        );
+        assert_eq!(db.query::<&mut Position>().unwrap().len(), 2);
+
+        db.set_change_ticks(8, 6);
+        assert!(db
+            .query_filtered::<EntityID, Changed<Position>>()
+            .unwrap()
+            .is_empty());
+    }
+}
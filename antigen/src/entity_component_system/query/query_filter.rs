@@ -0,0 +1,224 @@
+use std::marker::PhantomData;
+
+use crate::entity_component_system::{
+    ComponentAccess, ComponentID, ComponentStorage, ComponentTicks, ComponentTrait,
+    EntityComponentDirectory, EntityID,
+};
+
+/// A predicate over an entity's component layout that restricts which entities a query visits
+pub trait QueryFilter {
+    /// Register the component types an entity must have to pass this filter
+    fn required_components(_: &mut Vec<ComponentID>) {}
+
+    /// Register the component types whose data this filter reads, such as change ticks
+    fn access(access: ComponentAccess) -> ComponentAccess {
+        access
+    }
+
+    fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory;
+
+    /// Returns true if the entity's component data was added or changed as this filter requires,
+    /// relative to the tick the querying system last ran at
+    fn matches_ticks<CS, CD>(
+        _component_storage: &CS,
+        _entity_component_directory: &CD,
+        _entity_id: &EntityID,
+        _last_change_tick: u64,
+    ) -> bool
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        true
+    }
+}
+
+/// Fetch the change ticks of an entity's component of type T
+fn get_component_ticks<'a, T, CS, CD>(
+    component_storage: &'a CS,
+    entity_component_directory: &CD,
+    entity_id: &EntityID,
+) -> Option<&'a ComponentTicks>
+where
+    T: ComponentTrait + 'static,
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    let component_data_id = entity_component_directory
+        .get_entity_component_data_id(entity_id, &ComponentID::get::<T>())
+        .ok()?;
+
+    component_storage
+        .get_component_ticks(&component_data_id)
+        .ok()
+}
+
+/// Only match entities that have a component of type T
+#[derive(Debug, Default, Copy, Clone)]
+pub struct With<T>(PhantomData<T>);
+
+impl<T> QueryFilter for With<T>
+where
+    T: ComponentTrait + 'static,
+{
+    fn required_components(component_ids: &mut Vec<ComponentID>) {
+        component_ids.push(ComponentID::get::<T>());
+    }
+
+    fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory,
+    {
+        entity_component_directory.entity_has_component::<T>(entity_id)
+    }
+}
+
+/// Only match entities that don't have a component of type T
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Without<T>(PhantomData<T>);
+
+impl<T> QueryFilter for Without<T>
+where
+    T: ComponentTrait + 'static,
+{
+    fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory,
+    {
+        !entity_component_directory.entity_has_component::<T>(entity_id)
+    }
+}
+
+/// Only match entities whose component of type T was added since the querying system last ran
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Added<T>(PhantomData<T>);
+
+impl<T> QueryFilter for Added<T>
+where
+    T: ComponentTrait + 'static,
+{
+    fn required_components(component_ids: &mut Vec<ComponentID>) {
+        component_ids.push(ComponentID::get::<T>());
+    }
+
+    fn access(access: ComponentAccess) -> ComponentAccess {
+        access.with_read::<T>()
+    }
+
+    fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory,
+    {
+        entity_component_directory.entity_has_component::<T>(entity_id)
+    }
+
+    fn matches_ticks<CS, CD>(
+        component_storage: &CS,
+        entity_component_directory: &CD,
+        entity_id: &EntityID,
+        last_change_tick: u64,
+    ) -> bool
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        get_component_ticks::<T, _, _>(component_storage, entity_component_directory, entity_id)
+            .is_some_and(|ticks| ticks.is_added(last_change_tick))
+    }
+}
+
+/// Only match entities whose component of type T was added or written to since the querying system last ran
+#[derive(Debug, Default, Copy, Clone)]
+pub struct Changed<T>(PhantomData<T>);
+
+impl<T> QueryFilter for Changed<T>
+where
+    T: ComponentTrait + 'static,
+{
+    fn required_components(component_ids: &mut Vec<ComponentID>) {
+        component_ids.push(ComponentID::get::<T>());
+    }
+
+    fn access(access: ComponentAccess) -> ComponentAccess {
+        access.with_read::<T>()
+    }
+
+    fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+    where
+        CD: EntityComponentDirectory,
+    {
+        entity_component_directory.entity_has_component::<T>(entity_id)
+    }
+
+    fn matches_ticks<CS, CD>(
+        component_storage: &CS,
+        entity_component_directory: &CD,
+        entity_id: &EntityID,
+        last_change_tick: u64,
+    ) -> bool
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        get_component_ticks::<T, _, _>(component_storage, entity_component_directory, entity_id)
+            .is_some_and(|ticks| ticks.is_changed(last_change_tick))
+    }
+}
+
+macro_rules! impl_query_filter_tuple {
+    ($($filter:ident),*) => {
+        impl<$($filter),*> QueryFilter for ($($filter,)*)
+        where
+            $($filter: QueryFilter),*
+        {
+            #[allow(unused_variables)]
+            fn required_components(component_ids: &mut Vec<ComponentID>) {
+                $($filter::required_components(component_ids);)*
+            }
+
+            fn access(access: ComponentAccess) -> ComponentAccess {
+                $(let access = $filter::access(access);)*
+                access
+            }
+
+            #[allow(unused_variables)]
+            fn matches<CD>(entity_component_directory: &CD, entity_id: &EntityID) -> bool
+            where
+                CD: EntityComponentDirectory,
+            {
+                true $(&& $filter::matches(entity_component_directory, entity_id))*
+            }
+
+            #[allow(unused_variables)]
+            fn matches_ticks<CS, CD>(
+                component_storage: &CS,
+                entity_component_directory: &CD,
+                entity_id: &EntityID,
+                last_change_tick: u64,
+            ) -> bool
+            where
+                CS: ComponentStorage,
+                CD: EntityComponentDirectory,
+            {
+                true $(&& $filter::matches_ticks(
+                    component_storage,
+                    entity_component_directory,
+                    entity_id,
+                    last_change_tick,
+                ))*
+            }
+        }
+    };
+}
+
+impl_query_filter_tuple!();
+impl_query_filter_tuple!(A);
+impl_query_filter_tuple!(A, B);
+impl_query_filter_tuple!(A, B, C);
+impl_query_filter_tuple!(A, B, C, D);
+impl_query_filter_tuple!(A, B, C, D, E);
+impl_query_filter_tuple!(A, B, C, D, E, F);
+impl_query_filter_tuple!(A, B, C, D, E, F, G);
+impl_query_filter_tuple!(A, B, C, D, E, F, G, H);
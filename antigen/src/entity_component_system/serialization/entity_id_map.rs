@@ -0,0 +1,47 @@
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+};
+
+use crate::entity_component_system::{EcsError, EntityID};
+
+/// Mapping from the entity IDs recorded in serialized data to the live entities created on load
+#[derive(Debug, Default, Clone)]
+pub struct EntityIDMap(HashMap<EntityID, EntityID>);
+
+impl EntityIDMap {
+    pub fn new() -> Self {
+        EntityIDMap::default()
+    }
+
+    /// Fetch the live counterpart of a serialized entity ID, passing NULL through unchanged
+    pub fn map(&self, entity_id: EntityID) -> Result<EntityID, EcsError> {
+        if entity_id == EntityID::NULL {
+            return Ok(EntityID::NULL);
+        }
+
+        self.0
+            .get(&entity_id)
+            .copied()
+            .ok_or(EcsError::StaleEntity(entity_id))
+    }
+}
+
+impl Deref for EntityIDMap {
+    type Target = HashMap<EntityID, EntityID>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for EntityIDMap {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Trait for components that store entity IDs, which need rewriting when loaded into a different world
+pub trait MapEntities {
+    fn map_entities(&mut self, entity_id_map: &EntityIDMap) -> Result<(), EcsError>;
+}
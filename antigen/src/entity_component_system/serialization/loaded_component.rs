@@ -0,0 +1,95 @@
+use std::marker::PhantomData;
+
+use serde::Serialize;
+
+use crate::entity_component_system::{
+    ComponentID, ComponentStorage, ComponentTrait, EcsError, EntityComponentDirectory, EntityID,
+    SystemInterface,
+};
+
+use super::EntityIDMap;
+
+/// Type-erased component data read from a serialized world or scene
+///
+/// Loaded data acts as a template, so the same value can be inserted into any number of entities.
+pub(super) trait LoadedComponent<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    fn component_id(&self) -> ComponentID;
+
+    /// Serialized form of the data after remapping, used to detect changes between reloads
+    fn fingerprint(&self, entity_id_map: &EntityIDMap) -> Result<String, EcsError>;
+
+    /// Insert a copy of the data into an entity, remapping any entity IDs it stores
+    fn insert(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        entity_id: EntityID,
+        entity_id_map: &EntityIDMap,
+    ) -> Result<(), EcsError>;
+}
+
+/// Component data deserialized in full
+pub(super) struct ComponentValue<T> {
+    pub component_data: T,
+    pub map_entities: fn(&mut T, &EntityIDMap) -> Result<(), EcsError>,
+}
+
+impl<CS, CD, T> LoadedComponent<CS, CD> for ComponentValue<T>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+    T: ComponentTrait + Clone + Serialize + 'static,
+{
+    fn component_id(&self) -> ComponentID {
+        ComponentID::get::<T>()
+    }
+
+    fn fingerprint(&self, entity_id_map: &EntityIDMap) -> Result<String, EcsError> {
+        let mut component_data = self.component_data.clone();
+        (self.map_entities)(&mut component_data, entity_id_map)?;
+        ron::to_string(&component_data).map_err(|err| EcsError::Serialization(err.to_string()))
+    }
+
+    fn insert(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        entity_id: EntityID,
+        entity_id_map: &EntityIDMap,
+    ) -> Result<(), EcsError> {
+        let mut component_data = self.component_data.clone();
+        (self.map_entities)(&mut component_data, entity_id_map)?;
+        db.insert_entity_component(entity_id, component_data)?;
+        Ok(())
+    }
+}
+
+/// Runtime state that isn't stored, recreated from its default value on load
+pub(super) struct DefaultComponent<T>(pub PhantomData<T>);
+
+impl<CS, CD, T> LoadedComponent<CS, CD> for DefaultComponent<T>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+    T: ComponentTrait + Default + 'static,
+{
+    fn component_id(&self) -> ComponentID {
+        ComponentID::get::<T>()
+    }
+
+    fn fingerprint(&self, _: &EntityIDMap) -> Result<String, EcsError> {
+        Ok(String::new())
+    }
+
+    fn insert(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        entity_id: EntityID,
+        _: &EntityIDMap,
+    ) -> Result<(), EcsError> {
+        db.insert_entity_component(entity_id, T::default())?;
+        Ok(())
+    }
+}
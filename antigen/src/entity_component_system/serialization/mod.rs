@@ -0,0 +1,11 @@
+mod entity_id_map;
+mod loaded_component;
+mod scene_description;
+mod scene_instance;
+mod serialization_registry;
+mod world_data;
+
+pub use entity_id_map::{EntityIDMap, MapEntities};
+pub use scene_description::SceneDescription;
+pub use scene_instance::SceneInstance;
+pub use serialization_registry::SerializationRegistry;
@@ -0,0 +1,565 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Formatter,
+    path::Path,
+};
+
+use serde::{
+    de::{DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::entity_component_system::{
+    ComponentStorage, EcsError, EntityComponentDirectory, EntityID, SystemInterface,
+};
+
+use super::{
+    loaded_component::LoadedComponent, world_data::ComponentsSeed, EntityIDMap, SceneInstance,
+    SerializationRegistry,
+};
+
+type LoadedComponents<CS, CD> = Vec<Box<dyn LoadedComponent<CS, CD>>>;
+
+/// Data-driven description of a scene's entities, parsed at runtime using a serialization registry
+///
+/// Scene files are RON documents with named assemblages and a tree of entities:
+///
+/// ```ron
+/// (
+///     assemblages: {
+///         "Player": { "Control": (), "char": '@' },
+///     },
+///     entities: [
+///         (
+///             id: 0,
+///             name: "Main Window",
+///             components: { "Window": (), "Size": ((64, 32)) },
+///             children: [
+///                 (name: "Player", assemblage: "Player", components: { "Position": ((1, 1)) }),
+///             ],
+///         ),
+///     ],
+/// )
+/// ```
+///
+/// Every field of an entity is optional.
+/// Components listed on an entity override those of its assemblage,
+/// children receive a ParentEntity pointing at the enclosing entity,
+/// and `id` values can be referenced by mapped components such as EventTargets.
+pub struct SceneDescription<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    assemblages: HashMap<String, LoadedComponents<CS, CD>>,
+    entities: Vec<SceneEntity<CS, CD>>,
+}
+
+struct SceneEntity<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    id: Option<EntityID>,
+    name: Option<String>,
+    assemblage: Option<String>,
+    components: LoadedComponents<CS, CD>,
+    children: Vec<SceneEntity<CS, CD>>,
+}
+
+impl<CS, CD> SceneDescription<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    pub fn from_ron(
+        serialization_registry: &SerializationRegistry<CS, CD>,
+        ron: &str,
+    ) -> Result<Self, EcsError> {
+        let scene_description = ron::Options::default()
+            .from_str_seed(ron, SceneSeed(serialization_registry))
+            .map_err(|err| EcsError::Serialization(err.to_string()))?;
+
+        scene_description
+            .validate()
+            .map_err(EcsError::Serialization)?;
+
+        Ok(scene_description)
+    }
+
+    pub fn from_file<P>(
+        serialization_registry: &SerializationRegistry<CS, CD>,
+        path: P,
+    ) -> Result<Self, EcsError>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let ron = std::fs::read_to_string(path).map_err(|err| {
+            EcsError::Serialization(format!("Failed to read {}: {}", path.display(), err))
+        })?;
+
+        Self::from_ron(serialization_registry, &ron)
+    }
+
+    /// Check that assemblage references resolve and entity IDs are unique
+    fn validate(&self) -> Result<(), String> {
+        fn validate_entities<CS, CD>(
+            assemblages: &HashMap<String, LoadedComponents<CS, CD>>,
+            entities: &[SceneEntity<CS, CD>],
+            ids: &mut HashSet<EntityID>,
+        ) -> Result<(), String>
+        where
+            CS: ComponentStorage,
+            CD: EntityComponentDirectory,
+        {
+            for entity in entities {
+                if let Some(assemblage) = &entity.assemblage {
+                    if !assemblages.contains_key(assemblage) {
+                        return Err(format!("Unknown assemblage {}", assemblage));
+                    }
+                }
+
+                if let Some(id) = entity.id {
+                    if !ids.insert(id) {
+                        return Err(format!("Duplicate entity id {}", id));
+                    }
+                }
+
+                validate_entities(assemblages, &entity.children, ids)?;
+            }
+
+            Ok(())
+        }
+
+        validate_entities(&self.assemblages, &self.entities, &mut HashSet::new())
+    }
+
+    /// Create the described entities,
+    /// returning a mapping from the IDs given in the description to their live counterparts
+    pub fn instantiate(&self, db: &mut SystemInterface<CS, CD>) -> Result<EntityIDMap, EcsError> {
+        let mut scene_instance = SceneInstance::new();
+        scene_instance.update(self, db)?;
+        Ok(scene_instance.get_entity_id_map().clone())
+    }
+
+    /// Flatten the entity tree in depth-first order,
+    /// merging each entity's components over those of its assemblage
+    pub(super) fn resolve(&self) -> Vec<ResolvedEntity<'_, CS, CD>> {
+        let mut resolved = Vec::new();
+        self.resolve_entities(&self.entities, None, &[], None, &mut resolved);
+        resolved
+    }
+
+    fn resolve_entities<'a>(
+        &'a self,
+        entities: &'a [SceneEntity<CS, CD>],
+        anchor_id: Option<EntityID>,
+        path: &[String],
+        parent: Option<usize>,
+        resolved: &mut Vec<ResolvedEntity<'a, CS, CD>>,
+    ) {
+        let mut sibling_names: HashMap<&str, usize> = HashMap::new();
+        for scene_entity in entities {
+            // Unidentified entities are keyed by their path from the nearest identified ancestor,
+            // with duplicate sibling names told apart by order of appearance
+            let name = scene_entity.name.as_deref().unwrap_or_default();
+            let occurrence = sibling_names.entry(name).or_default();
+            let mut entity_path = path.to_vec();
+            entity_path.push(format!("{}[{}]", name, occurrence));
+            *occurrence += 1;
+
+            let key = match scene_entity.id {
+                Some(id) => SceneEntityKey::Id(id),
+                None => SceneEntityKey::Path(anchor_id, entity_path.clone()),
+            };
+
+            let mut components: Vec<&dyn LoadedComponent<CS, CD>> = Vec::new();
+            let assemblage_components = scene_entity
+                .assemblage
+                .iter()
+                .flat_map(|assemblage| &self.assemblages[assemblage]);
+            for component in assemblage_components.chain(&scene_entity.components) {
+                let component_id = component.component_id();
+                match components
+                    .iter_mut()
+                    .find(|existing| existing.component_id() == component_id)
+                {
+                    Some(existing) => *existing = component.as_ref(),
+                    None => components.push(component.as_ref()),
+                }
+            }
+
+            let index = resolved.len();
+            resolved.push(ResolvedEntity {
+                key,
+                id: scene_entity.id,
+                name: scene_entity.name.as_deref(),
+                parent,
+                components,
+            });
+
+            let (anchor_id, entity_path) = match scene_entity.id {
+                Some(id) => (Some(id), Vec::new()),
+                None => (anchor_id, entity_path),
+            };
+
+            self.resolve_entities(
+                &scene_entity.children,
+                anchor_id,
+                &entity_path,
+                Some(index),
+                resolved,
+            );
+        }
+    }
+}
+
+/// Identity of a scene entity that persists across edits to its description
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(super) enum SceneEntityKey {
+    Id(EntityID),
+    Path(Option<EntityID>, Vec<String>),
+}
+
+/// A scene entity with its assemblage applied and its parent given as an index into the resolved list
+pub(super) struct ResolvedEntity<'a, CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    pub key: SceneEntityKey,
+    pub id: Option<EntityID>,
+    pub name: Option<&'a str>,
+    pub parent: Option<usize>,
+    pub components: Vec<&'a dyn LoadedComponent<CS, CD>>,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum SceneField {
+    Assemblages,
+    Entities,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum SceneEntityField {
+    Id,
+    Name,
+    Assemblage,
+    Components,
+    Children,
+}
+
+struct SceneSeed<'a, CS, CD>(&'a SerializationRegistry<CS, CD>)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static;
+
+impl<'a, 'de, CS, CD> DeserializeSeed<'de> for SceneSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = SceneDescription<CS, CD>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("Scene", &["assemblages", "entities"], self)
+    }
+}
+
+impl<'a, 'de, CS, CD> Visitor<'de> for SceneSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = SceneDescription<CS, CD>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("struct Scene")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut assemblages = HashMap::new();
+        let mut entities = Vec::new();
+        while let Some(key) = map.next_key::<SceneField>()? {
+            match key {
+                SceneField::Assemblages => {
+                    assemblages = map.next_value_seed(AssemblagesSeed(self.0))?
+                }
+                SceneField::Entities => {
+                    entities = map.next_value_seed(SceneEntitiesSeed(self.0))?
+                }
+            }
+        }
+
+        Ok(SceneDescription {
+            assemblages,
+            entities,
+        })
+    }
+}
+
+struct AssemblagesSeed<'a, CS, CD>(&'a SerializationRegistry<CS, CD>)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static;
+
+impl<'a, 'de, CS, CD> DeserializeSeed<'de> for AssemblagesSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = HashMap<String, LoadedComponents<CS, CD>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a, 'de, CS, CD> Visitor<'de> for AssemblagesSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = HashMap<String, LoadedComponents<CS, CD>>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a map of assemblage names to components")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut assemblages = HashMap::new();
+        while let Some(name) = map.next_key::<String>()? {
+            let components = map.next_value_seed(ComponentsSeed(self.0))?;
+            assemblages.insert(name, components);
+        }
+
+        Ok(assemblages)
+    }
+}
+
+struct SceneEntitiesSeed<'a, CS, CD>(&'a SerializationRegistry<CS, CD>)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static;
+
+impl<'a, 'de, CS, CD> DeserializeSeed<'de> for SceneEntitiesSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = Vec<SceneEntity<CS, CD>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a, 'de, CS, CD> Visitor<'de> for SceneEntitiesSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = Vec<SceneEntity<CS, CD>>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a sequence of entities")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut entities = Vec::new();
+        while let Some(entity) = seq.next_element_seed(SceneEntitySeed(self.0))? {
+            entities.push(entity);
+        }
+
+        Ok(entities)
+    }
+}
+
+struct SceneEntitySeed<'a, CS, CD>(&'a SerializationRegistry<CS, CD>)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static;
+
+impl<'a, 'de, CS, CD> DeserializeSeed<'de> for SceneEntitySeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = SceneEntity<CS, CD>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct(
+            "Entity",
+            &["id", "name", "assemblage", "components", "children"],
+            self,
+        )
+    }
+}
+
+impl<'a, 'de, CS, CD> Visitor<'de> for SceneEntitySeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = SceneEntity<CS, CD>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("struct Entity")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut scene_entity = SceneEntity {
+            id: None,
+            name: None,
+            assemblage: None,
+            components: Vec::new(),
+            children: Vec::new(),
+        };
+
+        while let Some(key) = map.next_key::<SceneEntityField>()? {
+            match key {
+                SceneEntityField::Id => scene_entity.id = Some(map.next_value()?),
+                SceneEntityField::Name => scene_entity.name = Some(map.next_value()?),
+                SceneEntityField::Assemblage => scene_entity.assemblage = Some(map.next_value()?),
+                SceneEntityField::Components => {
+                    scene_entity.components = map.next_value_seed(ComponentsSeed(self.0))?
+                }
+                SceneEntityField::Children => {
+                    scene_entity.children = map.next_value_seed(SceneEntitiesSeed(self.0))?
+                }
+            }
+        }
+
+        Ok(scene_entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{EventTargets, Name, ParentEntity, Position, Size},
+        entity_component_system::{
+            ComponentHooks, EntityID, HeapComponentStorage, SceneDescription,
+            SerializationRegistry, SingleThreadedDirectory, SystemInterface,
+        },
+        primitive_types::Vector2I,
+    };
+
+    const SCENE: &str = r#"
+        (
+            assemblages: {
+                "Rect": {
+                    "Position": ((0, 0)),
+                    "Size": ((4, 4)),
+                },
+            },
+            entities: [
+                (
+                    id: 0,
+                    name: "Window",
+                    components: {
+                        "EventTargets": ([1]),
+                    },
+                    children: [
+                        (
+                            id: 1,
+                            name: "Rect",
+                            assemblage: "Rect",
+                            components: {
+                                "Position": ((2, 3)),
+                            },
+                        ),
+                    ],
+                ),
+            ],
+        )
+    "#;
+
+    #[test]
+    fn scene_description() {
+        let mut serialization_registry = SerializationRegistry::new();
+        serialization_registry.register_antigen_components();
+
+        let component_hooks = ComponentHooks::new();
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let scene_description = SceneDescription::from_ron(&serialization_registry, SCENE).unwrap();
+
+        // Descriptions can be instantiated repeatedly, with fresh entities each time
+        let first_map = scene_description.instantiate(&mut db).unwrap();
+        let entity_id_map = scene_description.instantiate(&mut db).unwrap();
+        assert_ne!(first_map.map(EntityID(0)), entity_id_map.map(EntityID(0)));
+
+        let window_entity = entity_id_map.map(EntityID(0)).unwrap();
+        let rect_entity = entity_id_map.map(EntityID(1)).unwrap();
+
+        assert_eq!(
+            **db.get_entity_component::<Name>(window_entity).unwrap(),
+            "Window"
+        );
+        assert_eq!(
+            **db.get_entity_component::<EventTargets>(window_entity)
+                .unwrap(),
+            vec![rect_entity]
+        );
+
+        // Entity components override assemblage components
+        assert_eq!(
+            **db.get_entity_component::<Position>(rect_entity).unwrap(),
+            Vector2I(2, 3)
+        );
+        assert_eq!(
+            **db.get_entity_component::<Size>(rect_entity).unwrap(),
+            Vector2I(4, 4)
+        );
+        assert_eq!(
+            **db.get_entity_component::<ParentEntity>(rect_entity)
+                .unwrap(),
+            window_entity
+        );
+
+        assert!(SceneDescription::from_ron(
+            &serialization_registry,
+            "(entities: [(assemblage: \"Missing\")])"
+        )
+        .is_err());
+        assert!(SceneDescription::from_ron(
+            &serialization_registry,
+            "(entities: [(id: 0), (id: 0)])"
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+
+use crate::{
+    components::{Name, ParentEntity},
+    entity_component_system::{
+        ComponentID, ComponentStorage, EcsError, EntityComponentDirectory, EntityID,
+        SystemInterface,
+    },
+};
+
+use super::{scene_description::SceneEntityKey, EntityIDMap, SceneDescription};
+
+/// Live entities created from a scene description, tracked so later versions of it can be applied as a diff
+///
+/// Updating only touches values that differ from the previously applied description,
+/// so runtime state such as the position of a moving entity survives edits to unrelated parts of the scene.
+#[derive(Debug, Default)]
+pub struct SceneInstance {
+    entities: HashMap<SceneEntityKey, InstanceEntity>,
+    entity_id_map: EntityIDMap,
+}
+
+/// The state last applied to a live entity
+#[derive(Debug)]
+struct InstanceEntity {
+    entity_id: EntityID,
+    name: Option<String>,
+    parent_id: Option<EntityID>,
+    components: HashMap<ComponentID, String>,
+}
+
+impl SceneInstance {
+    pub fn new() -> Self {
+        SceneInstance::default()
+    }
+
+    /// Mapping from the IDs given in the description to their live counterparts
+    pub fn get_entity_id_map(&self) -> &EntityIDMap {
+        &self.entity_id_map
+    }
+
+    /// Bring the live world in line with a scene description
+    ///
+    /// Entities keep their live IDs for as long as they remain in the description,
+    /// new entities are spawned, and entities that were removed from it are despawned.
+    pub fn update<CS, CD>(
+        &mut self,
+        scene_description: &SceneDescription<CS, CD>,
+        db: &mut SystemInterface<CS, CD>,
+    ) -> Result<(), EcsError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        let resolved = scene_description.resolve();
+
+        // Match described entities to live ones, spawning any that are new or were destroyed at runtime
+        let mut previous_entities = std::mem::take(&mut self.entities);
+        let mut instance_entities = Vec::with_capacity(resolved.len());
+        for entity in &resolved {
+            let instance_entity = match previous_entities
+                .remove(&entity.key)
+                .filter(|instance_entity| db.is_alive(&instance_entity.entity_id))
+            {
+                Some(instance_entity) => instance_entity,
+                None => InstanceEntity {
+                    entity_id: db.create_entity(entity.name)?,
+                    name: entity.name.map(Into::into),
+                    parent_id: None,
+                    components: HashMap::new(),
+                },
+            };
+            instance_entities.push(instance_entity);
+        }
+
+        for instance_entity in previous_entities.values() {
+            if db.is_alive(&instance_entity.entity_id) {
+                db.destroy_entity(instance_entity.entity_id)?;
+            }
+        }
+
+        self.entity_id_map = EntityIDMap::new();
+        for (entity, instance_entity) in resolved.iter().zip(&instance_entities) {
+            if let Some(id) = entity.id {
+                self.entity_id_map.insert(id, instance_entity.entity_id);
+            }
+        }
+
+        let entity_ids: Vec<EntityID> = instance_entities
+            .iter()
+            .map(|instance_entity| instance_entity.entity_id)
+            .collect();
+
+        for (entity, mut instance_entity) in resolved.into_iter().zip(instance_entities) {
+            let entity_id = instance_entity.entity_id;
+
+            if instance_entity.name.as_deref() != entity.name {
+                match entity.name {
+                    Some(name) => {
+                        db.insert_entity_component(entity_id, Name(name.into()))?;
+                    }
+                    None => Self::remove_component(db, entity_id, &ComponentID::get::<Name>())?,
+                }
+                instance_entity.name = entity.name.map(Into::into);
+            }
+
+            let mut components = HashMap::new();
+            for component in entity.components {
+                let component_id = component.component_id();
+                let fingerprint = component.fingerprint(&self.entity_id_map)?;
+                if instance_entity.components.get(&component_id) != Some(&fingerprint) {
+                    component.insert(db, entity_id, &self.entity_id_map)?;
+                }
+                components.insert(component_id, fingerprint);
+            }
+
+            for component_id in instance_entity.components.keys() {
+                if !components.contains_key(component_id) {
+                    Self::remove_component(db, entity_id, component_id)?;
+                }
+            }
+            instance_entity.components = components;
+
+            let parent_id = entity.parent.map(|index| entity_ids[index]);
+            if instance_entity.parent_id != parent_id {
+                match parent_id {
+                    Some(parent_id) => {
+                        db.insert_entity_component(entity_id, ParentEntity(parent_id))?;
+                    }
+                    None => {
+                        Self::remove_component(db, entity_id, &ComponentID::get::<ParentEntity>())?
+                    }
+                }
+                instance_entity.parent_id = parent_id;
+            }
+
+            self.entities.insert(entity.key, instance_entity);
+        }
+
+        Ok(())
+    }
+
+    /// Remove a component if the entity still has it, as runtime code may already have done so
+    fn remove_component<CS, CD>(
+        db: &mut SystemInterface<CS, CD>,
+        entity_id: EntityID,
+        component_id: &ComponentID,
+    ) -> Result<(), EcsError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        if db
+            .entity_component_directory
+            .get_entity_component_data_id(&entity_id, component_id)
+            .is_ok()
+        {
+            db.remove_component_id_from_entity(entity_id, component_id)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{ParentEntity, Position, Size},
+        entity_component_system::{
+            ComponentHooks, EntityID, HeapComponentStorage, SceneDescription, SceneInstance,
+            SerializationRegistry, SingleThreadedDirectory, SystemInterface,
+        },
+        primitive_types::Vector2I,
+    };
+
+    const SCENE: &str = r#"
+        (
+            entities: [
+                (
+                    id: 0,
+                    name: "Window",
+                    components: { "Size": ((10, 10)) },
+                    children: [
+                        (name: "Label", components: { "Position": ((1, 1)), "char": 'a' }),
+                        (name: "Box", components: { "Position": ((2, 2)) }),
+                    ],
+                ),
+            ],
+        )
+    "#;
+
+    const EDITED_SCENE: &str = r#"
+        (
+            entities: [
+                (
+                    id: 0,
+                    name: "Window",
+                    components: { "Size": ((20, 20)) },
+                    children: [
+                        (name: "Label", components: { "Position": ((1, 1)) }),
+                        (name: "Button", components: { "Position": ((3, 3)) }),
+                    ],
+                ),
+            ],
+        )
+    "#;
+
+    #[test]
+    fn scene_instance() {
+        let mut serialization_registry = SerializationRegistry::new();
+        serialization_registry.register_antigen_components();
+
+        let component_hooks = ComponentHooks::new();
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let find_entity = |db: &mut SystemInterface<_, _>, position: Vector2I| {
+            db.query::<(EntityID, &Position)>()
+                .unwrap()
+                .into_iter()
+                .find(|(_, entity_position)| ***entity_position == position)
+                .map(|(entity_id, _)| entity_id)
+                .unwrap()
+        };
+
+        let mut scene_instance = SceneInstance::new();
+        let scene_description = SceneDescription::from_ron(&serialization_registry, SCENE).unwrap();
+        scene_instance.update(&scene_description, &mut db).unwrap();
+
+        let window_entity = scene_instance.get_entity_id_map().map(EntityID(0)).unwrap();
+        let label_entity = find_entity(&mut db, Vector2I(1, 1));
+        let box_entity = find_entity(&mut db, Vector2I(2, 2));
+
+        // Runtime changes to values that aren't edited should survive a reload
+        **db.get_entity_component_mut::<Position>(label_entity)
+            .unwrap() = Vector2I(7, 7);
+
+        let scene_description =
+            SceneDescription::from_ron(&serialization_registry, EDITED_SCENE).unwrap();
+        scene_instance.update(&scene_description, &mut db).unwrap();
+
+        assert_eq!(
+            scene_instance.get_entity_id_map().map(EntityID(0)),
+            Ok(window_entity)
+        );
+        assert_eq!(
+            **db.get_entity_component::<Size>(window_entity).unwrap(),
+            Vector2I(20, 20)
+        );
+
+        assert!(db.is_alive(&label_entity));
+        assert_eq!(
+            **db.get_entity_component::<Position>(label_entity).unwrap(),
+            Vector2I(7, 7)
+        );
+        assert!(db.get_entity_component::<char>(label_entity).is_err());
+
+        assert!(!db.is_alive(&box_entity));
+
+        let button_entity = find_entity(&mut db, Vector2I(3, 3));
+        assert_eq!(
+            **db.get_entity_component::<ParentEntity>(button_entity)
+                .unwrap(),
+            window_entity
+        );
+    }
+}
@@ -0,0 +1,548 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    marker::PhantomData,
+};
+
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    ser::Error,
+    Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    components::{
+        Anchors, ChildEntitiesData, Control, DebugComponentDataList, DebugComponentList,
+        DebugEntityList, DebugExclude, DebugSceneTree, DebugSystemList, EventTargets,
+        GlobalPositionData, IntRange, ListData, LocalMousePositionData, Margins, Name,
+        ParentEntity, Position, RichText, Size, TextColor, TextLayout, TextStyle, Velocity, Window,
+        ZIndex,
+    },
+    entity_component_system::{
+        ComponentDataID, ComponentID, ComponentStorage, ComponentTrait, EcsError,
+        EntityComponentDirectory, SystemInterface,
+    },
+    primitive_types::ColorRGBF,
+};
+
+use super::{
+    loaded_component::{ComponentValue, DefaultComponent, LoadedComponent},
+    world_data::{EntityData, LoadedEntity, WorldData, WorldSeed},
+    EntityIDMap, MapEntities,
+};
+
+type SerializeComponent<CS> =
+    for<'a> fn(&'a CS, &ComponentDataID) -> Result<&'a dyn erased_serde::Serialize, EcsError>;
+
+type DeserializeComponent<CS, CD> = Box<
+    dyn Fn(
+            &mut dyn erased_serde::Deserializer,
+        ) -> Result<Box<dyn LoadedComponent<CS, CD>>, erased_serde::Error>
+        + Send
+        + Sync,
+>;
+
+pub(super) struct SerializableComponent<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    pub name: String,
+    pub serialize: SerializeComponent<CS>,
+    pub deserialize: DeserializeComponent<CS, CD>,
+}
+
+fn serialize_component<'a, CS, T>(
+    component_storage: &'a CS,
+    component_data_id: &ComponentDataID,
+) -> Result<&'a dyn erased_serde::Serialize, EcsError>
+where
+    CS: ComponentStorage,
+    T: ComponentTrait + Serialize + 'static,
+{
+    let component_data = component_storage.get_component_data::<T>(component_data_id)?;
+    Ok(component_data)
+}
+
+fn serialize_unit<'a, CS>(
+    _: &'a CS,
+    _: &ComponentDataID,
+) -> Result<&'a dyn erased_serde::Serialize, EcsError> {
+    Ok(&())
+}
+
+/// Opt-in set of component types that can be saved and loaded
+///
+/// Components are keyed by their unqualified type name in serialized data,
+/// and unregistered components are skipped when saving.
+/// Entity IDs are rewritten on load, so components that store them should be registered via `register_mapped`.
+pub struct SerializationRegistry<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    components: HashMap<ComponentID, SerializableComponent<CS, CD>>,
+    component_names: HashMap<String, ComponentID>,
+}
+
+impl<CS, CD> SerializationRegistry<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    pub fn new() -> Self {
+        SerializationRegistry {
+            components: HashMap::new(),
+            component_names: HashMap::new(),
+        }
+    }
+
+    pub fn register<T>(&mut self) -> &mut Self
+    where
+        T: ComponentTrait + Clone + Serialize + DeserializeOwned + 'static,
+    {
+        self.register_value::<T>(|_, _| Ok(()))
+    }
+
+    /// Register a component whose entity IDs should be remapped on load
+    pub fn register_mapped<T>(&mut self) -> &mut Self
+    where
+        T: ComponentTrait + Clone + Serialize + DeserializeOwned + MapEntities + 'static,
+    {
+        self.register_value::<T>(T::map_entities)
+    }
+
+    /// Register a component holding runtime state, which is saved as a unit and recreated from its default value
+    ///
+    /// ex. Window handles and event queues
+    pub fn register_default<T>(&mut self) -> &mut Self
+    where
+        T: ComponentTrait + Default + 'static,
+    {
+        self.insert_component::<T>(
+            serialize_unit::<CS>,
+            Box::new(|deserializer| {
+                erased_serde::deserialize::<IgnoredAny>(deserializer)?;
+                Ok(Box::new(DefaultComponent::<T>(PhantomData)))
+            }),
+        )
+    }
+
+    /// Register the serializable components provided by antigen
+    pub fn register_antigen_components(&mut self) -> &mut Self {
+        self.register::<Name>()
+            .register::<Position>()
+            .register::<Size>()
+            .register::<Velocity>()
+            .register::<Anchors>()
+            .register::<Margins>()
+            .register::<ZIndex>()
+            .register::<Control>()
+            .register::<Window>()
+            .register::<IntRange>()
+            .register::<char>()
+            .register::<String>()
+            .register::<ColorRGBF>()
+            .register::<TextColor>()
+            .register::<TextLayout>()
+            .register::<TextStyle>()
+            .register::<RichText>()
+            .register::<DebugEntityList>()
+            .register::<DebugSceneTree>()
+            .register::<DebugComponentList>()
+            .register::<DebugComponentDataList>()
+            .register::<DebugSystemList>()
+            .register::<DebugExclude>()
+            .register_mapped::<ParentEntity>()
+            .register_mapped::<ChildEntitiesData>()
+            .register_mapped::<ListData>()
+            .register_mapped::<EventTargets>()
+            .register_default::<GlobalPositionData>()
+            .register_default::<LocalMousePositionData>()
+            .register_default::<Vec<String>>()
+    }
+
+    fn register_value<T>(
+        &mut self,
+        map_entities: fn(&mut T, &EntityIDMap) -> Result<(), EcsError>,
+    ) -> &mut Self
+    where
+        T: ComponentTrait + Clone + Serialize + DeserializeOwned + 'static,
+    {
+        self.insert_component::<T>(
+            serialize_component::<CS, T>,
+            Box::new(move |deserializer| {
+                let component_data: T = erased_serde::deserialize(deserializer)?;
+                Ok(Box::new(ComponentValue {
+                    component_data,
+                    map_entities,
+                }))
+            }),
+        )
+    }
+
+    fn insert_component<T>(
+        &mut self,
+        serialize: SerializeComponent<CS>,
+        deserialize: DeserializeComponent<CS, CD>,
+    ) -> &mut Self
+    where
+        T: ComponentTrait + 'static,
+    {
+        let component_id = ComponentID::get::<T>();
+        let name = component_id.get_name();
+
+        if let Some(existing_id) = self.component_names.get(&name) {
+            assert!(
+                *existing_id == component_id,
+                "Component name {} is already registered to {}",
+                name,
+                existing_id
+            );
+        }
+
+        self.component_names.insert(name.clone(), component_id);
+        self.components.insert(
+            component_id,
+            SerializableComponent {
+                name,
+                serialize,
+                deserialize,
+            },
+        );
+
+        self
+    }
+
+    pub fn is_registered(&self, component_id: &ComponentID) -> bool {
+        self.components.contains_key(component_id)
+    }
+
+    pub(super) fn get_serializable_component(
+        &self,
+        name: &str,
+    ) -> Option<&SerializableComponent<CS, CD>> {
+        self.component_names
+            .get(name)
+            .and_then(|component_id| self.components.get(component_id))
+    }
+
+    fn get_world_data<'a>(
+        &'a self,
+        db: &'a SystemInterface<CS, CD>,
+    ) -> Result<WorldData<'a>, EcsError> {
+        let mut entity_ids = db
+            .entity_component_directory
+            .get_entities_by_predicate(|_| true);
+        entity_ids.sort();
+
+        let mut entities = Vec::new();
+        for entity_id in entity_ids {
+            let mut components = BTreeMap::new();
+            for (component_id, component_data_id) in db
+                .entity_component_directory
+                .get_entity_component_data(&entity_id)?
+            {
+                if let Some(serializable_component) = self.components.get(&component_id) {
+                    components.insert(
+                        serializable_component.name.as_str(),
+                        (serializable_component.serialize)(
+                            db.get_component_storage()?,
+                            &component_data_id,
+                        )?,
+                    );
+                }
+            }
+
+            entities.push(EntityData {
+                id: entity_id,
+                components,
+            });
+        }
+
+        Ok(WorldData { entities })
+    }
+
+    /// Create an entity for each loaded entity, then insert its components with remapped entity IDs
+    ///
+    /// If any component fails to load, such as one referencing an entity missing from the data,
+    /// every entity created so far is destroyed again so the world isn't left partially loaded,
+    /// and any commands queued by component hooks in the meantime are discarded.
+    fn load_entities(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        entities: Vec<LoadedEntity<CS, CD>>,
+    ) -> Result<EntityIDMap, EcsError> {
+        let commands = db.commands();
+        let queued_commands = commands.len();
+
+        let mut entity_id_map = EntityIDMap::new();
+        if let Err(err) = Self::insert_entities(db, entities, &mut entity_id_map) {
+            for entity_id in entity_id_map.values() {
+                db.destroy_entity(*entity_id)?;
+            }
+            commands.truncate(queued_commands);
+            return Err(err);
+        }
+
+        Ok(entity_id_map)
+    }
+
+    fn insert_entities(
+        db: &mut SystemInterface<CS, CD>,
+        entities: Vec<LoadedEntity<CS, CD>>,
+        entity_id_map: &mut EntityIDMap,
+    ) -> Result<(), EcsError> {
+        for (entity_id, _) in &entities {
+            entity_id_map.insert(*entity_id, db.create_entity(None)?);
+        }
+
+        for (entity_id, components) in entities {
+            let entity_id = entity_id_map.map(entity_id)?;
+            for component in components {
+                component.insert(db, entity_id, entity_id_map)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Write every entity and its registered components using the given serde serializer
+    pub fn serialize_world<S>(
+        &self,
+        db: &SystemInterface<CS, CD>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.get_world_data(db)
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+
+    /// Read entities written by `serialize_world` into the world alongside any existing entities
+    ///
+    /// Returns the mapping from serialized entity IDs to the newly-created entities.
+    /// Component hooks queue commands as usual, which the caller is responsible for applying.
+    pub fn deserialize_world<'de, D>(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        deserializer: D,
+    ) -> Result<EntityIDMap, EcsError>
+    where
+        D: Deserializer<'de>,
+    {
+        let entities = serde::de::DeserializeSeed::deserialize(WorldSeed(self), deserializer)
+            .map_err(|err| EcsError::Serialization(err.to_string()))?;
+        self.load_entities(db, entities)
+    }
+
+    pub fn save_ron(&self, db: &SystemInterface<CS, CD>) -> Result<String, EcsError> {
+        let world_data = self.get_world_data(db)?;
+        ron::ser::to_string_pretty(&world_data, ron::ser::PrettyConfig::default())
+            .map_err(|err| EcsError::Serialization(err.to_string()))
+    }
+
+    pub fn load_ron(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        ron: &str,
+    ) -> Result<EntityIDMap, EcsError> {
+        let entities = ron::Options::default()
+            .from_str_seed(ron, WorldSeed(self))
+            .map_err(|err| EcsError::Serialization(err.to_string()))?;
+        self.load_entities(db, entities)
+    }
+}
+
+impl<CS, CD> Default for SerializationRegistry<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn default() -> Self {
+        SerializationRegistry::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{ChildEntitiesData, Name, ParentEntity, Position},
+        entity_component_system::{
+            ComponentHooks, EcsError, EntityComponentDirectory, EntityID, HeapComponentStorage,
+            SerializationRegistry, SingleThreadedDirectory, SystemInterface, SystemTrait,
+        },
+        primitive_types::Vector2I,
+        systems::ChildEntities,
+    };
+
+    #[test]
+    fn serialization_registry() {
+        let mut serialization_registry = SerializationRegistry::new();
+        serialization_registry
+            .register::<Name>()
+            .register::<Position>()
+            .register_mapped::<ParentEntity>()
+            .register_mapped::<ChildEntitiesData>();
+
+        let component_hooks = ComponentHooks::new();
+
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let parent_entity = db.create_entity(Some("Parent")).unwrap();
+        let child_entity = db.create_entity(Some("Child")).unwrap();
+        db.insert_entity_component(child_entity, Position(Vector2I(1, 2)))
+            .unwrap();
+        db.insert_entity_component(child_entity, ParentEntity(parent_entity))
+            .unwrap();
+        let mut child_entities = ChildEntitiesData::default();
+        child_entities.push(child_entity);
+        db.insert_entity_component(parent_entity, child_entities)
+            .unwrap();
+
+        let ron = serialization_registry.save_ron(&db).unwrap();
+        let mut json = Vec::new();
+        serialization_registry
+            .serialize_world(&db, &mut serde_json::Serializer::new(&mut json))
+            .unwrap();
+
+        // Load into worlds with an existing entity so saved IDs don't line up with live ones
+        let mut loaded_component_storage = HeapComponentStorage::new();
+        let mut loaded_entity_component_directory = SingleThreadedDirectory::new();
+        let mut loaded_db = SystemInterface::new(
+            &mut loaded_component_storage,
+            &mut loaded_entity_component_directory,
+            &component_hooks,
+        );
+        loaded_db.create_entity(Some("Existing")).unwrap();
+
+        let ron_entity_id_map = serialization_registry
+            .load_ron(&mut loaded_db, &ron)
+            .unwrap();
+        let json_entity_id_map = serialization_registry
+            .deserialize_world(
+                &mut loaded_db,
+                &mut serde_json::Deserializer::from_slice(&json),
+            )
+            .unwrap();
+
+        for entity_id_map in [ron_entity_id_map, json_entity_id_map].iter() {
+            let loaded_parent = entity_id_map.map(parent_entity).unwrap();
+            let loaded_child = entity_id_map.map(child_entity).unwrap();
+            assert_ne!(loaded_parent, parent_entity);
+
+            assert_eq!(
+                **loaded_db
+                    .get_entity_component::<Name>(loaded_child)
+                    .unwrap(),
+                "Child"
+            );
+            assert_eq!(
+                **loaded_db
+                    .get_entity_component::<Position>(loaded_child)
+                    .unwrap(),
+                Vector2I(1, 2)
+            );
+            assert_eq!(
+                **loaded_db
+                    .get_entity_component::<ParentEntity>(loaded_child)
+                    .unwrap(),
+                loaded_parent
+            );
+            assert_eq!(
+                **loaded_db
+                    .get_entity_component::<ChildEntitiesData>(loaded_parent)
+                    .unwrap(),
+                vec![loaded_child]
+            );
+        }
+
+        // Unregistered component names are rejected
+        assert!(serialization_registry
+            .load_ron(
+                &mut loaded_db,
+                "(entities: [(id: 0, components: {\"Unknown\": ()})])"
+            )
+            .is_err());
+        assert_eq!(
+            loaded_db
+                .entity_component_directory
+                .get_entities_by_predicate(|_| true)
+                .len(),
+            5
+        );
+
+        // References to entities missing from the data fail the whole load, leaving nothing behind
+        assert_eq!(
+            serialization_registry
+                .load_ron(
+                    &mut loaded_db,
+                    "(entities: [(id: 0, components: {\"Name\": (\"Orphan\")}), (id: 1, components: {\"ParentEntity\": (7)})])"
+                )
+                .err(),
+            Some(EcsError::StaleEntity(EntityID::from_index_generation(7, 0).unwrap()))
+        );
+        assert_eq!(
+            loaded_db
+                .entity_component_directory
+                .get_entities_by_predicate(|_| true)
+                .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn load_rollback() {
+        let mut serialization_registry = SerializationRegistry::new();
+        serialization_registry
+            .register::<Name>()
+            .register_mapped::<ParentEntity>()
+            .register_mapped::<ChildEntitiesData>();
+
+        let mut component_hooks = ComponentHooks::new();
+        SystemTrait::<HeapComponentStorage, SingleThreadedDirectory>::register_component_hooks(
+            &ChildEntities::new(),
+            &mut component_hooks,
+        );
+
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        // The valid child queues a hook command before the dangling reference fails the load
+        assert_eq!(
+            serialization_registry
+                .load_ron(
+                    &mut db,
+                    "(entities: [(id: 0, components: {\"Name\": (\"Parent\")}), (id: 1, components: {\"ParentEntity\": (0)}), (id: 2, components: {\"ParentEntity\": (7)})])"
+                )
+                .err(),
+            Some(EcsError::StaleEntity(EntityID::from_index_generation(7, 0).unwrap()))
+        );
+
+        // Hook commands for the destroyed entities are discarded along with them
+        assert!(db.commands().is_empty());
+        assert!(db
+            .entity_component_directory
+            .get_entities_by_predicate(|_| true)
+            .is_empty());
+
+        // Recycled IDs aren't touched by stale commands
+        let entity_id = db.create_entity(None).unwrap();
+        db.apply_commands().unwrap();
+        assert!(db
+            .get_entity_component::<ChildEntitiesData>(entity_id)
+            .is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use std::{collections::BTreeMap, fmt::Formatter};
+
+use serde::{
+    de::{DeserializeSeed, Error, MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize,
+};
+
+use crate::entity_component_system::{ComponentStorage, EntityComponentDirectory, EntityID};
+
+use super::loaded_component::LoadedComponent;
+use super::serialization_registry::SerializableComponent;
+use super::SerializationRegistry;
+
+/// Serialized form of a world, borrowing component data from storage
+#[derive(Serialize)]
+#[serde(rename = "World")]
+pub(super) struct WorldData<'a> {
+    pub entities: Vec<EntityData<'a>>,
+}
+
+/// Serialized form of a single entity, keyed by registered component name
+#[derive(Serialize)]
+#[serde(rename = "Entity")]
+pub(super) struct EntityData<'a> {
+    pub id: EntityID,
+    pub components: BTreeMap<&'a str, &'a dyn erased_serde::Serialize>,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum WorldField {
+    Entities,
+}
+
+#[derive(Deserialize)]
+#[serde(field_identifier, rename_all = "lowercase")]
+enum EntityField {
+    Id,
+    Components,
+}
+
+/// Deserialized entity awaiting insertion
+pub(super) type LoadedEntity<CS, CD> = (EntityID, Vec<Box<dyn LoadedComponent<CS, CD>>>);
+
+/// Deserializes WorldData by looking up each component's type in the registry
+pub(super) struct WorldSeed<'a, CS, CD>(pub &'a SerializationRegistry<CS, CD>)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static;
+
+impl<'a, 'de, CS, CD> DeserializeSeed<'de> for WorldSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = Vec<LoadedEntity<CS, CD>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("World", &["entities"], self)
+    }
+}
+
+impl<'a, 'de, CS, CD> Visitor<'de> for WorldSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = Vec<LoadedEntity<CS, CD>>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("struct World")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entities = None;
+        while let Some(key) = map.next_key::<WorldField>()? {
+            match key {
+                WorldField::Entities => entities = Some(map.next_value_seed(EntitiesSeed(self.0))?),
+            }
+        }
+
+        entities.ok_or_else(|| A::Error::missing_field("entities"))
+    }
+}
+
+struct EntitiesSeed<'a, CS, CD>(&'a SerializationRegistry<CS, CD>)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static;
+
+impl<'a, 'de, CS, CD> DeserializeSeed<'de> for EntitiesSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = Vec<LoadedEntity<CS, CD>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'a, 'de, CS, CD> Visitor<'de> for EntitiesSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = Vec<LoadedEntity<CS, CD>>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a sequence of entities")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut entities = Vec::new();
+        while let Some(entity) = seq.next_element_seed(EntitySeed(self.0))? {
+            entities.push(entity);
+        }
+
+        Ok(entities)
+    }
+}
+
+struct EntitySeed<'a, CS, CD>(&'a SerializationRegistry<CS, CD>)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static;
+
+impl<'a, 'de, CS, CD> DeserializeSeed<'de> for EntitySeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = LoadedEntity<CS, CD>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_struct("Entity", &["id", "components"], self)
+    }
+}
+
+impl<'a, 'de, CS, CD> Visitor<'de> for EntitySeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = LoadedEntity<CS, CD>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("struct Entity")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut id = None;
+        let mut components = None;
+        while let Some(key) = map.next_key::<EntityField>()? {
+            match key {
+                EntityField::Id => id = Some(map.next_value::<EntityID>()?),
+                EntityField::Components => {
+                    components = Some(map.next_value_seed(ComponentsSeed(self.0))?)
+                }
+            }
+        }
+
+        let id = id.ok_or_else(|| A::Error::missing_field("id"))?;
+        Ok((id, components.unwrap_or_default()))
+    }
+}
+
+/// Deserializes a map of registered component names to component data
+pub(super) struct ComponentsSeed<'a, CS, CD>(pub &'a SerializationRegistry<CS, CD>)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static;
+
+impl<'a, 'de, CS, CD> DeserializeSeed<'de> for ComponentsSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = Vec<Box<dyn LoadedComponent<CS, CD>>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'a, 'de, CS, CD> Visitor<'de> for ComponentsSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = Vec<Box<dyn LoadedComponent<CS, CD>>>;
+
+    fn expecting(&self, formatter: &mut Formatter) -> std::fmt::Result {
+        formatter.write_str("a map of component names to component data")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut components = Vec::new();
+        while let Some(name) = map.next_key::<String>()? {
+            let serializable_component = self
+                .0
+                .get_serializable_component(&name)
+                .ok_or_else(|| A::Error::custom(format!("Unregistered component {}", name)))?;
+
+            components.push(map.next_value_seed(ComponentSeed(serializable_component))?);
+        }
+
+        Ok(components)
+    }
+}
+
+struct ComponentSeed<'a, CS, CD>(&'a SerializableComponent<CS, CD>)
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static;
+
+impl<'a, 'de, CS, CD> DeserializeSeed<'de> for ComponentSeed<'a, CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    type Value = Box<dyn LoadedComponent<CS, CD>>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut deserializer = <dyn erased_serde::Deserializer>::erase(deserializer);
+        (self.0.deserialize)(&mut deserializer).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,5 @@
+mod snapshot_registry;
+mod world_snapshot;
+
+pub use snapshot_registry::SnapshotRegistry;
+pub use world_snapshot::WorldSnapshot;
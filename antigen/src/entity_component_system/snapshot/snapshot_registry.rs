@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use crate::{
+    components::{
+        Anchors, CPUShader, ChildEntitiesData, Control, DebugComponentDataList, DebugComponentList,
+        DebugEntityList, DebugExclude, DebugSceneTree, DebugSystemList, EventQueue, EventTargets,
+        GlobalPositionData, IntRange, ListData, LocalMousePositionData, Margins, Name,
+        ParentEntity, Position, RichText, Size, TextColor, TextLayout, TextStyle, Timer, Velocity,
+        Window, ZIndex,
+    },
+    core::events::AntigenInputEvent,
+    entity_component_system::{
+        ComponentDataID, ComponentID, ComponentStorage, ComponentTrait, EcsError,
+        EntityComponentDirectory, EntityID, SystemInterface,
+    },
+    primitive_types::ColorRGBF,
+};
+
+use super::{world_snapshot::ComponentSnapshot, WorldSnapshot};
+
+type CloneComponent<CS, CD> =
+    fn(&CS, &ComponentDataID) -> Result<Box<dyn ComponentSnapshot<CS, CD>>, EcsError>;
+
+/// A component value cloned out of storage
+struct ComponentClone<T>(T);
+
+impl<CS, CD, T> ComponentSnapshot<CS, CD> for ComponentClone<T>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+    T: ComponentTrait + Clone + 'static,
+{
+    fn component_id(&self) -> ComponentID {
+        ComponentID::get::<T>()
+    }
+
+    fn insert(
+        &self,
+        db: &mut SystemInterface<CS, CD>,
+        entity_id: EntityID,
+    ) -> Result<(), EcsError> {
+        db.insert_entity_component(entity_id, self.0.clone())?;
+        Ok(())
+    }
+}
+
+fn clone_component<CS, CD, T>(
+    component_storage: &CS,
+    component_data_id: &ComponentDataID,
+) -> Result<Box<dyn ComponentSnapshot<CS, CD>>, EcsError>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+    T: ComponentTrait + Clone + 'static,
+{
+    let component_data = component_storage.get_component_data::<T>(component_data_id)?;
+    Ok(Box::new(ComponentClone(component_data.clone())))
+}
+
+/// Opt-in set of component types that are captured by world snapshots
+///
+/// Unregistered components are left as-is when a snapshot is restored,
+/// which keeps things like window handles and profiling data out of rollback.
+pub struct SnapshotRegistry<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    components: HashMap<ComponentID, CloneComponent<CS, CD>>,
+}
+
+impl<CS, CD> SnapshotRegistry<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    pub fn new() -> Self {
+        SnapshotRegistry {
+            components: HashMap::new(),
+        }
+    }
+
+    pub fn register<T>(&mut self) -> &mut Self
+    where
+        T: ComponentTrait + Clone + 'static,
+    {
+        self.components
+            .insert(ComponentID::get::<T>(), clone_component::<CS, CD, T>);
+        self
+    }
+
+    /// Register the cloneable components provided by antigen
+    pub fn register_antigen_components(&mut self) -> &mut Self {
+        self.register::<Name>()
+            .register::<Position>()
+            .register::<Size>()
+            .register::<Velocity>()
+            .register::<Anchors>()
+            .register::<Margins>()
+            .register::<ZIndex>()
+            .register::<Control>()
+            .register::<Window>()
+            .register::<IntRange>()
+            .register::<char>()
+            .register::<String>()
+            .register::<Vec<String>>()
+            .register::<ColorRGBF>()
+            .register::<TextColor>()
+            .register::<TextLayout>()
+            .register::<TextStyle>()
+            .register::<RichText>()
+            .register::<CPUShader>()
+            .register::<Timer>()
+            .register::<DebugEntityList>()
+            .register::<DebugSceneTree>()
+            .register::<DebugComponentList>()
+            .register::<DebugComponentDataList>()
+            .register::<DebugSystemList>()
+            .register::<DebugExclude>()
+            .register::<ParentEntity>()
+            .register::<ChildEntitiesData>()
+            .register::<ListData>()
+            .register::<EventTargets>()
+            .register::<GlobalPositionData>()
+            .register::<LocalMousePositionData>()
+            .register::<EventQueue<AntigenInputEvent>>()
+    }
+
+    pub fn is_registered(&self, component_id: &ComponentID) -> bool {
+        self.components.contains_key(component_id)
+    }
+
+    /// Clone every live entity and its registered components
+    pub fn take_snapshot(
+        &self,
+        db: &SystemInterface<CS, CD>,
+    ) -> Result<WorldSnapshot<CS, CD>, EcsError> {
+        let entity_allocator = db.entity_component_directory.get_entity_allocator().clone();
+
+        let mut entities = Vec::new();
+        for entity_id in entity_allocator.iter() {
+            let mut components = Vec::new();
+            for (component_id, component_data_id) in db
+                .entity_component_directory
+                .get_entity_component_data(&entity_id)?
+            {
+                if let Some(clone_component) = self.components.get(&component_id) {
+                    components.push(clone_component(
+                        db.get_component_storage()?,
+                        &component_data_id,
+                    )?);
+                }
+            }
+            entities.push((entity_id, components));
+        }
+
+        Ok(WorldSnapshot::new(
+            entity_allocator,
+            self.components.keys().copied().collect(),
+            entities,
+        ))
+    }
+}
+
+impl<CS, CD> Default for SnapshotRegistry<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn default() -> Self {
+        SnapshotRegistry::new()
+    }
+}
@@ -0,0 +1,173 @@
+use std::collections::HashSet;
+
+use crate::entity_component_system::{
+    ComponentID, ComponentStorage, EcsError, EntityAllocator, EntityComponentDirectory, EntityID,
+    SystemInterface,
+};
+
+/// Type-erased component data cloned into a snapshot
+pub(super) trait ComponentSnapshot<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    fn component_id(&self) -> ComponentID;
+
+    /// Insert a copy of the data into an entity, replacing its current value
+    fn insert(&self, db: &mut SystemInterface<CS, CD>, entity_id: EntityID)
+        -> Result<(), EcsError>;
+}
+
+type EntitySnapshot<CS, CD> = (EntityID, Vec<Box<dyn ComponentSnapshot<CS, CD>>>);
+
+/// In-memory copy of the world's entities and their snapshot-registered components, taken via SnapshotRegistry
+///
+/// Restoring rolls entity allocation back as well, so entities keep the exact IDs they had when the snapshot was taken.
+pub struct WorldSnapshot<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    entity_allocator: EntityAllocator,
+    component_ids: HashSet<ComponentID>,
+    entities: Vec<EntitySnapshot<CS, CD>>,
+}
+
+impl<CS, CD> WorldSnapshot<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    pub(super) fn new(
+        entity_allocator: EntityAllocator,
+        component_ids: HashSet<ComponentID>,
+        entities: Vec<EntitySnapshot<CS, CD>>,
+    ) -> Self {
+        WorldSnapshot {
+            entity_allocator,
+            component_ids,
+            entities,
+        }
+    }
+
+    pub fn get_entity_count(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Roll the world back to this snapshot
+    ///
+    /// Entities created since the snapshot are destroyed, and entities destroyed since are revived under their old IDs.
+    /// Registered components are reset to their snapshot values,
+    /// while unregistered components on surviving entities are left untouched.
+    /// Component hooks queue commands as usual, which the caller is responsible for applying.
+    pub fn restore(&self, db: &mut SystemInterface<CS, CD>) -> Result<(), EcsError> {
+        for entity_id in db
+            .entity_component_directory
+            .get_entities_by_predicate(|entity_id| !self.entity_allocator.is_alive(entity_id))
+        {
+            db.destroy_entity(entity_id)?;
+        }
+
+        db.entity_component_directory
+            .get_mut()?
+            .restore_entity_allocator(&self.entity_allocator);
+
+        for (entity_id, components) in &self.entities {
+            let snapshot_component_ids: HashSet<ComponentID> = components
+                .iter()
+                .map(|component| component.component_id())
+                .collect();
+
+            for (component_id, _) in db
+                .entity_component_directory
+                .get_entity_component_data(entity_id)?
+            {
+                if self.component_ids.contains(&component_id)
+                    && !snapshot_component_ids.contains(&component_id)
+                {
+                    db.remove_component_id_from_entity(*entity_id, &component_id)?;
+                }
+            }
+
+            for component in components {
+                component.insert(db, *entity_id)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Position, Size, SystemProfilingData},
+        entity_component_system::{
+            ComponentHooks, EntityComponentDirectory, HeapComponentStorage,
+            SingleThreadedDirectory, SnapshotRegistry, SystemInterface,
+        },
+        primitive_types::Vector2I,
+    };
+
+    #[test]
+    fn world_snapshot() {
+        let mut snapshot_registry = SnapshotRegistry::new();
+        snapshot_registry.register_antigen_components();
+
+        let component_hooks = ComponentHooks::new();
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let moving_entity = db.create_entity(Some("Moving")).unwrap();
+        db.insert_entity_component(moving_entity, Position(Vector2I(1, 1)))
+            .unwrap();
+        db.insert_entity_component(moving_entity, SystemProfilingData::default())
+            .unwrap();
+
+        let doomed_entity = db.create_entity(Some("Doomed")).unwrap();
+        db.insert_entity_component(doomed_entity, Position(Vector2I(2, 2)))
+            .unwrap();
+
+        let snapshot = snapshot_registry.take_snapshot(&db).unwrap();
+        assert_eq!(snapshot.get_entity_count(), 2);
+
+        **db.get_entity_component_mut::<Position>(moving_entity)
+            .unwrap() = Vector2I(5, 5);
+        db.insert_entity_component(moving_entity, Size(Vector2I(1, 0)))
+            .unwrap();
+        db.destroy_entity(doomed_entity).unwrap();
+        let spawned_entity = db.create_entity(Some("Spawned")).unwrap();
+
+        snapshot.restore(&mut db).unwrap();
+
+        assert_eq!(
+            **db.get_entity_component::<Position>(moving_entity).unwrap(),
+            Vector2I(1, 1)
+        );
+        assert!(db.get_entity_component::<Size>(moving_entity).is_err());
+
+        // Unregistered components are left alone
+        assert!(db
+            .get_entity_component::<SystemProfilingData>(moving_entity)
+            .is_ok());
+
+        assert!(db.is_alive(&doomed_entity));
+        assert_eq!(
+            **db.get_entity_component::<Position>(doomed_entity).unwrap(),
+            Vector2I(2, 2)
+        );
+
+        assert!(!db.is_alive(&spawned_entity));
+        assert_eq!(
+            db.entity_component_directory
+                .get_entities_by_predicate(|_| true)
+                .len(),
+            2
+        );
+    }
+}
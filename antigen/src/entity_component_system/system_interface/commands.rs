@@ -0,0 +1,221 @@
+use std::sync::{Arc, Mutex};
+
+use crate::components::Name;
+
+use super::{
+    ComponentStorage, ComponentTrait, EcsError, EntityComponentDirectory, EntityID, EntityReserver,
+    SystemInterface,
+};
+
+type Command<CS, CD> = Box<dyn FnOnce(&mut SystemInterface<CS, CD>) -> Result<(), EcsError> + Send>;
+
+/// Queue of structural changes to apply at the next sync point
+///
+/// Handles are cheap to clone and don't borrow the SystemInterface they came from,
+/// so systems can queue changes while iterating the results of a query.
+/// Queued commands are applied in submission order at the system runner's next sync point:
+/// after each system for SingleThreadedSystemRunner, and after each batch for ParallelSystemRunner.
+pub struct Commands<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    entity_reserver: EntityReserver,
+    queue: Arc<Mutex<Vec<Command<CS, CD>>>>,
+}
+
+impl<CS, CD> Commands<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    pub fn new(entity_reserver: EntityReserver) -> Self {
+        Commands {
+            entity_reserver,
+            queue: Default::default(),
+        }
+    }
+
+    /// Queue an arbitrary operation against the SystemInterface
+    pub fn push<F>(&self, command: F)
+    where
+        F: FnOnce(&mut SystemInterface<CS, CD>) -> Result<(), EcsError> + Send + 'static,
+    {
+        self.queue
+            .lock()
+            .expect("Command queue poisoned")
+            .push(Box::new(command));
+    }
+
+    /// Reserve an entity ID and queue its creation
+    ///
+    /// The returned ID can be passed to other commands immediately, but isn't alive until they're applied.
+    pub fn create_entity(&self, debug_label: Option<&str>) -> Result<EntityID, EcsError> {
+        let entity_id = self.entity_reserver.reserve()?;
+
+        let debug_label: Option<String> = debug_label.map(Into::into);
+        self.push(move |db| {
+            db.entity_component_directory
+                .get_mut()?
+                .create_reserved_entity(entity_id)?;
+
+            if let Some(debug_label) = debug_label {
+                db.insert_entity_component(entity_id, Name(debug_label))?;
+            }
+
+            Ok(())
+        });
+
+        Ok(entity_id)
+    }
+
+    pub fn insert_entity_component<T>(&self, entity_id: EntityID, component_data: T)
+    where
+        T: ComponentTrait + Send + 'static,
+    {
+        self.push(move |db| {
+            db.insert_entity_component(entity_id, component_data)?;
+            Ok(())
+        });
+    }
+
+    pub fn remove_component_from_entity<T>(&self, entity_id: EntityID)
+    where
+        T: ComponentTrait + 'static,
+    {
+        self.push(move |db| db.remove_component_from_entity::<T>(entity_id));
+    }
+
+    pub fn destroy_entity(&self, entity_id: EntityID) {
+        self.push(move |db| db.destroy_entity(entity_id));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue
+            .lock()
+            .expect("Command queue poisoned")
+            .is_empty()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.queue.lock().expect("Command queue poisoned").len()
+    }
+
+    /// Discard every command queued after the first `len`, ex. to roll back an operation whose hooks queued them
+    pub(crate) fn truncate(&self, len: usize) {
+        self.queue
+            .lock()
+            .expect("Command queue poisoned")
+            .truncate(len);
+    }
+
+    /// Apply queued commands in submission order, including any queued while applying
+    ///
+    /// A failing command is dropped and its error returned, with the commands after it left queued for the next apply.
+    pub fn apply(&self, db: &mut SystemInterface<CS, CD>) -> Result<(), EcsError> {
+        loop {
+            let commands = std::mem::take(&mut *self.queue.lock().expect("Command queue poisoned"));
+            if commands.is_empty() {
+                return Ok(());
+            }
+
+            let mut commands = commands.into_iter();
+            while let Some(command) = commands.next() {
+                if let Err(err) = command(db) {
+                    // Unapplied commands go back ahead of any that were queued while applying
+                    let mut queue = self.queue.lock().expect("Command queue poisoned");
+                    let queued_while_applying = std::mem::take(&mut *queue);
+                    queue.extend(commands);
+                    queue.extend(queued_while_applying);
+                    return Err(err);
+                }
+            }
+        }
+    }
+}
+
+impl<CS, CD> Clone for Commands<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn clone(&self) -> Self {
+        Commands {
+            entity_reserver: self.entity_reserver.clone(),
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{Name, Position},
+        entity_component_system::{
+            ComponentHooks, EcsError, EntityID, HeapComponentStorage, SingleThreadedDirectory,
+            SystemInterface,
+        },
+        primitive_types::Vector2I,
+    };
+
+    #[test]
+    fn commands() {
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let component_hooks = ComponentHooks::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let existing_entity = db.create_entity(None).unwrap();
+        db.insert_entity_component(existing_entity, Position(Vector2I(1, 1)))
+            .unwrap();
+
+        // Queue changes while the query results are borrowed
+        let commands = db.commands();
+        let mut spawned_entities = Vec::new();
+        for (entity_id, position) in db.query::<(EntityID, &Position)>().unwrap() {
+            let spawned_entity = commands.create_entity(Some("Spawned")).unwrap();
+            commands.insert_entity_component(spawned_entity, *position);
+            commands.destroy_entity(entity_id);
+            spawned_entities.push(spawned_entity);
+        }
+
+        let spawned_entity = spawned_entities[0];
+        assert!(!db.is_alive(&spawned_entity));
+        assert!(db.is_alive(&existing_entity));
+
+        db.apply_commands().unwrap();
+        assert!(commands.is_empty());
+
+        assert!(db.is_alive(&spawned_entity));
+        assert!(!db.is_alive(&existing_entity));
+        assert_eq!(
+            **db.get_entity_component::<Position>(spawned_entity).unwrap(),
+            Vector2I(1, 1)
+        );
+        assert_eq!(
+            db.get_entity_component::<Name>(spawned_entity).unwrap().0,
+            "Spawned"
+        );
+
+        // Errors surface when the offending command is applied
+        commands.destroy_entity(existing_entity);
+        commands.insert_entity_component(spawned_entity, Position(Vector2I(2, 2)));
+        assert_eq!(
+            db.apply_commands(),
+            Err(EcsError::StaleEntity(existing_entity))
+        );
+
+        // Commands after the failing one stay queued, and are applied next time
+        assert!(!commands.is_empty());
+        db.apply_commands().unwrap();
+        assert!(commands.is_empty());
+        assert_eq!(
+            **db.get_entity_component::<Position>(spawned_entity).unwrap(),
+            Vector2I(2, 2)
+        );
+    }
+}
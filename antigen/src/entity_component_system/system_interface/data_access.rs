@@ -0,0 +1,36 @@
+use std::ops::Deref;
+
+use crate::entity_component_system::EcsError;
+
+/// A SystemInterface's handle on world state
+///
+/// Interfaces given to systems running concurrently only hold shared references,
+/// so anything that needs to mutate the world has to go through get_mut and fail.
+pub enum DataAccess<'a, T> {
+    Exclusive(&'a mut T),
+    Shared(&'a T),
+}
+
+impl<'a, T> DataAccess<'a, T> {
+    pub fn is_exclusive(&self) -> bool {
+        matches!(self, DataAccess::Exclusive(_))
+    }
+
+    pub fn get_mut(&mut self) -> Result<&mut T, EcsError> {
+        match self {
+            DataAccess::Exclusive(data) => Ok(data),
+            DataAccess::Shared(_) => Err(EcsError::ExclusiveAccess),
+        }
+    }
+}
+
+impl<'a, T> Deref for DataAccess<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            DataAccess::Exclusive(data) => data,
+            DataAccess::Shared(data) => data,
+        }
+    }
+}
@@ -1,15 +1,34 @@
+mod commands;
+mod data_access;
+
+pub use commands::Commands;
+pub use data_access::DataAccess;
+
 use crate::components::Name;
 
-use super::{ComponentID, ComponentStorage, ComponentTrait, EntityComponentDirectory, EntityID};
+use super::{
+    ComponentAccess, ComponentDataID, ComponentEvent, ComponentHooks, ComponentID,
+    ComponentStorage, ComponentTicks, ComponentTrait, EcsError, EntityComponentDirectory, EntityID,
+    EntityReserver, Mut, Query, QueryFilter,
+};
 
-/// Ties together component data storage, entity-component lookup, and callback handling
+/// Ties together component data storage, entity-component lookup, and component hooks
+///
+/// Interfaces either have exclusive access to the world, or share it with other systems running concurrently.
+/// Shared interfaces can only fetch the component data their system declared access to,
+/// and have to queue structural changes through commands.
 pub struct SystemInterface<'a, CS, CD>
 where
     CS: ComponentStorage + 'static,
     CD: EntityComponentDirectory + 'static,
 {
-    pub component_storage: &'a mut CS,
-    pub entity_component_directory: &'a mut CD,
+    component_storage: DataAccess<'a, CS>,
+    pub entity_component_directory: DataAccess<'a, CD>,
+    pub component_hooks: &'a ComponentHooks<CS, CD>,
+    access: Option<ComponentAccess>,
+    commands: Commands<CS, CD>,
+    change_tick: u64,
+    last_change_tick: u64,
 }
 
 impl<'a, CS, CD> SystemInterface<'a, CS, CD>
@@ -17,12 +36,88 @@ where
     CS: ComponentStorage,
     CD: EntityComponentDirectory,
 {
-    pub fn new(component_storage: &'a mut CS, entity_component_directory: &'a mut CD) -> Self {
+    pub fn new(
+        component_storage: &'a mut CS,
+        entity_component_directory: &'a mut CD,
+        component_hooks: &'a ComponentHooks<CS, CD>,
+    ) -> Self {
+        let commands = Commands::new(entity_component_directory.get_entity_reserver());
+        SystemInterface {
+            component_storage: DataAccess::Exclusive(component_storage),
+            entity_component_directory: DataAccess::Exclusive(entity_component_directory),
+            component_hooks,
+            access: None,
+            commands,
+            change_tick: 1,
+            last_change_tick: 0,
+        }
+    }
+
+    /// Create an interface for a system running alongside others, limited to the component access it declared
+    pub fn new_shared(
+        component_storage: &'a CS,
+        entity_component_directory: &'a CD,
+        component_hooks: &'a ComponentHooks<CS, CD>,
+        access: ComponentAccess,
+    ) -> Self {
+        let commands = Commands::new(entity_component_directory.get_entity_reserver());
         SystemInterface {
-            component_storage,
-            entity_component_directory,
+            component_storage: DataAccess::Shared(component_storage),
+            entity_component_directory: DataAccess::Shared(entity_component_directory),
+            component_hooks,
+            access: Some(access),
+            commands,
+            change_tick: 1,
+            last_change_tick: 0,
         }
     }
+
+    /// Direct access to component storage, for operations that span every component type
+    ///
+    /// Fails for shared interfaces, since reading storage directly would bypass their access set.
+    pub fn get_component_storage(&self) -> Result<&CS, EcsError> {
+        if !self.component_storage.is_exclusive() {
+            return Err(EcsError::ExclusiveAccess);
+        }
+
+        Ok(&self.component_storage)
+    }
+
+    /// Fail if this interface is shared and its system didn't declare the given access
+    fn check_access(&self, access: &ComponentAccess) -> Result<(), EcsError> {
+        match &self.access {
+            Some(declared) => access.check_covered_by(declared),
+            None => Ok(()),
+        }
+    }
+
+    /// The tick that component additions and writes are recorded at
+    pub fn get_change_tick(&self) -> u64 {
+        self.change_tick
+    }
+
+    /// The tick the running system last ran at, which Added and Changed filters compare against
+    pub fn get_last_change_tick(&self) -> u64 {
+        self.last_change_tick
+    }
+
+    pub fn set_change_ticks(&mut self, change_tick: u64, last_change_tick: u64) {
+        self.change_tick = change_tick;
+        self.last_change_tick = last_change_tick;
+    }
+
+    /// Returns a handle for deferring structural changes until the next sync point
+    ///
+    /// ex. `db.commands().destroy_entity(entity_id)`
+    pub fn commands(&self) -> Commands<CS, CD> {
+        self.commands.clone()
+    }
+
+    /// Apply any commands queued through this interface
+    pub fn apply_commands(&mut self) -> Result<(), EcsError> {
+        let commands = self.commands.clone();
+        commands.apply(self)
+    }
 }
 
 impl<'a, CS, CD> SystemInterface<'a, CS, CD>
@@ -31,8 +126,8 @@ where
     CD: EntityComponentDirectory,
 {
     // CREATE
-    pub fn create_entity(&mut self, debug_label: Option<&str>) -> Result<EntityID, String> {
-        let entity_id = self.entity_component_directory.create_entity()?;
+    pub fn create_entity(&mut self, debug_label: Option<&str>) -> Result<EntityID, EcsError> {
+        let entity_id = self.entity_component_directory.get_mut()?.create_entity()?;
 
         if let Some(debug_label) = debug_label {
             self.insert_entity_component(entity_id, Name(debug_label.into()))?;
@@ -42,40 +137,92 @@ where
     }
 
     // INSERT
-    pub fn insert_component<T>(&mut self) -> Result<ComponentID, String>
+    pub fn insert_component<T>(&mut self) -> Result<ComponentID, EcsError>
     where
         T: ComponentTrait + 'static,
     {
-        self.entity_component_directory.insert_component::<T>()
+        self.entity_component_directory
+            .get_mut()?
+            .insert_component::<T>()
     }
 
     pub fn insert_entity_component<T>(
         &mut self,
         entity_id: EntityID,
         component_data: T,
-    ) -> Result<&mut T, String>
+    ) -> Result<&mut T, EcsError>
     where
         T: ComponentTrait + 'static,
     {
+        // Check liveness up front so stale entities don't leak component data
+        if !self.is_alive(&entity_id) {
+            return Err(EcsError::StaleEntity(entity_id));
+        }
+
         if !self.entity_component_directory.is_valid_component::<T>() {
             self.insert_component::<T>()?;
         }
 
-        let component_data_id = self.component_storage.insert_component(component_data)?;
+        let component_id = ComponentID::get::<T>();
+        let mut ticks = ComponentTicks::new(self.change_tick);
+        let event = match self
+            .entity_component_directory
+            .get_entity_component_data_id(&entity_id, &component_id)
+        {
+            Ok(existing_data_id) => {
+                // Replace the existing data rather than leaking it, keeping the tick it was added at
+                ticks.added = self
+                    .component_storage
+                    .get_component_ticks(&existing_data_id)?
+                    .added;
+                self.component_storage
+                    .get_mut()?
+                    .remove_component_data(&existing_data_id)?;
+                ComponentEvent::Replace
+            }
+            Err(_) => ComponentEvent::Add,
+        };
+
+        let component_storage = self.component_storage.get_mut()?;
+        let component_data_id = component_storage.insert_component(component_data)?;
+        *component_storage.get_component_ticks_mut(&component_data_id)? = ticks;
         self.entity_component_directory
+            .get_mut()?
             .insert_entity_component::<T>(&entity_id, component_data_id)?;
 
+        // Keep the entity's data together, for storages that lay it out by archetype
+        if let Some(sibling_data_id) = self
+            .entity_component_directory
+            .get_entity_component_data(&entity_id)?
+            .values()
+            .find(|sibling_data_id| **sibling_data_id != component_data_id)
+        {
+            self.component_storage
+                .get_mut()?
+                .group_component_data(&component_data_id, sibling_data_id)?;
+        }
+
+        self.run_component_hooks(event, &component_id, &component_data_id, entity_id)?;
+
         self.component_storage
+            .get_mut()?
             .get_component_data_mut::<T>(&component_data_id)
     }
 
+    // EXIST
+    pub fn is_alive(&self, entity_id: &EntityID) -> bool {
+        self.entity_component_directory.is_alive(entity_id)
+    }
+
     // GET
-    pub fn get_entity_component<T>(&self, entity_id: EntityID) -> Result<&T, String>
+    pub fn get_entity_component<T>(&self, entity_id: EntityID) -> Result<&T, EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
         T: ComponentTrait + 'static,
     {
+        self.check_access(&ComponentAccess::new().with_read::<T>())?;
+
         let component_data_id = self
             .entity_component_directory
             .get_entity_component_data_id(&entity_id, &ComponentID::get::<T>())?;
@@ -84,43 +231,222 @@ where
             .get_component_data(&component_data_id)
     }
 
-    pub fn get_entity_component_mut<T>(&mut self, entity_id: EntityID) -> Result<&mut T, String>
+    /// Mutably fetch an entity's component, which is marked as changed once written through
+    pub fn get_entity_component_mut<T>(
+        &mut self,
+        entity_id: EntityID,
+    ) -> Result<Mut<'_, T>, EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
         T: ComponentTrait + 'static,
     {
+        self.check_access(&ComponentAccess::new().with_write::<T>())?;
+
         let component_data_id = self
             .entity_component_directory
             .get_entity_component_data_id(&entity_id, &ComponentID::get::<T>())?;
 
-        self.component_storage
-            .get_component_data_mut::<T>(&component_data_id)
+        let (component_data, component_ticks) = self
+            .component_storage
+            .get_component_data_ptr::<T>(&component_data_id)?;
+
+        // Safety: Exclusive interfaces stay mutably borrowed while the result is live.
+        // Shared interfaces have checked that their system declared a write to T,
+        // and the runner never lets a concurrently running system read or write it.
+        unsafe {
+            Ok(Mut::new(
+                &mut *component_data,
+                &mut *component_ticks,
+                self.change_tick,
+            ))
+        }
+    }
+
+    // QUERY
+    /// Fetch a typed view of every entity that matches the given query, ordered by entity ID
+    ///
+    /// ex. `db.query::<(EntityID, &Velocity, &mut Position)>()`
+    pub fn query<'b, Q>(&'b mut self) -> Result<Vec<Q::Item>, EcsError>
+    where
+        Q: Query<'b>,
+    {
+        self.query_filtered::<Q, ()>()
+    }
+
+    /// Fetch a typed view of every entity that matches the given query and filter, ordered by entity ID
+    ///
+    /// ex. `db.query_filtered::<&mut Position, (With<Control>, Without<Window>)>()`
+    pub fn query_filtered<'b, Q, F>(&'b mut self) -> Result<Vec<Q::Item>, EcsError>
+    where
+        Q: Query<'b>,
+        F: QueryFilter,
+    {
+        let entities = self.query_entities::<Q, F>()?;
+
+        let component_storage: &'b CS = &self.component_storage;
+        let entity_component_directory: &'b CD = &self.entity_component_directory;
+        let change_tick = self.change_tick;
+
+        // Safety: Access validation guarantees that no component type is fetched mutably alongside
+        // any other reference to it, and each entity is visited once, so every mutably fetched slot is distinct.
+        // Storage is borrowed through a shared reference, and self stays mutably borrowed while the items are live.
+        // Shared interfaces only query declared components, which no concurrently running system writes.
+        entities
+            .into_iter()
+            .map(|entity_id| unsafe {
+                Q::fetch(
+                    component_storage,
+                    entity_component_directory,
+                    entity_id,
+                    change_tick,
+                )
+            })
+            .collect()
+    }
+
+    /// Fetch a typed view of the one entity that matches the given query,
+    /// returning an error if there are none or several
+    ///
+    /// ex. `db.query_single::<&CellFramebuffer>()`
+    pub fn query_single<'b, Q>(&'b mut self) -> Result<Q::Item, EcsError>
+    where
+        Q: Query<'b>,
+    {
+        self.query_single_filtered::<Q, ()>()
+    }
+
+    /// Fetch a typed view of the one entity that matches the given query and filter,
+    /// returning an error if there are none or several
+    ///
+    /// ex. `db.query_single_filtered::<(EntityID, &Size), With<Window>>()`
+    pub fn query_single_filtered<'b, Q, F>(&'b mut self) -> Result<Q::Item, EcsError>
+    where
+        Q: Query<'b>,
+        F: QueryFilter,
+    {
+        let entities = self.query_entities::<Q, F>()?;
+        let entity_id = match entities[..] {
+            [entity_id] => entity_id,
+            _ => {
+                return Err(EcsError::QuerySingle {
+                    query: std::any::type_name::<Q>(),
+                    count: entities.len(),
+                })
+            }
+        };
+
+        let component_storage: &'b CS = &self.component_storage;
+        let entity_component_directory: &'b CD = &self.entity_component_directory;
+
+        // Safety: As query_filtered, with a single entity
+        unsafe {
+            Q::fetch(
+                component_storage,
+                entity_component_directory,
+                entity_id,
+                self.change_tick,
+            )
+        }
+    }
+
+    /// Validate a query's access and collect the entities it matches, ordered by entity ID
+    fn query_entities<'b, Q, F>(&self) -> Result<Vec<EntityID>, EcsError>
+    where
+        Q: Query<'b>,
+        F: QueryFilter,
+    {
+        // Reject queries that would hand out aliasing mutable references
+        let mut access = ComponentAccess::new();
+        Q::access(&mut access)?;
+
+        // Shared interfaces can only query what their system declared
+        self.check_access(&access)?;
+        self.check_access(&F::access(ComponentAccess::new()))?;
+
+        let entity_component_directory: &CD = &self.entity_component_directory;
+        let component_storage: &CS = &self.component_storage;
+        let last_change_tick = self.last_change_tick;
+
+        let mut required_components = Vec::new();
+        Q::required_components(&mut required_components);
+        F::required_components(&mut required_components);
+
+        let mut entities = entity_component_directory.get_entities_with_components(
+            &required_components,
+            |entity_id| {
+                Q::matches(entity_component_directory, entity_id)
+                    && F::matches(entity_component_directory, entity_id)
+                    && F::matches_ticks(
+                        component_storage,
+                        entity_component_directory,
+                        entity_id,
+                        last_change_tick,
+                    )
+            },
+        );
+        entities.sort();
+
+        Ok(entities)
+    }
+
+    // HOOKS
+    fn run_component_hooks(
+        &self,
+        event: ComponentEvent,
+        component_id: &ComponentID,
+        component_data_id: &ComponentDataID,
+        entity_id: EntityID,
+    ) -> Result<(), EcsError> {
+        self.component_hooks.run(
+            event,
+            component_id,
+            &self.component_storage,
+            component_data_id,
+            entity_id,
+            &self.commands,
+        )
     }
 
     // DESTROY
-    pub fn remove_component_from_entity<T>(&mut self, entity_id: EntityID) -> Result<(), String>
+    pub fn remove_component_from_entity<T>(&mut self, entity_id: EntityID) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
         T: ComponentTrait + 'static,
     {
-        let component_id = ComponentID::get::<T>();
+        self.remove_component_id_from_entity(entity_id, &ComponentID::get::<T>())
+    }
 
+    /// Type-erased counterpart to remove_component_from_entity
+    pub(crate) fn remove_component_id_from_entity(
+        &mut self,
+        entity_id: EntityID,
+        component_id: &ComponentID,
+    ) -> Result<(), EcsError> {
         let component_data_id = self
             .entity_component_directory
-            .get_entity_component_data_id(&entity_id, &component_id)?;
+            .get_entity_component_data_id(&entity_id, component_id)?;
+
+        self.run_component_hooks(
+            ComponentEvent::Remove,
+            component_id,
+            &component_data_id,
+            entity_id,
+        )?;
 
         self.component_storage
-            .remove_component_data(&component_id, &component_data_id)?;
+            .get_mut()?
+            .remove_component_data(&component_data_id)?;
 
         self.entity_component_directory
-            .destroy_entity_component(&entity_id, &component_id)?;
+            .get_mut()?
+            .destroy_entity_component(&entity_id, component_id)?;
 
         Ok(())
     }
 
-    pub fn destroy_entity(&mut self, entity_id: EntityID) -> Result<(), String>
+    pub fn destroy_entity(&mut self, entity_id: EntityID) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
@@ -130,36 +456,55 @@ where
             .get_entity_component_data(&entity_id)?;
 
         for (component_id, component_data_id) in component_data_ids {
+            self.run_component_hooks(
+                ComponentEvent::Remove,
+                &component_id,
+                &component_data_id,
+                entity_id,
+            )?;
+
             self.component_storage
-                .remove_component_data(&component_id, &component_data_id)?;
+                .get_mut()?
+                .remove_component_data(&component_data_id)?;
         }
 
-        self.entity_component_directory.destroy_entity(entity_id)
+        self.entity_component_directory
+            .get_mut()?
+            .destroy_entity(entity_id)?;
+
+        Ok(())
     }
 
-    pub fn destroy_component<T>(&mut self) -> Result<(), String>
+    pub fn destroy_component<T>(&mut self) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
         T: ComponentTrait + 'static,
     {
         let component_id = ComponentID::get::<T>();
-        let entities: Vec<EntityID> =
-            self.entity_component_directory
-                .get_entities_by_predicate(|entity_id| {
-                    self.entity_component_directory
-                        .entity_has_component::<T>(entity_id)
-                });
+        let entities: Vec<EntityID> = self
+            .entity_component_directory
+            .get_entities_with_component::<T>(|_| true);
 
         for entity_id in entities {
             let component_data_id = self
                 .entity_component_directory
                 .get_entity_component_data_id(&entity_id, &component_id)?;
 
+            self.run_component_hooks(
+                ComponentEvent::Remove,
+                &component_id,
+                &component_data_id,
+                entity_id,
+            )?;
+
             self.component_storage
-                .remove_component_data(&component_id, &component_data_id)?;
+                .get_mut()?
+                .remove_component_data(&component_data_id)?;
         }
 
-        self.entity_component_directory.destroy_component::<T>()
+        self.entity_component_directory
+            .get_mut()?
+            .destroy_component::<T>()
     }
 }
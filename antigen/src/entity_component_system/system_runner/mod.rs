@@ -1,18 +1,24 @@
+mod parallel_system_runner;
 mod single_threaded_system_runner;
 
+pub use parallel_system_runner::ParallelSystemRunner;
 pub use single_threaded_system_runner::SingleThreadedSystemRunner;
 
 use super::{
-    system_storage::SystemStorage,
-    SystemInterface, SystemError,
-ComponentStorage, EntityComponentDirectory};
+    system_storage::SystemStorage, ComponentStorage, EntityComponentDirectory, SystemError,
+    SystemInterface,
+};
 
 /// Trait for handling systems execution for a given EntityComponentSystem
+///
+/// Runners advance the SystemInterface's change tick once per system,
+/// and give each system the tick it last ran at so Added and Changed filters see changes made since.
+/// Queued commands are applied at sync points, whose placement is up to the runner.
 pub trait SystemRunner {
-    fn run<'a, SS, CS, CD>(
+    fn run<SS, CS, CD>(
         &mut self,
-        system_storage: &'a mut SS,
-        entity_component_database: &'a mut SystemInterface<'a, CS, CD>,
+        system_storage: &mut SS,
+        entity_component_database: &mut SystemInterface<CS, CD>,
     ) -> Result<(), SystemError>
     where
         SS: SystemStorage<CS, CD>,
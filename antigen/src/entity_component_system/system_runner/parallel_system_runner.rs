@@ -0,0 +1,577 @@
+use std::{collections::HashMap, time::Duration};
+
+use rayon::{prelude::*, ThreadPool, ThreadPoolBuilder};
+
+use crate::{
+    components::SystemProfilingData, core::profiler::Profiler,
+    entity_component_system::system_storage::SystemOrdering,
+    entity_component_system::system_storage::SystemStorage, entity_component_system::Commands,
+    entity_component_system::ComponentAccess, entity_component_system::ComponentStorage,
+    entity_component_system::EcsError, entity_component_system::EntityComponentDirectory,
+    entity_component_system::SystemError, entity_component_system::SystemID,
+    entity_component_system::SystemInterface, entity_component_system::SystemLabel,
+    entity_component_system::SystemTrait,
+};
+
+use super::SystemRunner;
+
+/// Runs systems in execution order, executing batches of non-conflicting systems concurrently on a thread pool
+///
+/// Systems are grouped using the access sets declared by SystemTrait::get_component_access.
+/// Systems that conflict always run in execution order, and systems without a declared access set run alone.
+/// Concurrently running systems each get a shared SystemInterface limited to their declared access,
+/// so structural changes made by them have to be queued through commands.
+///
+/// Each batch is a sync point: commands are applied once the whole batch finishes,
+/// so systems don't see structural changes queued by others in the same batch.
+/// Ordering a system after another puts it in a later batch, where those changes are visible.
+pub struct ParallelSystemRunner {
+    thread_pool: ThreadPool,
+    scheduled_systems: Vec<SystemID>,
+    schedule: Vec<Vec<SystemID>>,
+    last_change_ticks: HashMap<SystemID, u64>,
+}
+
+impl ParallelSystemRunner {
+    pub fn new(num_threads: usize) -> Result<Self, String> {
+        let thread_pool = ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .thread_name(|index| format!("System Runner {}", index))
+            .build()
+            .map_err(|err| format!("Error creating system runner thread pool: {}", err))?;
+
+        Ok(ParallelSystemRunner {
+            thread_pool,
+            scheduled_systems: Vec::new(),
+            schedule: Vec::new(),
+            last_change_ticks: HashMap::new(),
+        })
+    }
+
+    pub fn get_schedule(&self) -> &[Vec<SystemID>] {
+        &self.schedule
+    }
+
+    /// Build the conflict graph between systems, and assign each system to the batch after its latest conflicting predecessor
+    ///
+    /// Besides conflicting access, systems conflict with those in earlier stages and those they're ordered against,
+    /// so that stage boundaries and before / after constraints are kept.
+    #[allow(clippy::type_complexity)]
+    fn build_schedule(
+        systems: &[(SystemID, Option<ComponentAccess>, SystemOrdering)],
+    ) -> Vec<Vec<SystemID>> {
+        fn conflicts(
+            (_, access, ordering): &(SystemID, Option<ComponentAccess>, SystemOrdering),
+            (_, predecessor_access, predecessor_ordering): &(
+                SystemID,
+                Option<ComponentAccess>,
+                SystemOrdering,
+            ),
+        ) -> bool {
+            let ordered = |labels: &[SystemLabel], other: &SystemOrdering| {
+                labels.iter().any(|label| other.labels.contains(label))
+            };
+
+            if ordering.stage != predecessor_ordering.stage
+                || ordered(&ordering.after, predecessor_ordering)
+                || ordered(&predecessor_ordering.before, ordering)
+            {
+                return true;
+            }
+
+            match (access, predecessor_access) {
+                (Some(lhs), Some(rhs)) => !lhs.is_compatible(rhs),
+                _ => true,
+            }
+        }
+
+        let mut batch_indices: Vec<usize> = Vec::with_capacity(systems.len());
+        for (i, system) in systems.iter().enumerate() {
+            let batch_index = systems[..i]
+                .iter()
+                .zip(&batch_indices)
+                .filter(|(predecessor, _)| conflicts(system, predecessor))
+                .map(|(_, batch_index)| batch_index + 1)
+                .max()
+                .unwrap_or(0);
+
+            batch_indices.push(batch_index);
+        }
+
+        let mut schedule: Vec<Vec<SystemID>> = Vec::new();
+        for ((system_id, _, _), batch_index) in systems.iter().zip(batch_indices) {
+            if schedule.len() <= batch_index {
+                schedule.resize_with(batch_index + 1, Vec::new);
+            }
+            schedule[batch_index].push(*system_id);
+        }
+
+        schedule
+    }
+}
+
+impl Default for ParallelSystemRunner {
+    fn default() -> Self {
+        let num_threads = std::thread::available_parallelism()
+            .map(usize::from)
+            .unwrap_or(1);
+
+        ParallelSystemRunner::new(num_threads).unwrap()
+    }
+}
+
+impl SystemRunner for ParallelSystemRunner {
+    fn run<SS, CS, CD>(
+        &mut self,
+        system_storage: &mut SS,
+        entity_component_database: &mut SystemInterface<CS, CD>,
+    ) -> Result<(), SystemError>
+    where
+        SS: SystemStorage<CS, CD>,
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        superluminal_perf::begin_event("System Runner");
+
+        if let Some(system_debug_entity) = entity_component_database
+            .entity_component_directory
+            .get_entity_by_predicate(|entity_id| {
+                entity_component_database
+                    .entity_component_directory
+                    .entity_has_component::<SystemProfilingData>(entity_id)
+            })
+        {
+            // Rebuild the schedule whenever the system execution order changes
+            let system_ids: Vec<SystemID> = system_storage
+                .get_systems()?
+                .iter()
+                .map(|(system_id, _)| *system_id)
+                .collect();
+            if system_ids != self.scheduled_systems {
+                let system_accesses: Vec<(SystemID, Option<ComponentAccess>)> = system_storage
+                    .get_systems()?
+                    .iter()
+                    .map(|(system_id, system)| (*system_id, system.get_component_access()))
+                    .collect();
+
+                let scheduled_systems = system_accesses
+                    .into_iter()
+                    .map(|(system_id, access)| {
+                        let ordering = system_storage.get_system_ordering(&system_id)?.clone();
+                        Ok((system_id, access, ordering))
+                    })
+                    .collect::<Result<Vec<_>, EcsError>>()?;
+
+                self.schedule = Self::build_schedule(&scheduled_systems);
+                self.scheduled_systems = system_ids;
+            }
+
+            let systems = system_storage.get_systems()?;
+            let mut systems: HashMap<SystemID, &mut (dyn SystemTrait<CS, CD> + 'static)> =
+                systems.into_iter().collect();
+
+            let last_change_ticks = &mut self.last_change_ticks;
+            for batch in &self.schedule {
+                // Each system gets its own change tick, in execution order
+                let base_change_tick = entity_component_database.get_change_tick();
+                let batch_ticks: Vec<(u64, u64)> = batch
+                    .iter()
+                    .zip(1..)
+                    .map(|(system_id, offset)| {
+                        let change_tick = base_change_tick + offset;
+                        let last_change_tick = last_change_ticks
+                            .insert(*system_id, change_tick)
+                            .unwrap_or(0);
+                        (change_tick, last_change_tick)
+                    })
+                    .collect();
+
+                #[allow(clippy::type_complexity)]
+                let durations: Vec<(
+                    SystemID,
+                    Result<Duration, SystemError>,
+                    Commands<CS, CD>,
+                )> = if let [system_id] = batch.as_slice() {
+                    // Single systems run on the calling thread, which keeps exclusive systems off the pool
+                    let system = systems.remove(system_id).expect("Scheduled system missing");
+
+                    let (change_tick, last_change_tick) = batch_ticks[0];
+                    entity_component_database.set_change_ticks(change_tick, last_change_tick);
+
+                    let label = system_id.get_name();
+                    let profiler = Profiler::start();
+                    superluminal_perf::begin_event_with_data("Run System", &label, 0);
+                    let result = system.run(entity_component_database);
+                    superluminal_perf::end_event();
+
+                    vec![(
+                        *system_id,
+                        result.map(|_| profiler.finish()),
+                        entity_component_database.commands(),
+                    )]
+                } else {
+                    let component_storage = entity_component_database.get_component_storage()?;
+                    let entity_component_directory: &CD =
+                        &entity_component_database.entity_component_directory;
+                    let component_hooks = entity_component_database.component_hooks;
+
+                    #[allow(clippy::type_complexity)]
+                    let batch_systems: Vec<(
+                        SystemID,
+                        &mut (dyn SystemTrait<CS, CD> + 'static),
+                        ComponentAccess,
+                        (u64, u64),
+                    )> = batch
+                        .iter()
+                        .zip(&batch_ticks)
+                        .map(|(system_id, ticks)| {
+                            let system =
+                                systems.remove(system_id).expect("Scheduled system missing");
+                            let access = system
+                                .get_component_access()
+                                .expect("Systems without declared access run alone");
+                            (*system_id, system, access, *ticks)
+                        })
+                        .collect();
+
+                    // Each system can only reach the component data it declared access to,
+                    // and the scheduler never batches systems whose access sets conflict
+                    self.thread_pool.install(|| {
+                        batch_systems
+                            .into_par_iter()
+                            .map(
+                                |(system_id, system, access, (change_tick, last_change_tick))| {
+                                    let mut db = SystemInterface::new_shared(
+                                        component_storage,
+                                        entity_component_directory,
+                                        component_hooks,
+                                        access,
+                                    );
+                                    db.set_change_ticks(change_tick, last_change_tick);
+
+                                    let label = system_id.get_name();
+                                    let profiler = Profiler::start();
+                                    superluminal_perf::begin_event_with_data(
+                                        "Run System",
+                                        &label,
+                                        0,
+                                    );
+                                    let result = system.run(&mut db);
+                                    superluminal_perf::end_event();
+
+                                    (system_id, result.map(|_| profiler.finish()), db.commands())
+                                },
+                            )
+                            .collect()
+                    })
+                };
+
+                // Apply structural changes queued by the batch in execution order, at each system's tick
+                for ((system_id, duration, commands), (change_tick, last_change_tick)) in
+                    durations.into_iter().zip(batch_ticks)
+                {
+                    let duration = duration?;
+                    entity_component_database.set_change_ticks(change_tick, last_change_tick);
+                    commands.apply(entity_component_database)?;
+
+                    entity_component_database
+                        .get_entity_component_mut::<SystemProfilingData>(system_debug_entity)?
+                        .set_duration(system_id, duration);
+                }
+            }
+        }
+
+        superluminal_perf::end_event();
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::mpsc::{channel, Receiver, Sender},
+        time::Duration,
+    };
+
+    use crate::{
+        components::{Name, Position, SystemProfilingData, Velocity},
+        entity_component_system::{
+            system_storage::{HeapSystemStorage, SystemOrdering},
+            ComponentAccess, ComponentHooks, ComponentID, ComponentStorage, EcsError,
+            EntityComponentDirectory, EntityID, HeapComponentStorage, SingleThreadedDirectory,
+            SystemBuilder, SystemError, SystemID, SystemInterface, SystemRunner, SystemStage,
+            SystemStorage, SystemTrait,
+        },
+        primitive_types::Vector2I,
+    };
+
+    use super::ParallelSystemRunner;
+
+    #[test]
+    fn schedule() {
+        struct System;
+
+        let systems = vec![
+            (
+                SystemID::next::<System>(),
+                Some(ComponentAccess::new().with_write::<Position>()),
+                SystemOrdering::default(),
+            ),
+            (
+                SystemID::next::<System>(),
+                Some(ComponentAccess::new().with_read::<Velocity>()),
+                SystemOrdering::default(),
+            ),
+            (
+                SystemID::next::<System>(),
+                Some(ComponentAccess::new().with_read::<Position>()),
+                SystemOrdering::default(),
+            ),
+            (SystemID::next::<System>(), None, SystemOrdering::default()),
+            (
+                SystemID::next::<System>(),
+                Some(ComponentAccess::new().with_read::<Velocity>()),
+                SystemOrdering::default(),
+            ),
+        ];
+
+        let schedule = ParallelSystemRunner::build_schedule(&systems);
+        let system_ids = systems
+            .iter()
+            .map(|(system_id, _, _)| *system_id)
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            schedule,
+            vec![
+                vec![system_ids[0], system_ids[1]],
+                vec![system_ids[2]],
+                vec![system_ids[3]],
+                vec![system_ids[4]],
+            ]
+        );
+    }
+
+    #[test]
+    fn ordered_schedule() {
+        struct System;
+
+        let access = || Some(ComponentAccess::new().with_read::<Position>());
+        let systems = vec![
+            (
+                SystemID::next::<System>(),
+                access(),
+                SystemOrdering {
+                    labels: vec!["first"],
+                    ..Default::default()
+                },
+            ),
+            (
+                SystemID::next::<System>(),
+                access(),
+                SystemOrdering {
+                    after: vec!["first"],
+                    ..Default::default()
+                },
+            ),
+            (
+                SystemID::next::<System>(),
+                access(),
+                SystemOrdering {
+                    before: vec!["last"],
+                    ..Default::default()
+                },
+            ),
+            (
+                SystemID::next::<System>(),
+                access(),
+                SystemOrdering {
+                    labels: vec!["last"],
+                    ..Default::default()
+                },
+            ),
+            (
+                SystemID::next::<System>(),
+                access(),
+                SystemOrdering {
+                    stage: SystemStage::Render,
+                    ..Default::default()
+                },
+            ),
+        ];
+
+        let schedule = ParallelSystemRunner::build_schedule(&systems);
+        let system_ids = systems
+            .iter()
+            .map(|(system_id, _, _)| *system_id)
+            .collect::<Vec<_>>();
+
+        // Systems without conflicting access are still split by ordering constraints and stages
+        assert_eq!(
+            schedule,
+            vec![
+                vec![system_ids[0], system_ids[2]],
+                vec![system_ids[1], system_ids[3]],
+                vec![system_ids[4]],
+            ]
+        );
+    }
+
+    /// Signal the other system in the batch, then wait for it to do the same
+    fn rendezvous(sender: &Sender<()>, receiver: &Receiver<()>) -> Result<(), SystemError> {
+        sender
+            .send(())
+            .map_err(|err| SystemError::Err(err.to_string()))?;
+        receiver
+            .recv_timeout(Duration::from_secs(5))
+            .map_err(|_| SystemError::Err("Batched systems didn't run concurrently".into()))
+    }
+
+    struct MovePositions {
+        sender: Sender<()>,
+        receiver: Receiver<()>,
+        entity_id: EntityID,
+    }
+
+    impl<CS, CD> SystemTrait<CS, CD> for MovePositions
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError> {
+            rendezvous(&self.sender, &self.receiver)?;
+
+            // Shared interfaces only reach declared components, and can't make structural changes
+            assert_eq!(
+                db.get_entity_component::<Name>(self.entity_id).err(),
+                Some(EcsError::UndeclaredAccess(ComponentID::get::<Name>()))
+            );
+            assert_eq!(
+                db.create_entity(None).err(),
+                Some(EcsError::ExclusiveAccess)
+            );
+
+            for mut position in db.query::<&mut Position>()? {
+                **position = Vector2I(1, 1);
+            }
+
+            let commands = db.commands();
+            let entity_id = commands.create_entity(None)?;
+            commands.insert_entity_component(entity_id, Position(Vector2I(1, 1)));
+
+            Ok(())
+        }
+
+        fn get_component_access(&self) -> Option<ComponentAccess> {
+            Some(ComponentAccess::new().with_write::<Position>())
+        }
+    }
+
+    struct RenameEntities {
+        sender: Sender<()>,
+        receiver: Receiver<()>,
+    }
+
+    impl<CS, CD> SystemTrait<CS, CD> for RenameEntities
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError> {
+            rendezvous(&self.sender, &self.receiver)?;
+
+            for mut name in db.query::<&mut Name>()? {
+                **name = "Renamed".into();
+            }
+
+            Ok(())
+        }
+
+        fn get_component_access(&self) -> Option<ComponentAccess> {
+            Some(ComponentAccess::new().with_write::<Name>())
+        }
+    }
+
+    struct ReadPositions(Sender<Vec<Vector2I>>);
+
+    impl<CS, CD> SystemTrait<CS, CD> for ReadPositions
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError> {
+            let positions = db
+                .query::<&Position>()?
+                .into_iter()
+                .map(|position| **position)
+                .collect();
+
+            self.0
+                .send(positions)
+                .map_err(|err| SystemError::Err(err.to_string()))
+        }
+
+        fn get_component_access(&self) -> Option<ComponentAccess> {
+            Some(ComponentAccess::new().with_read::<Position>())
+        }
+    }
+
+    #[test]
+    fn concurrent_systems() {
+        let component_hooks = ComponentHooks::new();
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let profiling_entity = db.create_entity(None).unwrap();
+        db.insert_entity_component(profiling_entity, SystemProfilingData::default())
+            .unwrap();
+
+        let entity_id = db.create_entity(Some("Entity")).unwrap();
+        db.insert_entity_component(entity_id, Position(Vector2I(0, 0)))
+            .unwrap();
+
+        let (move_sender, rename_receiver) = channel();
+        let (rename_sender, move_receiver) = channel();
+        let (positions_sender, positions_receiver) = channel();
+
+        let mut system_storage =
+            HeapSystemStorage::<HeapComponentStorage, SingleThreadedDirectory>::new();
+        let move_positions = system_storage.insert_system(MovePositions {
+            sender: move_sender,
+            receiver: move_receiver,
+            entity_id,
+        });
+        let rename_entities = system_storage.insert_system(RenameEntities {
+            sender: rename_sender,
+            receiver: rename_receiver,
+        });
+        let read_positions = system_storage.insert_system(ReadPositions(positions_sender));
+        SystemBuilder::new(&mut system_storage, move_positions).label("Move");
+        SystemBuilder::new(&mut system_storage, read_positions).after("Move");
+
+        let mut system_runner = ParallelSystemRunner::new(2).unwrap();
+        system_runner.run(&mut system_storage, &mut db).unwrap();
+
+        // Non-conflicting systems share a batch, and each waits for the other to start
+        let schedule = system_runner.get_schedule();
+        assert_eq!(schedule.len(), 2);
+        assert!(schedule[0].contains(&move_positions) && schedule[0].contains(&rename_entities));
+        assert_eq!(schedule[1], vec![read_positions]);
+
+        // The conflicting reader runs afterwards, seeing the writes and queued entity
+        assert_eq!(
+            positions_receiver.recv().unwrap(),
+            vec![Vector2I(1, 1), Vector2I(1, 1)]
+        );
+        assert_eq!(
+            **db.get_entity_component::<Name>(entity_id).unwrap(),
+            "Renamed"
+        );
+    }
+}
@@ -1,21 +1,25 @@
+use std::collections::HashMap;
+
 use crate::{
     components::SystemProfilingData, core::profiler::Profiler,
     entity_component_system::system_storage::SystemStorage,
     entity_component_system::ComponentStorage, entity_component_system::EntityComponentDirectory,
     entity_component_system::SystemError, entity_component_system::SystemID,
-    entity_component_system::SystemInterface, entity_component_system::SystemTrait,
+    entity_component_system::SystemInterface,
 };
 
 use super::SystemRunner;
 
 #[derive(Default)]
-pub struct SingleThreadedSystemRunner;
+pub struct SingleThreadedSystemRunner {
+    last_change_ticks: HashMap<SystemID, u64>,
+}
 
 impl SystemRunner for SingleThreadedSystemRunner {
-    fn run<'a, SS, CS, CD>(
+    fn run<SS, CS, CD>(
         &mut self,
-        system_storage: &'a mut SS,
-        entity_component_database: &'a mut SystemInterface<'a, CS, CD>,
+        system_storage: &mut SS,
+        entity_component_database: &mut SystemInterface<CS, CD>,
     ) -> Result<(), SystemError>
     where
         SS: SystemStorage<CS, CD>,
@@ -32,12 +36,14 @@ impl SystemRunner for SingleThreadedSystemRunner {
                     .entity_has_component::<SystemProfilingData>(entity_id)
             })
         {
-            let systems = system_storage.get_systems();
-            let mut systems: Vec<(SystemID, &mut dyn SystemTrait<CS, CD>)> =
-                systems.into_iter().collect();
-            systems.sort_by(|(lhs_id, _), (rhs_id, _)| lhs_id.cmp(rhs_id));
+            for (system_id, system) in system_storage.get_systems()? {
+                let change_tick = entity_component_database.get_change_tick() + 1;
+                let last_change_tick = self
+                    .last_change_ticks
+                    .insert(system_id, change_tick)
+                    .unwrap_or(0);
+                entity_component_database.set_change_ticks(change_tick, last_change_tick);
 
-            for (system_id, system) in systems {
                 let label = system_id.get_name();
                 let profiler = Profiler::start();
                 superluminal_perf::begin_event_with_data("Run System", &label, 0);
@@ -45,6 +51,9 @@ impl SystemRunner for SingleThreadedSystemRunner {
                 superluminal_perf::end_event();
                 let duration = profiler.finish();
 
+                // Apply structural changes queued by the system before the next one runs
+                entity_component_database.apply_commands()?;
+
                 entity_component_database
                     .get_entity_component_mut::<SystemProfilingData>(system_debug_entity)?
                     .set_duration(system_id, duration);
@@ -1,10 +1,10 @@
 use std::collections::HashMap;
 
 use crate::entity_component_system::{
-    ComponentStorage, EntityComponentDirectory, SystemID, SystemTrait,
+    ComponentStorage, EcsError, EntityComponentDirectory, SystemID, SystemTrait,
 };
 
-use super::SystemStorage;
+use super::{sort_systems, SystemOrdering, SystemStorage};
 
 pub struct HeapSystemStorage<S, D>
 where
@@ -12,6 +12,8 @@ where
     D: EntityComponentDirectory,
 {
     systems: HashMap<SystemID, Box<dyn SystemTrait<S, D>>>,
+    system_orderings: HashMap<SystemID, SystemOrdering>,
+    sorted_systems: Option<Vec<SystemID>>,
 }
 
 impl<'a, S, D> HeapSystemStorage<S, D>
@@ -22,6 +24,8 @@ where
     pub fn new() -> HeapSystemStorage<S, D> {
         HeapSystemStorage {
             systems: HashMap::new(),
+            system_orderings: HashMap::new(),
+            sorted_systems: None,
         }
     }
 }
@@ -47,13 +51,58 @@ where
     {
         let id = SystemID::next::<T>();
         self.systems.insert(id, Box::new(system));
+        self.system_orderings.insert(id, SystemOrdering::default());
+        self.sorted_systems = None;
         id
     }
 
-    fn get_systems(&mut self) -> HashMap<SystemID, &mut (dyn SystemTrait<CS, CD> + 'static)> {
-        self.systems
+    fn get_system_ordering(&self, system_id: &SystemID) -> Result<&SystemOrdering, EcsError> {
+        self.system_orderings
+            .get(system_id)
+            .ok_or(EcsError::NoSuchSystem(*system_id))
+    }
+
+    fn get_system_ordering_mut(
+        &mut self,
+        system_id: &SystemID,
+    ) -> Result<&mut SystemOrdering, EcsError> {
+        // Ordering may change through the returned reference, so invalidate the cached sort
+        self.sorted_systems = None;
+
+        self.system_orderings
+            .get_mut(system_id)
+            .ok_or(EcsError::NoSuchSystem(*system_id))
+    }
+
+    fn get_systems(
+        &mut self,
+    ) -> Result<Vec<(SystemID, &mut (dyn SystemTrait<CS, CD> + 'static))>, EcsError> {
+        let sorted_systems = match &self.sorted_systems {
+            Some(sorted_systems) => sorted_systems,
+            None => {
+                let system_orderings: Vec<(SystemID, &SystemOrdering)> = self
+                    .system_orderings
+                    .iter()
+                    .map(|(system_id, ordering)| (*system_id, ordering))
+                    .collect();
+
+                self.sorted_systems
+                    .get_or_insert(sort_systems(&system_orderings)?)
+            }
+        };
+
+        let mut systems: HashMap<SystemID, &mut (dyn SystemTrait<CS, CD> + 'static)> = self
+            .systems
             .iter_mut()
             .map(|(system_id, system)| (*system_id, system.as_mut()))
-            .collect()
+            .collect();
+
+        Ok(sorted_systems
+            .iter()
+            .map(|system_id| {
+                let system = systems.remove(system_id).expect("Sorted system missing");
+                (*system_id, system)
+            })
+            .collect())
     }
 }
@@ -1,10 +1,12 @@
 mod heap_system_storage;
-
-use std::collections::HashMap;
+mod system_builder;
+mod system_ordering;
 
 pub use heap_system_storage::HeapSystemStorage;
+pub use system_builder::SystemBuilder;
+pub use system_ordering::{sort_systems, SystemLabel, SystemOrdering, SystemStage};
 
-use super::{ComponentStorage, EntityComponentDirectory, SystemID, SystemTrait};
+use super::{ComponentStorage, EcsError, EntityComponentDirectory, SystemID, SystemTrait};
 
 /// Trait for handling systems execution for a given EntityComponentSystem
 pub trait SystemStorage<CS, CD> {
@@ -14,7 +16,18 @@ pub trait SystemStorage<CS, CD> {
         CD: EntityComponentDirectory,
         T: SystemTrait<CS, CD> + 'static;
 
-    fn get_systems(&mut self) -> HashMap<SystemID, &mut (dyn SystemTrait<CS, CD> + 'static)>
+    fn get_system_ordering(&self, system_id: &SystemID) -> Result<&SystemOrdering, EcsError>;
+
+    fn get_system_ordering_mut(
+        &mut self,
+        system_id: &SystemID,
+    ) -> Result<&mut SystemOrdering, EcsError>;
+
+    /// Returns every system in execution order, as resolved from their stages and ordering constraints
+    #[allow(clippy::type_complexity)]
+    fn get_systems(
+        &mut self,
+    ) -> Result<Vec<(SystemID, &mut (dyn SystemTrait<CS, CD> + 'static))>, EcsError>
     where
         CS: ComponentStorage + 'static,
         CD: EntityComponentDirectory + 'static;
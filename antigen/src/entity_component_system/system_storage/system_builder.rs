@@ -0,0 +1,85 @@
+use std::marker::PhantomData;
+
+use crate::entity_component_system::{
+    ComponentStorage, EcsError, EntityComponentDirectory, SystemID, SystemLabel, SystemStage,
+};
+
+use super::{SystemOrdering, SystemStorage};
+
+/// Configures the stage and ordering constraints of a system after it has been inserted
+///
+/// ex. `ecs.push_system(GlobalPosition::new()).stage(SystemStage::Layout).after("anchors_margins")`
+///
+/// Configuring a system that isn't in storage is a no-op, with the error reported by `finish`.
+pub struct SystemBuilder<'a, SS, CS, CD>
+where
+    SS: SystemStorage<CS, CD>,
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    system_storage: &'a mut SS,
+    system_id: SystemID,
+    error: Option<EcsError>,
+    _phantom_data: PhantomData<(CS, CD)>,
+}
+
+impl<'a, SS, CS, CD> SystemBuilder<'a, SS, CS, CD>
+where
+    SS: SystemStorage<CS, CD>,
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    pub fn new(system_storage: &'a mut SS, system_id: SystemID) -> Self {
+        SystemBuilder {
+            system_storage,
+            system_id,
+            error: None,
+            _phantom_data: PhantomData,
+        }
+    }
+
+    pub fn get_id(&self) -> SystemID {
+        self.system_id
+    }
+
+    /// Returns this system's ID, or the first error encountered while configuring it
+    pub fn finish(self) -> Result<SystemID, EcsError> {
+        match self.error {
+            Some(err) => Err(err),
+            None => Ok(self.system_id),
+        }
+    }
+
+    /// Run this system in the given stage
+    pub fn stage(self, stage: SystemStage) -> Self {
+        self.with_ordering(|ordering| ordering.stage = stage)
+    }
+
+    /// Tag this system with a label that other systems can order themselves against
+    pub fn label(self, label: SystemLabel) -> Self {
+        self.with_ordering(|ordering| ordering.labels.push(label))
+    }
+
+    /// Run this system before every system with the given label
+    pub fn before(self, label: SystemLabel) -> Self {
+        self.with_ordering(|ordering| ordering.before.push(label))
+    }
+
+    /// Run this system after every system with the given label
+    pub fn after(self, label: SystemLabel) -> Self {
+        self.with_ordering(|ordering| ordering.after.push(label))
+    }
+
+    fn with_ordering<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(&mut SystemOrdering),
+    {
+        if self.error.is_none() {
+            match self.system_storage.get_system_ordering_mut(&self.system_id) {
+                Ok(ordering) => f(ordering),
+                Err(err) => self.error = Some(err),
+            }
+        }
+        self
+    }
+}
@@ -0,0 +1,218 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::entity_component_system::{EcsError, SystemID};
+
+/// Coarse phase of the frame that a system runs in
+///
+/// Stages run in declaration order; systems within a stage are ordered by their before / after constraints,
+/// falling back to insertion order.
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum SystemStage {
+    Input,
+    #[default]
+    Update,
+    Layout,
+    Render,
+    Cleanup,
+}
+
+/// Name used to reference a system, or group of systems, in ordering constraints
+pub type SystemLabel = &'static str;
+
+/// Ordering constraints for a single system
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct SystemOrdering {
+    pub stage: SystemStage,
+    pub labels: Vec<SystemLabel>,
+    pub before: Vec<SystemLabel>,
+    pub after: Vec<SystemLabel>,
+}
+
+/// Resolve a set of systems into an execution order that satisfies their stages and before / after constraints
+pub fn sort_systems(systems: &[(SystemID, &SystemOrdering)]) -> Result<Vec<SystemID>, EcsError> {
+    let mut labelled_systems: HashMap<SystemLabel, Vec<usize>> = HashMap::new();
+    for (i, (_, ordering)) in systems.iter().enumerate() {
+        for label in &ordering.labels {
+            labelled_systems.entry(label).or_default().push(i);
+        }
+    }
+
+    let get_labelled_systems = |system_id: &SystemID, label: &SystemLabel| {
+        labelled_systems.get(label).ok_or(EcsError::UnknownLabel {
+            system_id: *system_id,
+            label,
+        })
+    };
+
+    // Build dependency edges, rejecting any that contradict stage order
+    let mut successors: Vec<Vec<usize>> = vec![Vec::new(); systems.len()];
+    let mut predecessor_counts: Vec<usize> = vec![0; systems.len()];
+    for (i, (system_id, ordering)) in systems.iter().enumerate() {
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        for label in &ordering.before {
+            for j in get_labelled_systems(system_id, label)? {
+                edges.push((i, *j));
+            }
+        }
+        for label in &ordering.after {
+            for j in get_labelled_systems(system_id, label)? {
+                edges.push((*j, i));
+            }
+        }
+
+        for (from, to) in edges {
+            let (from_id, from_ordering) = systems[from];
+            let (to_id, to_ordering) = systems[to];
+
+            if from == to {
+                return Err(EcsError::SelfOrdering(from_id));
+            }
+
+            if from_ordering.stage > to_ordering.stage {
+                return Err(EcsError::StageOrdering {
+                    before: (from_id, from_ordering.stage),
+                    after: (to_id, to_ordering.stage),
+                });
+            }
+
+            if !successors[from].contains(&to) {
+                successors[from].push(to);
+                predecessor_counts[to] += 1;
+            }
+        }
+    }
+
+    // Kahn's algorithm, always picking the earliest ready system by stage and insertion order
+    let sort_key = |i: usize| {
+        let (system_id, ordering) = systems[i];
+        (ordering.stage, system_id, i)
+    };
+
+    let mut ready: BTreeSet<(SystemStage, SystemID, usize)> = (0..systems.len())
+        .filter(|i| predecessor_counts[*i] == 0)
+        .map(sort_key)
+        .collect();
+
+    let mut sorted: Vec<SystemID> = Vec::with_capacity(systems.len());
+    while let Some(key) = ready.iter().next().copied() {
+        ready.remove(&key);
+
+        let (_, system_id, i) = key;
+        sorted.push(system_id);
+
+        for successor in &successors[i] {
+            predecessor_counts[*successor] -= 1;
+            if predecessor_counts[*successor] == 0 {
+                ready.insert(sort_key(*successor));
+            }
+        }
+    }
+
+    if sorted.len() < systems.len() {
+        return Err(EcsError::OrderingCycle(find_cycle(
+            systems,
+            &successors,
+            &predecessor_counts,
+        )));
+    }
+
+    Ok(sorted)
+}
+
+/// Walk the unsorted remainder of the dependency graph to find a cycle
+fn find_cycle(
+    systems: &[(SystemID, &SystemOrdering)],
+    successors: &[Vec<usize>],
+    predecessor_counts: &[usize],
+) -> Vec<SystemID> {
+    let unsorted = |i: &usize| predecessor_counts[*i] > 0;
+
+    // Every unsorted system has an unsorted predecessor, so walking predecessors must revisit a system
+    let mut path: Vec<usize> = Vec::new();
+    let mut current = (0..systems.len())
+        .find(unsorted)
+        .expect("No unsorted systems");
+
+    while !path.contains(&current) {
+        path.push(current);
+        current = (0..systems.len())
+            .filter(unsorted)
+            .find(|candidate| successors[*candidate].contains(&current))
+            .expect("Unsorted system has no unsorted predecessor");
+    }
+
+    let start = path.iter().position(|i| *i == current).unwrap();
+    let mut cycle: Vec<usize> = path[start..].to_vec();
+    cycle.reverse();
+    cycle.push(cycle[0]);
+
+    cycle
+        .into_iter()
+        .map(|i| {
+            let (system_id, _) = systems[i];
+            system_id
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sort_systems, SystemOrdering, SystemStage};
+    use crate::entity_component_system::{EcsError, SystemID};
+
+    #[test]
+    fn sort() {
+        struct Input;
+        struct Layout;
+        struct Render;
+
+        let render = SystemID::next::<Render>();
+        let layout = SystemID::next::<Layout>();
+        let input = SystemID::next::<Input>();
+
+        let render_ordering = SystemOrdering {
+            stage: SystemStage::Render,
+            ..Default::default()
+        };
+        let layout_ordering = SystemOrdering {
+            stage: SystemStage::Layout,
+            labels: vec!["layout"],
+            ..Default::default()
+        };
+        let input_ordering = SystemOrdering {
+            stage: SystemStage::Layout,
+            before: vec!["layout"],
+            ..Default::default()
+        };
+
+        let sorted = sort_systems(&[
+            (render, &render_ordering),
+            (layout, &layout_ordering),
+            (input, &input_ordering),
+        ])
+        .unwrap();
+        assert_eq!(sorted, vec![input, layout, render]);
+
+        let cyclic_ordering = SystemOrdering {
+            stage: SystemStage::Layout,
+            labels: vec!["input"],
+            after: vec!["layout"],
+            before: vec!["layout"],
+        };
+        let err =
+            sort_systems(&[(layout, &layout_ordering), (input, &cyclic_ordering)]).unwrap_err();
+        assert_eq!(err, EcsError::OrderingCycle(vec![input, layout, input]));
+
+        let unknown_ordering = SystemOrdering {
+            after: vec!["missing"],
+            ..Default::default()
+        };
+        assert_eq!(
+            sort_systems(&[(input, &unknown_ordering)]),
+            Err(EcsError::UnknownLabel {
+                system_id: input,
+                label: "missing"
+            })
+        );
+    }
+}
@@ -33,9 +33,11 @@ impl Display for ComponentID {
 }
 
 /// Base component trait
-pub trait ComponentTrait: Debug + UpcastComponentTrait {}
+///
+/// Components are Send + Sync so that systems with non-conflicting access can run on separate threads.
+pub trait ComponentTrait: Debug + Send + Sync + UpcastComponentTrait {}
 
-impl<T> ComponentTrait for T where T: Debug + Any {}
+impl<T> ComponentTrait for T where T: Debug + Send + Sync + Any {}
 
 /// Trait for upcasting a component to an Any reference
 pub trait UpcastComponentTrait: Any {
@@ -60,8 +62,8 @@ where
 pub trait DowncastComponentTrait {
     type Target: UpcastComponentTrait;
 
-    fn as_data(component: &dyn ComponentTrait) -> &Self::Target;
-    fn as_mut_data(component: &mut dyn ComponentTrait) -> &mut Self::Target;
+    fn as_data(component: &dyn ComponentTrait) -> Option<&Self::Target>;
+    fn as_mut_data(component: &mut dyn ComponentTrait) -> Option<&mut Self::Target>;
 }
 
 impl<T> DowncastComponentTrait for T
@@ -70,24 +72,11 @@ where
 {
     type Target = T;
 
-    fn as_data(component: &dyn ComponentTrait) -> &T {
-        component.as_any().downcast_ref::<T>().unwrap_or_else(|| {
-            panic!(
-                "Failed to downcast component to type {}",
-                crate::core::type_name::type_name::<T>()
-            )
-        })
+    fn as_data(component: &dyn ComponentTrait) -> Option<&T> {
+        component.as_any().downcast_ref::<T>()
     }
 
-    fn as_mut_data(component: &mut dyn ComponentTrait) -> &mut T {
-        component
-            .as_mut_any()
-            .downcast_mut::<T>()
-            .unwrap_or_else(|| {
-                panic!(
-                    "Failed to downcast component to type {}",
-                    crate::core::type_name::type_name::<T>()
-                )
-            })
+    fn as_mut_data(component: &mut dyn ComponentTrait) -> Option<&mut T> {
+        component.as_mut_any().downcast_mut::<T>()
     }
 }
@@ -1,25 +1,68 @@
-use crate::core::uid::UID;
-use std::{fmt::Display, sync::atomic::AtomicUsize, sync::atomic::Ordering};
+use crate::{core::uid::UID, entity_component_system::EcsError};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
 
-#[derive(Debug, Copy, Clone, Hash, Ord, PartialOrd, Eq, PartialEq)]
+/// Generational entity handle
+///
+/// The low bits store an index that directories recycle once its entity is destroyed,
+/// and the high bits store a generation that's bumped on each reuse to invalidate stale handles.
+#[derive(Debug, Copy, Clone, Hash, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct EntityID(pub UID);
 
 impl EntityID {
-    pub fn next() -> Self {
-        static COUNTER: AtomicUsize = AtomicUsize::new(0);
-        EntityID(COUNTER.fetch_add(1, Ordering::Relaxed))
+    /// Number of low bits used to store the index; the remaining high bits store the generation
+    const INDEX_BITS: u32 = UID::BITS / 2;
+    const INDEX_MASK: UID = (1 << Self::INDEX_BITS) - 1;
+    const GENERATION_MASK: UID = UID::MAX >> Self::INDEX_BITS;
+
+    /// Placeholder ID that never refers to a live entity
+    pub const NULL: EntityID = EntityID(UID::MAX);
+
+    /// Pack an index and generation into an ID
+    pub fn from_index_generation(index: usize, generation: usize) -> Result<Self, EcsError> {
+        // The maximum index is reserved for NULL
+        if index >= Self::INDEX_MASK {
+            return Err(EcsError::IDCapacity {
+                id_type: "EntityID",
+                index,
+            });
+        }
+
+        Ok(EntityID(
+            ((generation & Self::GENERATION_MASK) << Self::INDEX_BITS) | index,
+        ))
+    }
+
+    pub fn get_index(&self) -> usize {
+        let EntityID(entity_id) = *self;
+        entity_id & Self::INDEX_MASK
+    }
+
+    pub fn get_generation(&self) -> usize {
+        let EntityID(entity_id) = *self;
+        entity_id >> Self::INDEX_BITS
+    }
+
+    /// The ID that will refer to the next entity allocated at this index
+    pub fn next_generation(&self) -> Self {
+        let generation = (self.get_generation() + 1) & Self::GENERATION_MASK;
+        EntityID((generation << Self::INDEX_BITS) | self.get_index())
     }
 }
 
 impl Default for EntityID {
     fn default() -> Self {
-        EntityID::next()
+        EntityID::NULL
     }
 }
 
 impl Display for EntityID {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let EntityID(entity_id) = self;
-        write!(f, "{}", entity_id)
+        if *self == EntityID::NULL {
+            write!(f, "NULL")
+        } else {
+            write!(f, "{}v{}", self.get_index(), self.get_generation())
+        }
     }
 }
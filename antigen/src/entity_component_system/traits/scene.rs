@@ -1,13 +1,13 @@
 use crate::entity_component_system::{
     system_storage::SystemStorage,
-    SystemInterface, EntityComponentSystem, SystemRunner,
+    SystemInterface, EntityComponentSystem, SystemRunner, EcsError,
 ComponentStorage, EntityComponentDirectory};
 
 /// A collection of systems and assembled entities
 pub trait Scene {
     fn load<'a, CS, CD, SS, SR>(
         ecs: &'a mut EntityComponentSystem<CS, CD, SS, SR>,
-    ) -> Result<(), String>
+    ) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory + 'static,
@@ -17,19 +17,20 @@ pub trait Scene {
         Self::register_systems(ecs)?;
         let mut entity_component_database = ecs.get_system_interface();
         Self::create_entities(&mut entity_component_database)?;
+        entity_component_database.apply_commands()?;
         Ok(())
     }
 
     fn register_systems<CS, CD, SS, SR>(
         db: &mut EntityComponentSystem<CS, CD, SS, SR>,
-    ) -> Result<(), String>
+    ) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory + 'static,
         SS: SystemStorage<CS, CD> + 'static,
         SR: SystemRunner + 'static;
 
-    fn create_entities<CS, CD>(db: &mut SystemInterface<CS, CD>) -> Result<(), String>
+    fn create_entities<CS, CD>(db: &mut SystemInterface<CS, CD>) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory;
@@ -5,16 +5,45 @@ use std::{
 };
 
 use crate::{
-    core::uid::UID, entity_component_system::ComponentStorage,
-    entity_component_system::EntityComponentDirectory, entity_component_system::SystemInterface,
+    core::uid::UID, entity_component_system::ComponentAccess,
+    entity_component_system::ComponentHooks, entity_component_system::ComponentStorage,
+    entity_component_system::EcsError,
+    entity_component_system::EntityComponentDirectory,
+    entity_component_system::SystemInterface,
 };
 
 #[derive(Debug, Clone)]
 pub enum SystemError {
     Err(String),
+    Ecs(EcsError),
     Quit,
 }
 
+impl Display for SystemError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SystemError::Err(err) => write!(f, "{}", err),
+            SystemError::Ecs(err) => write!(f, "{}", err),
+            SystemError::Quit => write!(f, "Quit"),
+        }
+    }
+}
+
+impl std::error::Error for SystemError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SystemError::Ecs(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<EcsError> for SystemError {
+    fn from(err: EcsError) -> Self {
+        SystemError::Ecs(err)
+    }
+}
+
 impl From<String> for SystemError {
     fn from(string: String) -> Self {
         SystemError::Err(string)
@@ -59,10 +88,24 @@ impl Display for SystemID {
 }
 
 /// A monolithic set of logic that runs on sets of entities with specific component layouts
-pub trait SystemTrait<CS, CD>
+///
+/// Systems are Send so that those with non-conflicting access can be run on a thread pool.
+pub trait SystemTrait<CS, CD>: Send
 where
     CS: ComponentStorage,
     CD: EntityComponentDirectory,
 {
     fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>;
+
+    /// The component types this system reads and writes, used to run it alongside non-conflicting systems
+    ///
+    /// Systems that declare an access set may only read and write component data of the declared types,
+    /// and must queue entity and component creation or destruction through SystemInterface::commands.
+    /// Returning None runs the system exclusively.
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        None
+    }
+
+    /// Register callbacks for component lifecycle events, called when the system is pushed
+    fn register_component_hooks(&self, _component_hooks: &mut ComponentHooks<CS, CD>) {}
 }
@@ -2,8 +2,9 @@ use std::{
     iter::Sum,
     ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
 };
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq, Serialize, Deserialize)]
 pub struct ColorRGB<T>(pub T, pub T, pub T)
 where
     T: Copy + Clone + PartialOrd + PartialEq;
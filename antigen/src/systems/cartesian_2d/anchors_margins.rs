@@ -6,7 +6,7 @@ use crate::{
     components::Size,
     entity_component_system::system_interface::SystemInterface,
     entity_component_system::EntityID,
-    entity_component_system::{SystemError, SystemTrait},
+    entity_component_system::{ComponentAccess, SystemError, SystemTrait, With},
     primitive_types::Vector2I,
 };
 use crate::{
@@ -40,25 +40,16 @@ where
         CD: EntityComponentDirectory,
     {
         // Fetch anchor entities
-        let anchor_entities =
-            db.entity_component_directory
-                .get_entities_by_predicate(|entity_id| {
-                    db.entity_component_directory
-                        .entity_has_component::<Anchors>(entity_id)
-                        && db
-                            .entity_component_directory
-                            .entity_has_component::<Position>(entity_id)
-                        && db
-                            .entity_component_directory
-                            .entity_has_component::<ParentEntity>(entity_id)
-                });
+        let anchor_entities: Vec<(EntityID, EntityID)> = db
+            .query_filtered::<(EntityID, &ParentEntity), (With<Anchors>, With<Position>)>()?
+            .into_iter()
+            .map(|(entity_id, parent_entity)| (entity_id, **parent_entity))
+            .collect();
 
         // Sort into a HashMap based on tree depth
         let mut tree_depth_entities: HashMap<i64, Vec<EntityID>> = HashMap::new();
 
-        for entity_id in &anchor_entities {
-            let parent_id: EntityID = **db.get_entity_component::<ParentEntity>(*entity_id)?;
-
+        for (entity_id, parent_id) in anchor_entities {
             let mut candidate_id = parent_id;
             let mut depth = 0i64;
             loop {
@@ -73,10 +64,10 @@ where
 
             match tree_depth_entities.get_mut(&depth) {
                 Some(tree_depth) => {
-                    tree_depth.push(*entity_id);
+                    tree_depth.push(entity_id);
                 }
                 None => {
-                    tree_depth_entities.insert(depth, vec![*entity_id]);
+                    tree_depth_entities.insert(depth, vec![entity_id]);
                 }
             };
         }
@@ -128,4 +119,15 @@ where
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_read::<ParentEntity>()
+                .with_read::<Anchors>()
+                .with_read::<Margins>()
+                .with_write::<Position>()
+                .with_write::<Size>(),
+        )
+    }
 }
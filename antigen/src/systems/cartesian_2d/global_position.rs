@@ -5,7 +5,7 @@ use crate::{
 };
 use crate::{
     entity_component_system::{
-        ComponentStorage, EntityComponentDirectory, SystemError, SystemTrait,
+        ComponentAccess, ComponentStorage, EntityComponentDirectory, SystemError, SystemTrait, With,
     },
     primitive_types::Vector2I,
 };
@@ -35,22 +35,13 @@ where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
     {
-        let entities = db
-            .entity_component_directory
-            .get_entities_by_predicate(|entity_id| {
-                db.entity_component_directory
-                    .entity_has_component::<Position>(entity_id)
-                    && db
-                        .entity_component_directory
-                        .entity_has_component::<ParentEntity>(entity_id)
-                    && db
-                        .entity_component_directory
-                        .entity_has_component::<GlobalPositionData>(entity_id)
-            });
+        let entities: Vec<(EntityID, EntityID, Vector2I)> = db
+            .query_filtered::<(EntityID, &ParentEntity, &Position), With<GlobalPositionData>>()?
+            .into_iter()
+            .map(|(entity_id, parent_entity, position)| (entity_id, **parent_entity, **position))
+            .collect();
 
-        for entity_id in entities {
-            let parent_entity: EntityID = **db.get_entity_component::<ParentEntity>(entity_id)?;
-            let mut global_position: Vector2I = **db.get_entity_component::<Position>(entity_id)?;
+        for (entity_id, parent_entity, mut global_position) in entities {
             let mut candidate_id = parent_entity;
 
             loop {
@@ -74,4 +65,13 @@ where
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_read::<ParentEntity>()
+                .with_read::<Position>()
+                .with_write::<GlobalPositionData>(),
+        )
+    }
 }
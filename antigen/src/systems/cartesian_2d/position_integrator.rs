@@ -1,13 +1,10 @@
+use crate::entity_component_system::{
+    ComponentAccess, ComponentStorage, EntityComponentDirectory, SystemError, SystemTrait,
+};
 use crate::{
     components::{Position, Velocity},
     entity_component_system::system_interface::SystemInterface,
 };
-use crate::{
-    entity_component_system::{
-        ComponentStorage, EntityComponentDirectory, SystemError, SystemTrait,
-    },
-    primitive_types::Vector2I,
-};
 
 #[derive(Debug)]
 pub struct PositionIntegrator;
@@ -34,23 +31,18 @@ where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
     {
-        let entities = db
-            .entity_component_directory
-            .get_entities_by_predicate(|entity_id| {
-                db.entity_component_directory
-                    .entity_has_component::<Position>(entity_id)
-                    && db
-                        .entity_component_directory
-                        .entity_has_component::<Velocity>(entity_id)
-            });
-
-        for entity_id in entities {
-            let velocity: Vector2I = **db.get_entity_component::<Velocity>(entity_id)?;
-
-            let position = db.get_entity_component_mut::<Position>(entity_id)?;
-            **position += velocity;
+        for (mut position, velocity) in db.query::<(&mut Position, &Velocity)>()? {
+            **position += **velocity;
         }
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_write::<Position>()
+                .with_read::<Velocity>(),
+        )
+    }
 }
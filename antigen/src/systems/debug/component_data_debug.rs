@@ -1,5 +1,6 @@
 use crate::{
     components::DebugComponentDataList, components::DebugExclude, components::IntRange,
+    components::ListData, components::RichText,
     entity_component_system::system_interface::SystemInterface,
     entity_component_system::ComponentID, entity_component_system::ComponentStorage,
     entity_component_system::EntityComponentDirectory, entity_component_system::EntityID,
@@ -11,6 +12,62 @@ use crate::{
 
 use super::{ComponentInspectorEvent, EntityInspectorEvent};
 
+/// Convert the Debug output of a component into RichText markup,
+/// coloring field names, strings, numbers and keyword values
+fn highlight_debug_string(string: &str) -> String {
+    let chars: Vec<char> = string.chars().collect();
+    let mut markup = String::new();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let char = chars[i];
+
+        let color = if char == '"' || char == '\'' {
+            // String or char literal, skipping escaped quotes
+            i += 1;
+            while i < chars.len() && chars[i] != char {
+                if chars[i] == '\\' {
+                    i += 1;
+                }
+                i += 1;
+            }
+            i = (i + 1).min(chars.len());
+            Some("green")
+        } else if char.is_ascii_digit() {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '.') {
+                i += 1;
+            }
+            Some("yellow")
+        } else if char.is_alphabetic() || char == '_' {
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+
+            let word: String = chars[start..i].iter().collect();
+            let is_field = chars.get(i) == Some(&':') && chars.get(i + 1) != Some(&':');
+            if is_field {
+                Some("cyan")
+            } else if word == "true" || word == "false" || word == "None" {
+                Some("magenta")
+            } else {
+                None
+            }
+        } else {
+            i += 1;
+            None
+        };
+
+        let token = RichText::escape(&chars[start..i].iter().collect::<String>());
+        match color {
+            Some(color) => markup += &format!("[{}]{}[/]", color, token),
+            None => markup += &token,
+        }
+    }
+
+    markup
+}
+
 #[derive(Debug)]
 pub struct ComponentDataDebug;
 
@@ -82,10 +139,8 @@ where
                             .get_entity_component_data_id(inspected_entity, inspected_component)?;
 
                         let component_data_string = db
-                            .component_storage
+                            .get_component_storage()?
                             .get_component_data_string(&component_data_id)?;
-                        let component_data_string =
-                            format!("{}: {}", component_data_id, component_data_string);
 
                         let entity_component_debug_entities = db
                             .entity_component_directory
@@ -98,8 +153,24 @@ where
                             });
 
                         for entity_id in entity_component_debug_entities {
+                            // Lists displaying rich text get highlighted output
+                            let rich_text = match db.get_entity_component::<ListData>(entity_id) {
+                                Ok(list_data) => list_data.get_rich_text(),
+                                Err(_) => false,
+                            };
+
+                            let component_data_string = if rich_text {
+                                format!(
+                                    "[dim]{}:[/] {}",
+                                    component_data_id,
+                                    highlight_debug_string(&component_data_string)
+                                )
+                            } else {
+                                format!("{}: {}", component_data_id, component_data_string)
+                            };
+
                             *db.get_entity_component_mut::<Vec<String>>(entity_id)? =
-                                vec![component_data_string.clone()];
+                                vec![component_data_string];
                         }
                     }
                 }
@@ -109,3 +180,25 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::components::RichText;
+
+    use super::highlight_debug_string;
+
+    #[test]
+    fn highlight_debug_string_markup() {
+        let debug_string =
+            "Foo {\n    name: \"a [b]\",\n    size: Vector2I(1, -2.5),\n    parent: None,\n}";
+
+        let markup = highlight_debug_string(debug_string);
+        assert_eq!(
+            markup,
+            "Foo {\n    [cyan]name[/]: [green]\"a [[b]\"[/],\n    [cyan]size[/]: Vector2I([yellow]1[/], -[yellow]2.5[/]),\n    [cyan]parent[/]: [magenta]None[/],\n}"
+        );
+
+        // Highlighting only adds styling
+        assert_eq!(RichText::parse(&markup).to_plain_string(), debug_string);
+    }
+}
@@ -6,7 +6,7 @@ use crate::{
 };
 use crate::{
     components::EventQueue,
-    entity_component_system::{SystemError, SystemTrait},
+    entity_component_system::{ComponentAccess, SystemError, SystemTrait},
 };
 
 use super::EntityInspectorEvent;
@@ -50,15 +50,16 @@ where
                 });
 
         if let Some(component_inspector_entity) = component_inspector_entity {
-            let event_queue: &mut Vec<ComponentInspectorEvent> = db
+            let mut event_queue = db
                 .get_entity_component_mut::<EventQueue<ComponentInspectorEvent>>(
                     component_inspector_entity,
                 )?;
 
             let mut events: Vec<ComponentInspectorEvent> = Vec::new();
-            events.append(event_queue);
+            events.append(&mut event_queue);
 
-            let int_range = db.get_entity_component_mut::<IntRange>(component_inspector_entity)?;
+            let mut int_range =
+                db.get_entity_component_mut::<IntRange>(component_inspector_entity)?;
             int_range.set_range(0..debug_entities.len() as i64);
             for event in events {
                 let ComponentInspectorEvent::SetInspectedComponent(index) = event;
@@ -122,4 +123,13 @@ where
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_write::<EventQueue<ComponentInspectorEvent>>()
+                .with_write::<IntRange>()
+                .with_write::<Vec<String>>(),
+        )
+    }
 }
@@ -4,7 +4,7 @@ use crate::{
     components::EventQueue,
     components::IntRange,
     components::Name,
-    entity_component_system::{SystemError, SystemTrait},
+    entity_component_system::{ComponentAccess, SystemError, SystemTrait},
 };
 use crate::{
     components::{DebugEntityList, DebugExclude},
@@ -52,15 +52,14 @@ where
                 });
 
         if let Some(entity_inspector_entity) = entity_inspector_entity {
-            let event_queue: &mut Vec<EntityInspectorEvent> = db
-                .get_entity_component_mut::<EventQueue<EntityInspectorEvent>>(
-                    entity_inspector_entity,
-                )?;
+            let mut event_queue = db.get_entity_component_mut::<EventQueue<EntityInspectorEvent>>(
+                entity_inspector_entity,
+            )?;
 
             let mut events: Vec<EntityInspectorEvent> = Vec::new();
-            events.append(event_queue);
+            events.append(&mut event_queue);
 
-            let int_range = db.get_entity_component_mut::<IntRange>(entity_inspector_entity)?;
+            let mut int_range = db.get_entity_component_mut::<IntRange>(entity_inspector_entity)?;
             int_range.set_range(0..debug_entities.len() as i64);
             for event in events {
                 let EntityInspectorEvent::SetInspectedEntity(index) = event;
@@ -100,4 +99,14 @@ where
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_read::<Name>()
+                .with_write::<EventQueue<EntityInspectorEvent>>()
+                .with_write::<IntRange>()
+                .with_write::<Vec<String>>(),
+        )
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
     components::Name,
-    entity_component_system::{SystemError, SystemTrait},
+    entity_component_system::{ComponentAccess, SystemError, SystemTrait},
 };
 use crate::{
     components::{ChildEntitiesData, DebugExclude, DebugSceneTree, ParentEntity},
@@ -123,4 +123,13 @@ where
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_read::<Name>()
+                .with_read::<ChildEntitiesData>()
+                .with_write::<Vec<String>>(),
+        )
+    }
 }
@@ -3,8 +3,8 @@ use std::{collections::HashMap, fmt::Debug, time::Duration};
 use crate::{
     components::{DebugSystemList, EventQueue, IntRange, SystemProfilingData},
     entity_component_system::{
-        system_interface::SystemInterface, ComponentStorage, EntityComponentDirectory, SystemError,
-        SystemID, SystemTrait,
+        system_interface::SystemInterface, ComponentAccess, ComponentStorage,
+        EntityComponentDirectory, SystemError, SystemID, SystemTrait,
     },
 };
 
@@ -59,15 +59,16 @@ where
             if let Some(system_inspector_entity) = system_inspector_entity {
                 let mut events: Vec<SystemInspectorEvent> = Vec::new();
                 {
-                    let event_queue: &mut Vec<SystemInspectorEvent> = db
+                    let mut event_queue = db
                         .get_entity_component_mut::<EventQueue<SystemInspectorEvent>>(
                             system_inspector_entity,
                         )?;
 
-                    events.append(event_queue);
+                    events.append(&mut event_queue);
                 }
 
-                let int_range = db.get_entity_component_mut::<IntRange>(system_inspector_entity)?;
+                let mut int_range =
+                    db.get_entity_component_mut::<IntRange>(system_inspector_entity)?;
                 int_range.set_range(0..system_durations.len() as i64);
                 for event in events {
                     let SystemInspectorEvent::SetInspectedSystem(index) = event;
@@ -123,4 +124,14 @@ where
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_read::<SystemProfilingData>()
+                .with_write::<EventQueue<SystemInspectorEvent>>()
+                .with_write::<IntRange>()
+                .with_write::<Vec<String>>(),
+        )
+    }
 }
@@ -4,7 +4,7 @@ use crate::entity_component_system::system_interface::SystemInterface;
 use crate::{
     components::EventQueue,
     entity_component_system::{
-        ComponentStorage, EntityComponentDirectory, SystemError, SystemTrait,
+        ComponentAccess, ComponentStorage, EntityComponentDirectory, SystemError, SystemTrait,
     },
 };
 
@@ -40,27 +40,21 @@ impl<CS, CD, T> SystemTrait<CS, CD> for EventConsumer<T>
 where
     CS: ComponentStorage,
     CD: EntityComponentDirectory,
-    T: Debug + 'static,
+    T: Debug + Send + Sync + 'static,
 {
     fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
     {
-        let event_queue_entities =
-            db.entity_component_directory
-                .get_entities_by_predicate(|entity_id| {
-                    db.entity_component_directory
-                        .entity_has_component::<EventQueue<T>>(entity_id)
-                });
-
-        for entity_id in event_queue_entities {
-            let event_queue: &mut Vec<T> =
-                db.get_entity_component_mut::<EventQueue<T>>(entity_id)?;
-
+        for mut event_queue in db.query::<&mut EventQueue<T>>()? {
             event_queue.clear();
         }
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(ComponentAccess::new().with_write::<EventQueue<T>>())
+    }
 }
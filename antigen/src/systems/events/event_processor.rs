@@ -3,7 +3,8 @@ use std::fmt::Debug;
 use crate::{
     components::EventQueue,
     entity_component_system::{
-        ComponentStorage, EntityComponentDirectory, EntityID, SystemError, SystemTrait,
+        ComponentAccess, ComponentStorage, EntityComponentDirectory, EntityID, SystemError,
+        SystemTrait,
     },
 };
 use crate::{components::EventTargets, entity_component_system::system_interface::SystemInterface};
@@ -31,8 +32,8 @@ impl<CS, CD, O, I> SystemTrait<CS, CD> for EventProcessor<O, I>
 where
     CS: ComponentStorage,
     CD: EntityComponentDirectory,
-    O: Debug + Copy + 'static,
-    I: Debug + Copy + 'static,
+    O: Debug + Copy + Send + Sync + 'static,
+    I: Debug + Copy + Send + Sync + 'static,
 {
     fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
     where
@@ -52,8 +53,8 @@ where
         for output_entity in output_entities {
             let events: Vec<O>;
             {
-                let event_queue: &mut Vec<O> =
-                    db.get_entity_component_mut::<EventQueue<O>>(output_entity)?;
+                let event_queue: &Vec<O> =
+                    db.get_entity_component::<EventQueue<O>>(output_entity)?;
 
                 events = event_queue.clone();
             }
@@ -73,12 +74,20 @@ where
                 .collect();
 
             for event_target in event_targets {
-                let event_queue: &mut Vec<I> =
-                    db.get_entity_component_mut::<EventQueue<I>>(event_target)?;
+                let mut event_queue = db.get_entity_component_mut::<EventQueue<I>>(event_target)?;
                 event_queue.append(&mut events.clone());
             }
         }
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_write::<EventQueue<O>>()
+                .with_read::<EventTargets>()
+                .with_write::<EventQueue<I>>(),
+        )
+    }
 }
@@ -0,0 +1,139 @@
+use crate::{
+    components::{EventQueue, InputRecordingData, InputRecordingMode},
+    core::events::AntigenInputEvent,
+    entity_component_system::{
+        system_interface::SystemInterface, ComponentAccess, ComponentStorage,
+        EntityComponentDirectory, SystemError, SystemTrait,
+    },
+};
+
+/// Captures the contents of each input event queue per frame, and replays them in place of live input
+///
+/// Should run after input systems have filled the queue, and before anything that consumes it.
+#[derive(Debug)]
+pub struct InputRecorder;
+
+impl Default for InputRecorder {
+    fn default() -> Self {
+        InputRecorder
+    }
+}
+
+impl InputRecorder {
+    pub fn new() -> Self {
+        InputRecorder
+    }
+}
+
+impl<CS, CD> SystemTrait<CS, CD> for InputRecorder
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        for (mut event_queue, mut input_recording) in
+            db.query::<(&mut EventQueue<AntigenInputEvent>, &mut InputRecordingData)>()?
+        {
+            match input_recording.get_mode() {
+                InputRecordingMode::Idle => (),
+                InputRecordingMode::Recording => input_recording.push_frame(event_queue.to_vec()),
+                InputRecordingMode::Playback { .. } => {
+                    if let Some(events) = input_recording.next_frame() {
+                        event_queue.clear();
+                        event_queue.extend_from_slice(events);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_write::<EventQueue<AntigenInputEvent>>()
+                .with_write::<InputRecordingData>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{EventQueue, InputRecordingData, InputRecordingMode},
+        core::events::AntigenInputEvent,
+        entity_component_system::{
+            ComponentHooks, HeapComponentStorage, SingleThreadedDirectory, SystemInterface,
+            SystemTrait,
+        },
+    };
+
+    use super::InputRecorder;
+
+    #[test]
+    fn input_recorder() {
+        let component_hooks = ComponentHooks::new();
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let entity_id = db.create_entity(None).unwrap();
+        db.insert_entity_component(entity_id, EventQueue::<AntigenInputEvent>::default())
+            .unwrap();
+        db.insert_entity_component(entity_id, InputRecordingData::new())
+            .unwrap()
+            .start_recording();
+
+        let mut input_recorder = InputRecorder::new();
+        let frames = vec![
+            vec![AntigenInputEvent::MouseScroll { delta: 1 }],
+            vec![],
+            vec![AntigenInputEvent::MouseScroll { delta: -1 }],
+        ];
+
+        let set_events = |db: &mut SystemInterface<_, _>, events: &[AntigenInputEvent]| {
+            let mut event_queue = db
+                .get_entity_component_mut::<EventQueue<AntigenInputEvent>>(entity_id)
+                .unwrap();
+            event_queue.clear();
+            event_queue.extend_from_slice(events);
+        };
+
+        for events in &frames {
+            set_events(&mut db, events);
+            input_recorder.run(&mut db).unwrap();
+        }
+
+        db.get_entity_component_mut::<InputRecordingData>(entity_id)
+            .unwrap()
+            .start_playback();
+
+        // Recorded input replaces live input until the recording runs out
+        for events in &frames {
+            set_events(&mut db, &[AntigenInputEvent::MousePress { button_mask: 1 }]);
+            input_recorder.run(&mut db).unwrap();
+            assert_eq!(
+                **db.get_entity_component::<EventQueue<AntigenInputEvent>>(entity_id)
+                    .unwrap(),
+                *events
+            );
+        }
+
+        input_recorder.run(&mut db).unwrap();
+        assert_eq!(
+            db.get_entity_component::<InputRecordingData>(entity_id)
+                .unwrap()
+                .get_mode(),
+            InputRecordingMode::Idle
+        );
+    }
+}
@@ -3,8 +3,8 @@ use crate::{
     components::{GlobalPositionData, ParentEntity, Position, Window},
     core::events::AntigenInputEvent,
     entity_component_system::{
-        system_interface::SystemInterface, ComponentStorage, EntityComponentDirectory, SystemError,
-        SystemTrait,
+        system_interface::SystemInterface, ComponentAccess, ComponentStorage,
+        EntityComponentDirectory, SystemError, SystemTrait,
     },
     primitive_types::Vector2I,
 };
@@ -99,4 +99,16 @@ where
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_read::<EventQueue<AntigenInputEvent>>()
+                .with_read::<ParentEntity>()
+                .with_read::<Window>()
+                .with_read::<GlobalPositionData>()
+                .with_read::<Position>()
+                .with_write::<LocalMousePositionData>(),
+        )
+    }
 }
@@ -1,3 +1,5 @@
+mod input_recorder;
 mod local_mouse_position;
 
+pub use input_recorder::*;
 pub use local_mouse_position::*;
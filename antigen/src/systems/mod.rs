@@ -4,6 +4,7 @@ mod events;
 mod input;
 mod rendering;
 mod scene_tree;
+mod serialization;
 mod user_interface;
 
 pub use cartesian_2d::*;
@@ -12,4 +13,5 @@ pub use events::*;
 pub use input::*;
 pub use rendering::*;
 pub use scene_tree::*;
+pub use serialization::*;
 pub use user_interface::*;
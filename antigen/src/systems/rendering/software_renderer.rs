@@ -1,12 +1,12 @@
-use crate::components::{Control, SoftwareFramebuffer};
+use crate::components::{CellFramebuffer, Control};
 use crate::{
     components::{
         CPUShader, CPUShaderInput, ChildEntitiesData, GlobalPositionData, Position, Size, Window,
         ZIndex,
     },
     entity_component_system::{
-        system_interface::SystemInterface, ComponentStorage, EntityComponentDirectory, EntityID,
-        SystemError, SystemTrait,
+        system_interface::SystemInterface, ComponentAccess, ComponentStorage,
+        EntityComponentDirectory, EntityID, SystemError, SystemTrait, With,
     },
     primitive_types::ColorRGB,
     primitive_types::ColorRGBF,
@@ -18,7 +18,7 @@ pub struct SoftwareRenderer;
 
 impl SoftwareRenderer {
     fn render_rect(
-        framebuffer: &mut SoftwareFramebuffer<ColorRGBF>,
+        framebuffer: &mut CellFramebuffer,
         window_size: Vector2I,
         position: Vector2I,
         size: Vector2I,
@@ -47,7 +47,7 @@ impl SoftwareRenderer {
                 let local_pos = Vector2I(rx - pos_x, ry - pos_y);
                 let CPUShader(color_shader) = color_shader;
                 if let Some(color) = color_shader(CPUShaderInput::new(local_pos, size, color)) {
-                    framebuffer.draw(rx, ry, window_width, color, z);
+                    framebuffer.draw_background(rx, ry, window_width, color, z);
                 }
             }
         }
@@ -65,40 +65,11 @@ where
         CD: EntityComponentDirectory,
     {
         // Fetch window entity
-        let window_entity = db
-            .entity_component_directory
-            .get_entity_by_predicate(|entity_id| {
-                db.entity_component_directory
-                    .entity_has_component::<Window>(entity_id)
-                    && db
-                        .entity_component_directory
-                        .entity_has_component::<Size>(entity_id)
-            })
-            .ok_or("No window entity")?;
-
-        let window_width: i64;
-        let window_height: i64;
-        {
-            let size = db.get_entity_component::<Size>(window_entity)?;
-
-            let Vector2I(width, height) = **size;
-
-            window_width = width as i64;
-            window_height = height as i64;
-        }
-
-        // Fetch color buffer entity
-        let cpu_framebuffer_entity = db
-            .entity_component_directory
-            .get_entity_by_predicate(|entity_id| {
-                db.entity_component_directory
-                    .entity_has_component::<SoftwareFramebuffer<ColorRGBF>>(entity_id)
-            })
-            .unwrap_or_else(|| panic!("No CPU framebuffer component"));
+        let (window_entity, window_size) = db
+            .query_single_filtered::<(EntityID, &Size), With<Window>>()
+            .map(|(entity_id, size)| (entity_id, **size))?;
 
-        let cell_count = (window_width * window_height) as usize;
-        db.get_entity_component_mut::<SoftwareFramebuffer<ColorRGBF>>(cpu_framebuffer_entity)?
-            .resize(cell_count);
+        let Vector2I(window_width, window_height) = window_size;
 
         // Recursively traverse parent-child tree and populate Z-ordered list of controls
         let mut control_entities: Vec<(EntityID, i64)> = Vec::new();
@@ -140,13 +111,11 @@ where
         populate_control_entities(db, window_entity, &mut control_entities, 0)?;
         control_entities.sort();
 
-        // Render Entities
-        db.get_entity_component_mut::<SoftwareFramebuffer<ColorRGBF>>(cpu_framebuffer_entity)?
-            .clear();
-
+        // Gather render data
+        let mut rects: Vec<(Vector2I, Vector2I, ColorRGBF, CPUShader, i64)> = Vec::new();
         for (entity_id, z) in control_entities {
             // Get Position
-            let Vector2I(x, y) = if let Ok(global_position) =
+            let position = if let Ok(global_position) =
                 db.get_entity_component::<GlobalPositionData>(entity_id)
             {
                 **global_position
@@ -170,15 +139,24 @@ where
             };
 
             // Get size
-            let Vector2I(width, height) = **db.get_entity_component::<Size>(entity_id)?;
+            let size = **db.get_entity_component::<Size>(entity_id)?;
 
+            rects.push((position, size, color, shader, z));
+        }
+
+        // Render Entities
+        let mut framebuffer = db.query_single::<&mut CellFramebuffer>()?;
+
+        let cell_count = (window_width * window_height) as usize;
+        framebuffer.resize(cell_count);
+        framebuffer.clear_backgrounds();
+
+        for (position, size, color, shader, z) in rects {
             Self::render_rect(
-                db.get_entity_component_mut::<SoftwareFramebuffer<ColorRGBF>>(
-                    cpu_framebuffer_entity,
-                )?,
-                Vector2I(window_width, window_height),
-                Vector2I(x, y),
-                Vector2I(width, height),
+                &mut framebuffer,
+                window_size,
+                position,
+                size,
                 color,
                 shader,
                 z,
@@ -187,4 +165,20 @@ where
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_read::<Window>()
+                .with_read::<Size>()
+                .with_read::<Control>()
+                .with_read::<ZIndex>()
+                .with_read::<ChildEntitiesData>()
+                .with_read::<GlobalPositionData>()
+                .with_read::<Position>()
+                .with_read::<ColorRGBF>()
+                .with_read::<CPUShader>()
+                .with_write::<CellFramebuffer>(),
+        )
+    }
 }
@@ -1,69 +1,90 @@
-use crate::components::{Control, SoftwareFramebuffer};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::components::{
+    CellAttributes, CellFramebuffer, Control, Glyph, RichText, TextColor, TextLayout, TextSpan,
+    TextStyle, TAB_WIDTH,
+};
 use crate::{
     components::{ChildEntitiesData, GlobalPositionData, Position, Size, Window, ZIndex},
     entity_component_system::{
-        system_interface::SystemInterface, ComponentStorage, EntityComponentDirectory, EntityID,
-        SystemError, SystemTrait,
+        system_interface::SystemInterface, ComponentAccess, ComponentStorage,
+        EntityComponentDirectory, EntityID, SystemError, SystemTrait,
     },
     primitive_types::Vector2I,
 };
 
-const TAB_WIDTH: i64 = 4;
-
 #[derive(Debug)]
 pub struct StringRenderer;
 
 impl StringRenderer {
     fn render_string(
-        framebuffer: &mut SoftwareFramebuffer<char>,
+        framebuffer: &mut CellFramebuffer,
         window_size: Vector2I,
         position: Vector2I,
-        string: &str,
+        spans: &[TextSpan],
         z: i64,
     ) {
         let Vector2I(window_width, window_height) = window_size;
-        let Vector2I(x, mut y) = position;
+        let Vector2I(start_x, mut y) = position;
 
-        let len = string.len() as i64;
-
-        let mut new_x = x;
-        let mut new_str = string.to_string();
-        if x < -len {
-            new_str.clear();
-        } else if x < 0 {
-            new_x = 0;
-            new_str = string[(len - (len + x)) as usize..].into();
-        }
+        // Offset from the start of the current line, in cells
+        let mut x = 0i64;
+        for span in spans {
+            for grapheme in span.text.graphemes(true) {
+                if y >= window_height {
+                    return;
+                }
 
-        if new_x > window_width {
-            new_str.clear();
-        } else if new_x > window_width - new_str.len() as i64 {
-            new_str = new_str[..(window_width - new_x) as usize].into();
-        }
+                match grapheme {
+                    "\0" => continue,
+                    "\n" | "\r\n" => {
+                        x = 0;
+                        y += 1;
+                    }
+                    "\t" => {
+                        x += TAB_WIDTH - (x % TAB_WIDTH);
+                    }
+                    _ => {
+                        let glyph = Glyph::new(grapheme);
+                        let width = glyph.width() as i64;
+                        let cell_x = start_x + x;
 
-        let len = new_str.len() as i64;
-        if len <= 0 || y < 0 || y >= window_height {
-            return;
-        }
+                        // Wide glyphs are drawn whole or not at all,
+                        // leaving blanks where one is cut by the window edge
+                        if y >= 0 && cell_x >= 0 && cell_x + width <= window_width {
+                            for offset in 0..width {
+                                framebuffer.draw_glyph(
+                                    cell_x + offset,
+                                    y,
+                                    window_width,
+                                    if offset == 0 {
+                                        glyph
+                                    } else {
+                                        Glyph::CONTINUATION
+                                    },
+                                    span.color,
+                                    span.attributes,
+                                    z,
+                                );
+                            }
+                        } else if y >= 0 {
+                            for cell_x in (cell_x..cell_x + width)
+                                .filter(|cell_x| *cell_x >= 0 && *cell_x < window_width)
+                            {
+                                framebuffer.draw_glyph(
+                                    cell_x,
+                                    y,
+                                    window_width,
+                                    Glyph::SPACE,
+                                    span.color,
+                                    span.attributes,
+                                    z,
+                                );
+                            }
+                        }
 
-        let mut x = 0i64;
-        for char in new_str.chars() {
-            if x >= window_width || y >= window_height {
-                break;
-            }
-
-            match char {
-                '\0' => continue,
-                '\n' => {
-                    x = 0;
-                    y += 1;
-                }
-                '\t' => {
-                    x += TAB_WIDTH - (x % TAB_WIDTH);
-                }
-                _ => {
-                    framebuffer.draw(new_x + x, y, window_width, char, z);
-                    x += 1;
+                        x += width;
+                    }
                 }
             }
         }
@@ -102,17 +123,17 @@ where
             window_height = height;
         }
 
-        // Fetch string framebuffer entity
-        let string_framebuffer_entity = db
+        // Fetch cell framebuffer entity
+        let framebuffer_entity = db
             .entity_component_directory
             .get_entity_by_predicate(|entity_id| {
                 db.entity_component_directory
-                    .entity_has_component::<SoftwareFramebuffer<char>>(entity_id)
+                    .entity_has_component::<CellFramebuffer>(entity_id)
             })
-            .unwrap_or_else(|| panic!("No string framebuffer component"));
+            .unwrap_or_else(|| panic!("No cell framebuffer component"));
 
         let cell_count = (window_width * window_height) as usize;
-        db.get_entity_component_mut::<SoftwareFramebuffer<char>>(string_framebuffer_entity)?
+        db.get_entity_component_mut::<CellFramebuffer>(framebuffer_entity)?
             .resize(cell_count);
 
         // Recursively traverse parent-child tree and populate Z-ordered list of controls
@@ -136,7 +157,10 @@ where
                     .entity_has_component::<String>(&entity_id)
                     || db
                         .entity_component_directory
-                        .entity_has_component::<char>(&entity_id))
+                        .entity_has_component::<char>(&entity_id)
+                    || db
+                        .entity_component_directory
+                        .entity_has_component::<RichText>(&entity_id))
             {
                 z_index = match db.get_entity_component::<ZIndex>(entity_id) {
                     Ok(z_index) => **z_index,
@@ -146,9 +170,7 @@ where
                 z_layers.push((entity_id, z_index));
             }
 
-            if let Ok(child_entities) =
-                db.get_entity_component::<ChildEntitiesData>(entity_id)
-            {
+            if let Ok(child_entities) = db.get_entity_component::<ChildEntitiesData>(entity_id) {
                 for child_id in child_entities.iter() {
                     populate_control_entities(db, *child_id, z_layers, z_index)?;
                 }
@@ -161,8 +183,8 @@ where
         control_entities.sort();
 
         // Render Entities
-        db.get_entity_component_mut::<SoftwareFramebuffer<char>>(string_framebuffer_entity)?
-            .clear();
+        db.get_entity_component_mut::<CellFramebuffer>(framebuffer_entity)?
+            .clear_glyphs();
 
         for (entity_id, z) in control_entities {
             // Get Position
@@ -177,28 +199,166 @@ where
                 }
             };
 
-            // Get String
-            let string = if let Ok(string) = db.get_entity_component::<String>(entity_id) {
-                string.clone()
-            } else if let Ok(char) = db.get_entity_component::<char>(entity_id) {
-                char.to_string()
+            // Get text color and style
+            let color = db
+                .get_entity_component::<TextColor>(entity_id)
+                .ok()
+                .map(|text_color| **text_color);
+
+            let attributes = match db.get_entity_component::<TextStyle>(entity_id) {
+                Ok(text_style) => **text_style,
+                Err(_) => CellAttributes::default(),
+            };
+
+            // Get text, with rich text spans layered over the entity's color and style
+            let spans = if let Ok(rich_text) = db.get_entity_component::<RichText>(entity_id) {
+                rich_text
+                    .iter()
+                    .map(|span| TextSpan {
+                        text: span.text.clone(),
+                        color: span.color.or(color),
+                        attributes: span.attributes | attributes,
+                    })
+                    .collect()
             } else {
-                return Err("No valid string component".into());
+                let text = if let Ok(string) = db.get_entity_component::<String>(entity_id) {
+                    string.clone()
+                } else if let Ok(char) = db.get_entity_component::<char>(entity_id) {
+                    char.to_string()
+                } else {
+                    return Err("No valid string component".into());
+                };
+
+                vec![TextSpan {
+                    text,
+                    color,
+                    attributes,
+                }]
+            };
+
+            // Fit within the entity's size if it has a layout, or render as-is
+            let text = RichText(spans);
+            let lines = match (
+                db.get_entity_component::<TextLayout>(entity_id),
+                db.get_entity_component::<Size>(entity_id),
+            ) {
+                (Ok(text_layout), Ok(size)) => text_layout.layout(&text, **size),
+                _ => vec![(Vector2I(0, 0), text)],
             };
 
-            for (i, string) in string.split('\n').enumerate() {
+            for (offset, line) in lines {
                 Self::render_string(
-                    db.get_entity_component_mut::<SoftwareFramebuffer<char>>(
-                        string_framebuffer_entity,
-                    )?,
+                    &mut *db.get_entity_component_mut::<CellFramebuffer>(framebuffer_entity)?,
                     Vector2I(window_width, window_height),
-                    Vector2I(x, y + i as i64),
-                    string,
+                    Vector2I(x, y) + offset,
+                    &line,
                     z,
-                )
+                );
             }
         }
 
         Ok(())
     }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_read::<Window>()
+                .with_read::<Size>()
+                .with_read::<Control>()
+                .with_read::<ZIndex>()
+                .with_read::<ChildEntitiesData>()
+                .with_read::<GlobalPositionData>()
+                .with_read::<Position>()
+                .with_read::<String>()
+                .with_read::<char>()
+                .with_read::<RichText>()
+                .with_read::<TextColor>()
+                .with_read::<TextLayout>()
+                .with_read::<TextStyle>()
+                .with_write::<CellFramebuffer>(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{
+            CellAttributes, CellFramebuffer, ChildEntitiesData, Control, Glyph, Position, Size,
+            TextColor, TextStyle, Window,
+        },
+        entity_component_system::{
+            ComponentHooks, EntityID, HeapComponentStorage, SingleThreadedDirectory,
+            SystemInterface, SystemTrait,
+        },
+        primitive_types::{ColorRGB, Vector2I},
+    };
+
+    use super::StringRenderer;
+
+    #[test]
+    fn text_color_style() {
+        let component_hooks = ComponentHooks::new();
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
+
+        let framebuffer_entity = db.create_entity(None).unwrap();
+        db.insert_entity_component(framebuffer_entity, CellFramebuffer::default())
+            .unwrap();
+
+        let window_entity = db.create_entity(None).unwrap();
+        db.insert_entity_component(window_entity, Window).unwrap();
+        db.insert_entity_component(window_entity, Size(Vector2I(2, 2)))
+            .unwrap();
+        db.insert_entity_component(window_entity, ChildEntitiesData::default())
+            .unwrap();
+
+        let create_text = |db: &mut SystemInterface<_, _>, text: char, y: i64| -> EntityID {
+            let entity_id = db.create_entity(None).unwrap();
+            db.insert_entity_component(entity_id, Control).unwrap();
+            db.insert_entity_component(entity_id, Position(Vector2I(0, y)))
+                .unwrap();
+            db.insert_entity_component(entity_id, text).unwrap();
+            db.get_entity_component_mut::<ChildEntitiesData>(window_entity)
+                .unwrap()
+                .push(entity_id);
+            entity_id
+        };
+
+        let styled_entity = create_text(&mut db, 'a', 0);
+        create_text(&mut db, 'b', 1);
+
+        let red = ColorRGB(1.0, 0.0, 0.0);
+        let bold = CellAttributes {
+            bold: true,
+            ..CellAttributes::default()
+        };
+        db.insert_entity_component(styled_entity, TextColor(red))
+            .unwrap();
+        db.insert_entity_component(styled_entity, TextStyle(bold))
+            .unwrap();
+
+        StringRenderer.run(&mut db).unwrap();
+
+        let cells = db
+            .get_entity_component::<CellFramebuffer>(framebuffer_entity)
+            .unwrap()
+            .get_cells();
+
+        // Text with the components is drawn in their color and style
+        assert_eq!(cells[0].glyph, Glyph::from('a'));
+        assert_eq!(cells[0].foreground, Some(red));
+        assert_eq!(cells[0].attributes, bold);
+
+        // Text without them leaves the color to the backend, and is drawn unstyled
+        assert_eq!(cells[2].glyph, Glyph::from('b'));
+        assert_eq!(cells[2].foreground, None);
+        assert_eq!(cells[2].attributes, CellAttributes::default());
+    }
 }
@@ -1,12 +1,21 @@
 use crate::{
     components::ChildEntitiesData,
+    entity_component_system::Changed,
+    entity_component_system::ComponentAccess,
+    entity_component_system::ComponentHooks,
     entity_component_system::ComponentStorage,
+    entity_component_system::EcsError,
     entity_component_system::EntityComponentDirectory,
     entity_component_system::EntityID,
     entity_component_system::{SystemError, SystemTrait},
 };
 use crate::{components::ParentEntity, entity_component_system::system_interface::SystemInterface};
 
+/// Keeps each parent's ChildEntitiesData in sync with its children's ParentEntity components
+///
+/// ParentEntity hooks handle children being added, reparented through insert_entity_component, or removed.
+/// Hooks don't fire for ParentEntity components modified in place, so each run reparents
+/// any that have changed since the last, which leaves ChildEntitiesData stale until then.
 #[derive(Debug)]
 pub struct ChildEntities;
 
@@ -22,6 +31,62 @@ impl ChildEntities {
     }
 }
 
+fn add_child<CS, CD>(
+    db: &mut SystemInterface<CS, CD>,
+    parent_id: EntityID,
+    entity_id: EntityID,
+) -> Result<(), EcsError>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    if !db.is_alive(&parent_id) || !db.is_alive(&entity_id) {
+        return Ok(());
+    }
+
+    match db.get_entity_component_mut::<ChildEntitiesData>(parent_id) {
+        Ok(mut child_entities) => {
+            if !child_entities.contains(&entity_id) {
+                child_entities.push(entity_id);
+            }
+        }
+        Err(_) => {
+            let mut child_entities = ChildEntitiesData::default();
+            child_entities.push(entity_id);
+            db.insert_entity_component(parent_id, child_entities)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove an entity from the children of every parent except the given one, dropping emptied children components
+fn remove_child<CS, CD>(
+    db: &mut SystemInterface<CS, CD>,
+    entity_id: EntityID,
+    except_parent_id: Option<EntityID>,
+) -> Result<(), EcsError>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    let mut childless_entities = Vec::new();
+    for (parent_id, mut child_entities) in db.query::<(EntityID, &mut ChildEntitiesData)>()? {
+        if Some(parent_id) != except_parent_id && child_entities.contains(&entity_id) {
+            child_entities.retain(|child_id| *child_id != entity_id);
+            if child_entities.is_empty() {
+                childless_entities.push(parent_id);
+            }
+        }
+    }
+
+    for parent_id in childless_entities {
+        db.remove_component_from_entity::<ChildEntitiesData>(parent_id)?;
+    }
+
+    Ok(())
+}
+
 impl<CS, CD> SystemTrait<CS, CD> for ChildEntities
 where
     CS: ComponentStorage,
@@ -32,53 +97,98 @@ where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
     {
-        // Add existing children to their parent entities' children component
-        let entities_with_parents =
-            db.entity_component_directory
-                .get_entities_by_predicate(|entity_id| {
-                    db.entity_component_directory
-                        .entity_has_component::<ParentEntity>(entity_id)
+        let commands = db.commands();
+        for (entity_id, parent_entity) in
+            db.query_filtered::<(EntityID, &ParentEntity), Changed<ParentEntity>>()?
+        {
+            let parent_id = **parent_entity;
+            commands.push(move |db| {
+                remove_child(db, entity_id, Some(parent_id))?;
+                add_child(db, parent_id, entity_id)
+            });
+        }
+
+        Ok(())
+    }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(ComponentAccess::new().with_read::<ParentEntity>())
+    }
+
+    fn register_component_hooks(&self, component_hooks: &mut ComponentHooks<CS, CD>) {
+        component_hooks
+            .on_add::<ParentEntity, _>(|entity_id, parent_entity, commands| {
+                let parent_id = **parent_entity;
+                commands.push(move |db| add_child(db, parent_id, entity_id));
+            })
+            .on_replace::<ParentEntity, _>(|entity_id, parent_entity, commands| {
+                let parent_id = **parent_entity;
+                commands.push(move |db| {
+                    remove_child(db, entity_id, Some(parent_id))?;
+                    add_child(db, parent_id, entity_id)
                 });
+            })
+            .on_remove::<ParentEntity, _>(|entity_id, _, commands| {
+                commands.push(move |db| remove_child(db, entity_id, None));
+            });
+    }
+}
 
-        for entity_id in entities_with_parents {
-            let parent_id: EntityID = **db.get_entity_component::<ParentEntity>(entity_id)?;
+#[cfg(test)]
+mod tests {
+    use crate::{
+        components::{ChildEntitiesData, ParentEntity},
+        entity_component_system::{
+            ComponentHooks, HeapComponentStorage, SingleThreadedDirectory, SystemInterface,
+            SystemTrait,
+        },
+    };
 
-            let child_entities: &mut Vec<EntityID> = match db
-                .get_entity_component_mut::<ChildEntitiesData>(parent_id)
-            {
-                Ok(child_entities) => child_entities,
-                Err(_) => db.insert_entity_component(parent_id, ChildEntitiesData::default())?,
-            };
+    use super::ChildEntities;
 
-            if !child_entities.contains(&entity_id) {
-                child_entities.push(entity_id);
-            }
-        }
+    #[test]
+    fn child_entities() {
+        let mut system = ChildEntities::new();
+        let mut component_hooks = ComponentHooks::new();
+        SystemTrait::<HeapComponentStorage, SingleThreadedDirectory>::register_component_hooks(
+            &system,
+            &mut component_hooks,
+        );
 
-        // Prune destroyed entities from existing children components
-        let entities_with_children =
-            db.entity_component_directory
-                .get_entities_by_predicate(|entity_id| {
-                    db.entity_component_directory
-                        .entity_has_component::<ChildEntitiesData>(entity_id)
-                });
+        let mut component_storage = HeapComponentStorage::new();
+        let mut entity_component_directory = SingleThreadedDirectory::new();
+        let mut db = SystemInterface::new(
+            &mut component_storage,
+            &mut entity_component_directory,
+            &component_hooks,
+        );
 
-        for entity_id in entities_with_children {
-            let valid_entities: Vec<EntityID> = db
-                .get_entity_component::<ChildEntitiesData>(entity_id)?
-                .iter()
-                .filter(|entity_id| db.entity_component_directory.is_valid_entity(entity_id))
-                .copied()
-                .collect();
-
-            if valid_entities.is_empty() {
-                println!("No valid children, removing component");
-                db.remove_component_from_entity::<ChildEntitiesData>(entity_id)?;
-            } else {
-                **db.get_entity_component_mut::<ChildEntitiesData>(entity_id)? = valid_entities;
-            }
-        }
+        let first_parent = db.create_entity(None).unwrap();
+        let second_parent = db.create_entity(None).unwrap();
+        let child = db.create_entity(None).unwrap();
+        db.insert_entity_component(child, ParentEntity(first_parent))
+            .unwrap();
+        db.apply_commands().unwrap();
 
-        Ok(())
+        assert_eq!(
+            **db.get_entity_component::<ChildEntitiesData>(first_parent)
+                .unwrap(),
+            vec![child]
+        );
+
+        // Reparenting in place doesn't fire hooks, so it's picked up when the system next runs
+        db.set_change_ticks(2, 1);
+        **db.get_entity_component_mut::<ParentEntity>(child).unwrap() = second_parent;
+        system.run(&mut db).unwrap();
+        db.apply_commands().unwrap();
+
+        assert!(db
+            .get_entity_component::<ChildEntitiesData>(first_parent)
+            .is_err());
+        assert_eq!(
+            **db.get_entity_component::<ChildEntitiesData>(second_parent)
+                .unwrap(),
+            vec![child]
+        );
     }
 }
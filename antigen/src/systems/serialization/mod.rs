@@ -0,0 +1,3 @@
+mod scene_reloader;
+
+pub use scene_reloader::*;
@@ -0,0 +1,92 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::entity_component_system::{
+    system_interface::SystemInterface, ComponentStorage, EcsError, EntityComponentDirectory,
+    SceneDescription, SceneInstance, SerializationRegistry, SystemError, SystemTrait,
+};
+
+/// Spawns the entities of a scene file, then keeps them in sync with it as it changes on disk
+///
+/// The file's modification time is polled each run, and the live world is updated via SceneInstance
+/// so that unchanged entities keep their IDs.
+/// Errors in the initial load are returned, while later parse errors leave the world untouched
+/// until the file is next modified, so a half-finished edit doesn't bring down the application.
+pub struct SceneReloader<CS, CD>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+{
+    path: PathBuf,
+    serialization_registry: SerializationRegistry<CS, CD>,
+    scene_instance: SceneInstance,
+    modified: Option<SystemTime>,
+}
+
+impl<CS, CD> SceneReloader<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    pub fn new<P>(path: P, serialization_registry: SerializationRegistry<CS, CD>) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        SceneReloader {
+            path: path.as_ref().into(),
+            serialization_registry,
+            scene_instance: SceneInstance::new(),
+            modified: None,
+        }
+    }
+
+    /// Update the world from the scene file if it has been modified since the last reload
+    pub fn reload(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), EcsError> {
+        let initial_load = self.modified.is_none();
+
+        let modified = match std::fs::metadata(&self.path).and_then(|metadata| metadata.modified())
+        {
+            Ok(modified) => modified,
+            Err(err) if initial_load => {
+                return Err(EcsError::Serialization(format!(
+                    "Failed to read {}: {}",
+                    self.path.display(),
+                    err
+                )))
+            }
+            // Editors may briefly remove the file while saving
+            Err(_) => return Ok(()),
+        };
+
+        if self.modified == Some(modified) {
+            return Ok(());
+        }
+        self.modified = Some(modified);
+
+        let scene_description =
+            match SceneDescription::from_file(&self.serialization_registry, &self.path) {
+                Ok(scene_description) => scene_description,
+                Err(err) if initial_load => return Err(err),
+                Err(_) => return Ok(()),
+            };
+
+        self.scene_instance.update(&scene_description, db)
+    }
+}
+
+impl<CS, CD> SystemTrait<CS, CD> for SceneReloader<CS, CD>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        self.reload(db)?;
+        Ok(())
+    }
+}
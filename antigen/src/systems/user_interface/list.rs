@@ -3,14 +3,14 @@ use std::collections::HashMap;
 use crate::{
     components::{
         Control, DebugExclude, EventQueue, GlobalPositionData, ListData, LocalMousePositionData,
-        ParentEntity, Position, Size,
+        ParentEntity, Position, RichText, Size, TextColor,
     },
     core::events::AntigenInputEvent,
     entity_component_system::{
-        system_interface::SystemInterface, ComponentStorage, EntityComponentDirectory, EntityID,
-        SystemError, SystemTrait,
+        system_interface::SystemInterface, Assemblage, AssembledEntity, ComponentStorage, EcsError,
+        EntityComponentDirectory, EntityID, SystemError, SystemTrait, With,
     },
-    primitive_types::{ColorRGB, ColorRGBF, Vector2I},
+    primitive_types::{ColorRGB, Vector2I},
 };
 
 #[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
@@ -19,13 +19,40 @@ pub enum ListEvent {
     Pressed(Option<usize>),
 }
 
+const LIST_HOVER_ENTITY: &str = "List Hover Entity";
+const LIST_FOCUS_ENTITY: &str = "List Focus Entity";
+
+/// Prefab for the hover and focus rectangles assembled onto each list control
+fn list_rects_assemblage<CS, CD>() -> Result<Assemblage<CS, CD>, EcsError>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    let rect_assemblage = Assemblage::build("List Rect", "List item highlight")
+        .add_component(Control)?
+        .add_component(Position::default())?
+        .add_component(Size::default())?
+        .add_component(GlobalPositionData::default())?
+        .finish();
+
+    let hover_assemblage = Assemblage::build("List Hover Rect", "Hovered list item highlight")
+        .extend(&rect_assemblage)
+        .add_component(ColorRGB(0.5f32, 0.5f32, 0.5f32))?
+        .finish();
+
+    Ok(Assemblage::build(
+        "List Rects",
+        "Hover and focus highlights for a list control",
+    )
+    .add_child(LIST_HOVER_ENTITY, hover_assemblage)
+    .add_child(LIST_FOCUS_ENTITY, rect_assemblage)
+    .finish())
+}
+
 #[derive(Debug)]
 pub struct List {
-    // Maps list control entities -> rectangle entities
-    list_focus_entities: HashMap<EntityID, EntityID>,
-
-    // Maps list control entities -> rectangle entities
-    list_hover_entities: HashMap<EntityID, EntityID>,
+    // Maps list control entities -> hover and focus rectangle entities
+    list_rect_entities: HashMap<EntityID, AssembledEntity>,
 
     // Maps list control entities -> string entities
     list_string_entities: HashMap<EntityID, Vec<EntityID>>,
@@ -34,8 +61,7 @@ pub struct List {
 impl List {
     pub fn new() -> Self {
         List {
-            list_focus_entities: HashMap::new(),
-            list_hover_entities: HashMap::new(),
+            list_rect_entities: HashMap::new(),
             list_string_entities: HashMap::new(),
         }
     }
@@ -57,81 +83,66 @@ where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
     {
-        let list_control_entities =
-            db.entity_component_directory
-                .get_entities_by_predicate(|entity_id| {
-                    db.entity_component_directory
-                        .entity_has_component::<ListData>(entity_id)
-                        && db
-                            .entity_component_directory
-                            .entity_has_component::<Position>(entity_id)
-                        && db
-                            .entity_component_directory
-                            .entity_has_component::<Size>(entity_id)
-                        && db
-                            .entity_component_directory
-                            .entity_has_component::<ParentEntity>(entity_id)
-                });
+        // Forget cached entities that have since been destroyed, so they get recreated
+        let mut stale_rect_entities = Vec::new();
+        self.list_rect_entities
+            .retain(|list_control_entity, list_rect_entities| {
+                let alive = db.is_alive(list_control_entity)
+                    && list_rect_entities
+                        .children
+                        .values()
+                        .all(|rect_entity| db.is_alive(rect_entity));
+                if !alive {
+                    stale_rect_entities.extend(list_rect_entities.children.values().copied());
+                }
+                alive
+            });
+        for rect_entity in stale_rect_entities {
+            if db.is_alive(&rect_entity) {
+                db.destroy_entity(rect_entity)?;
+            }
+        }
+        self.list_string_entities
+            .retain(|list_control_entity, _| db.is_alive(list_control_entity));
+        for string_entities in self.list_string_entities.values_mut() {
+            string_entities.retain(|string_entity| db.is_alive(string_entity));
+        }
+
+        let list_control_entities = db.query_filtered::<EntityID, (
+            With<ListData>,
+            With<Position>,
+            With<Size>,
+            With<ParentEntity>,
+        )>()?;
+
+        // String entities are created and destroyed at the end of the system,
+        // and skipped below until they're alive
+        let commands = db.commands();
 
         for list_control_entity in list_control_entities {
-            self.list_hover_entities
-                .entry(list_control_entity)
-                .or_insert_with(|| {
-                    let list_hover_entity = db.create_entity(Some("List Hover Entity")).unwrap();
-                    db.insert_entity_component(list_hover_entity, Control)
-                        .unwrap();
-                    db.insert_entity_component(list_hover_entity, Position::default())
-                        .unwrap();
-                    db.insert_entity_component(list_hover_entity, Size::default())
-                        .unwrap();
-                    db.insert_entity_component(list_hover_entity, GlobalPositionData::default())
-                        .unwrap();
-                    db.insert_entity_component(list_hover_entity, ColorRGB(0.5f32, 0.5f32, 0.5f32))
-                        .unwrap();
-                    db.insert_entity_component(
-                        list_hover_entity,
-                        ParentEntity(list_control_entity),
-                    )
-                    .unwrap();
-                    list_hover_entity
-                });
-
-            let list_hover_entity = self
-                .list_hover_entities
-                .get(&list_control_entity)
-                .ok_or("Error getting list hover entity")?;
-
-            self.list_focus_entities
-                .entry(list_control_entity)
-                .or_insert_with(|| {
-                    let list_focus_entity = db.create_entity(Some("List Focus Entity")).unwrap();
-                    db.insert_entity_component(list_focus_entity, Control)
-                        .unwrap();
-                    db.insert_entity_component(list_focus_entity, Position::default())
-                        .unwrap();
-                    db.insert_entity_component(list_focus_entity, Size::default())
-                        .unwrap();
-                    db.insert_entity_component(list_focus_entity, GlobalPositionData::default())
-                        .unwrap();
-                    db.insert_entity_component(
-                        list_focus_entity,
-                        ParentEntity(list_control_entity),
-                    )
-                    .unwrap();
-                    list_focus_entity
-                });
-
-            let list_focus_entity = self
-                .list_focus_entities
-                .get(&list_control_entity)
-                .ok_or("Error getting list focus entity")?;
+            let list_rect_entities = match self.list_rect_entities.get(&list_control_entity) {
+                Some(list_rect_entities) => list_rect_entities,
+                None => {
+                    let list_rect_entities = list_rects_assemblage()?.assemble_entity_tree(
+                        db,
+                        list_control_entity,
+                        (),
+                    )?;
+                    self.list_rect_entities
+                        .entry(list_control_entity)
+                        .or_insert(list_rect_entities)
+                }
+            };
+            let list_hover_entity = list_rect_entities.children[LIST_HOVER_ENTITY];
+            let list_focus_entity = list_rect_entities.children[LIST_FOCUS_ENTITY];
 
             // Fetch entity references
-            let (string_list_entity, scroll_offset) =
+            let (string_list_entity, scroll_offset, rich_text) =
                 match db.get_entity_component::<ListData>(list_control_entity) {
                     Ok(pancurses_list_control_component) => (
                         pancurses_list_control_component.get_string_list_entity(),
                         pancurses_list_control_component.get_scroll_offset(),
+                        pancurses_list_control_component.get_rich_text(),
                     ),
                     Err(err) => return Err(err.into()),
                 };
@@ -146,23 +157,26 @@ where
                         Err(err) => return Err(err.into()),
                     };
 
-                // Fetch strings
-                let string_list: Vec<Vec<String>> = db
+                // Fetch strings, parsing them as markup if the list uses rich text
+                let string_list: Vec<Vec<RichText>> = db
                     .get_entity_component::<Vec<String>>(string_list_entity)?
                     .iter()
                     .skip(scroll_offset)
                     .take(height as usize)
                     .map(|string| {
-                        let substrings: Vec<String> = string
-                            .split('\n')
-                            .map(|string| {
-                                string
-                                    .chars()
-                                    .take(std::cmp::min(width as usize, string.len() as usize))
-                                    .collect::<String>()
+                        let text = if rich_text {
+                            RichText::parse(string)
+                        } else {
+                            RichText::from(string.as_str())
+                        };
+
+                        text.lines()
+                            .into_iter()
+                            .map(|mut line| {
+                                line.truncate(width as usize);
+                                line
                             })
-                            .collect();
-                        substrings
+                            .collect()
                     })
                     .collect();
 
@@ -189,14 +203,15 @@ where
                 let string_count: usize = string_list.iter().map(|strings| strings.len()).sum();
                 let string_count = std::cmp::min(string_count, height as usize);
                 while string_entities.len() < string_count {
-                    let string_entity = db.create_entity(Some("List String Entity"))?;
-                    db.insert_entity_component(string_entity, Control)?;
-                    db.insert_entity_component(string_entity, Position::default())?;
-                    db.insert_entity_component(string_entity, GlobalPositionData::default())?;
-                    db.insert_entity_component(string_entity, ParentEntity(list_control_entity))?;
-                    db.insert_entity_component(string_entity, String::default())?;
-                    db.insert_entity_component(string_entity, ColorRGBF::default())?;
-                    db.insert_entity_component(string_entity, DebugExclude)?;
+                    let string_entity = commands.create_entity(Some("List String Entity"))?;
+                    commands.insert_entity_component(string_entity, Control);
+                    commands.insert_entity_component(string_entity, Position::default());
+                    commands.insert_entity_component(string_entity, GlobalPositionData::default());
+                    commands
+                        .insert_entity_component(string_entity, ParentEntity(list_control_entity));
+                    commands.insert_entity_component(string_entity, RichText::default());
+                    commands.insert_entity_component(string_entity, TextColor::default());
+                    commands.insert_entity_component(string_entity, DebugExclude);
 
                     string_entities.push(string_entity);
                 }
@@ -204,7 +219,7 @@ where
                 // Destroy item entities for lines that no longer exist
                 while string_entities.len() > string_count {
                     if let Some(string_entity) = string_entities.pop() {
-                        db.destroy_entity(string_entity)?;
+                        commands.destroy_entity(string_entity);
                     }
                 }
 
@@ -245,7 +260,7 @@ where
                 };
 
                 // Clear local event queue
-                if let Ok(list_event_queue) =
+                if let Ok(mut list_event_queue) =
                     db.get_entity_component_mut::<EventQueue<ListEvent>>(list_control_entity)
                 {
                     list_event_queue.clear();
@@ -253,14 +268,10 @@ where
 
                 // If the mouse was clicked inside this control, update the selected index
                 if contains_mouse {
-                    let event_queue_entity =
-                        db.entity_component_directory
-                            .get_entity_by_predicate(|entity_id| {
-                                db.entity_component_directory
-                                    .entity_has_component::<EventQueue<AntigenInputEvent>>(
-                                        entity_id,
-                                    )
-                            });
+                    let event_queue_entity = db
+                        .query_filtered::<EntityID, With<EventQueue<AntigenInputEvent>>>()?
+                        .into_iter()
+                        .next();
 
                     if let Some(event_queue_entity) = event_queue_entity {
                         let event_queue: &Vec<AntigenInputEvent> = db
@@ -278,7 +289,7 @@ where
                                     };
 
                                     // Push press event into queue
-                                    if let Ok(list_event_queue) = db
+                                    if let Ok(mut list_event_queue) = db
                                         .get_entity_component_mut::<EventQueue<ListEvent>>(
                                             list_control_entity,
                                         )
@@ -286,14 +297,14 @@ where
                                         list_event_queue.push(ListEvent::Pressed(index));
                                     }
 
-                                    if let Ok(list) =
+                                    if let Ok(mut list) =
                                         db.get_entity_component_mut::<ListData>(list_control_entity)
                                     {
                                         list.set_selected_index(index);
                                     }
                                 }
                                 AntigenInputEvent::MouseScroll { delta } => {
-                                    if let Ok(list) =
+                                    if let Ok(mut list) =
                                         db.get_entity_component_mut::<ListData>(list_control_entity)
                                     {
                                         list.add_scroll_offset(delta as i64);
@@ -310,7 +321,7 @@ where
                     .get_entity_component_mut::<ListData>(list_control_entity)?
                     .get_selected_index();
 
-                **db.get_entity_component_mut::<Position>(*list_hover_entity)? = Vector2I(
+                **db.get_entity_component_mut::<Position>(list_hover_entity)? = Vector2I(
                     0,
                     if let Some(hovered_item) = hovered_item {
                         hovered_item as i64
@@ -319,17 +330,17 @@ where
                     },
                 );
 
-                **db.get_entity_component_mut::<Size>(*list_hover_entity)? =
+                **db.get_entity_component_mut::<Size>(list_hover_entity)? =
                     if let Some(hovered_item) = hovered_item {
                         Vector2I(width, string_list[hovered_item as usize].len() as i64)
                     } else {
                         Vector2I(0, 0)
                     };
 
-                **db.get_entity_component_mut::<Position>(*list_focus_entity)? =
+                **db.get_entity_component_mut::<Position>(list_focus_entity)? =
                     Vector2I(0, selected_item.unwrap_or(0) as i64);
 
-                **db.get_entity_component_mut::<Size>(*list_focus_entity)? =
+                **db.get_entity_component_mut::<Size>(list_focus_entity)? =
                     if let Some(focused_item) = selected_item {
                         if focused_item < string_list.len() {
                             Vector2I(width, string_list[focused_item as usize].len() as i64)
@@ -353,20 +364,24 @@ where
 
                         let string_entity = string_entities[y as usize];
 
-                        // Update each string entity's position
-                        **db.get_entity_component_mut::<Position>(string_entity)? = Vector2I(0, y);
+                        if db.is_alive(&string_entity) {
+                            // Update each string entity's position
+                            **db.get_entity_component_mut::<Position>(string_entity)? =
+                                Vector2I(0, y);
 
-                        // Update each string entity's text
-                        *db.get_entity_component_mut::<String>(string_entity)? = string.clone();
+                            // Update each string entity's text
+                            *db.get_entity_component_mut::<RichText>(string_entity)? =
+                                string.clone();
 
-                        // Update color pair based on focused item
-                        let data = if Some(string_index) == selected_item {
-                            ColorRGB(0.0, 0.0, 0.0)
-                        } else {
-                            ColorRGB(1.0, 1.0, 1.0)
-                        };
+                            // Update text color based on focused item
+                            let data = if Some(string_index) == selected_item {
+                                ColorRGB(0.0, 0.0, 0.0)
+                            } else {
+                                ColorRGB(1.0, 1.0, 1.0)
+                            };
 
-                        *db.get_entity_component_mut::<ColorRGBF>(string_entity)? = data;
+                            **db.get_entity_component_mut::<TextColor>(string_entity)? = data;
+                        }
 
                         y += 1;
                     }
@@ -0,0 +1,21 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::AnsiTerminal;
+
+/// Handle to the terminal a window entity is displayed in, opened by the AnsiWindow system
+#[derive(Debug, Default)]
+pub struct AnsiTerminalData(pub Option<AnsiTerminal>);
+
+impl Deref for AnsiTerminalData {
+    type Target = Option<AnsiTerminal>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for AnsiTerminalData {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
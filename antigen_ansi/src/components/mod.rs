@@ -0,0 +1,3 @@
+mod ansi_terminal;
+
+pub use ansi_terminal::*;
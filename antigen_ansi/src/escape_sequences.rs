@@ -0,0 +1,165 @@
+use std::fmt::Write;
+
+use antigen::{
+    components::{CellAttributes, Glyph},
+    primitive_types::{ColorRGB, ColorRGB8},
+};
+
+/// Alternate screen, hidden cursor, and any-motion mouse reporting in SGR format
+pub const ENTER_TERMINAL_MODE: &str = "\x1b[?1049h\x1b[?25l\x1b[?1003h\x1b[?1006h";
+
+/// Reverses ENTER_TERMINAL_MODE and resets text attributes
+pub const LEAVE_TERMINAL_MODE: &str = "\x1b[?1006l\x1b[?1003l\x1b[0m\x1b[?25h\x1b[?1049l";
+
+/// A glyph cell with truecolor foreground and background
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AnsiCell {
+    pub glyph: Glyph,
+    pub foreground: ColorRGB8,
+    pub background: ColorRGB8,
+    pub attributes: CellAttributes,
+}
+
+/// Select graphic rendition parameters for the given attributes
+fn attribute_params(attributes: CellAttributes) -> Vec<&'static str> {
+    let mut params = Vec::new();
+
+    if attributes.bold {
+        params.push("1");
+    }
+
+    if attributes.dim {
+        params.push("2");
+    }
+
+    if attributes.underline {
+        params.push("4");
+    }
+
+    if attributes.reverse {
+        params.push("7");
+    }
+
+    params
+}
+
+/// Encode a full frame of cells in row-major order as escape sequences
+///
+/// Each row starts with an absolute cursor move, and colors and attributes are only emitted when they change.
+pub fn encode_frame(width: usize, cells: &[AnsiCell]) -> String {
+    let mut frame = String::new();
+    let mut foreground = None;
+    let mut background = None;
+    let mut attributes = CellAttributes::default();
+
+    for (y, row) in cells.chunks(width.max(1)).enumerate() {
+        write!(frame, "\x1b[{};1H", y + 1).unwrap();
+
+        for cell in row {
+            // The terminal advances past both halves of a wide glyph when writing it
+            if cell.glyph.is_continuation() {
+                continue;
+            }
+
+            if attributes != cell.attributes {
+                // Attributes can't be switched off individually, so reset them all
+                // along with the colors, and reapply those still in use
+                frame += "\x1b[0m";
+                foreground = None;
+                background = None;
+
+                for param in attribute_params(cell.attributes) {
+                    write!(frame, "\x1b[{}m", param).unwrap();
+                }
+                attributes = cell.attributes;
+            }
+
+            if foreground != Some(cell.foreground) {
+                let ColorRGB(r, g, b) = cell.foreground;
+                write!(frame, "\x1b[38;2;{};{};{}m", r, g, b).unwrap();
+                foreground = Some(cell.foreground);
+            }
+
+            if background != Some(cell.background) {
+                let ColorRGB(r, g, b) = cell.background;
+                write!(frame, "\x1b[48;2;{};{};{}m", r, g, b).unwrap();
+                background = Some(cell.background);
+            }
+
+            // Control characters would move the cursor and misalign the rest of the row
+            if cell.glyph.as_str().chars().any(char::is_control) {
+                frame.push(' ');
+            } else {
+                frame += cell.glyph.as_str();
+            }
+        }
+    }
+
+    frame += "\x1b[0m";
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use antigen::{
+        components::{CellAttributes, Glyph},
+        primitive_types::ColorRGB,
+    };
+
+    use super::{encode_frame, AnsiCell};
+
+    #[test]
+    fn encode_frame_colors() {
+        let white = ColorRGB(255, 255, 255);
+        let black = ColorRGB(0, 0, 0);
+        let red = ColorRGB(255, 0, 0);
+
+        let cell = |char: char, background| AnsiCell {
+            glyph: char.into(),
+            foreground: white,
+            background,
+            attributes: CellAttributes::default(),
+        };
+
+        assert_eq!(
+            encode_frame(
+                2,
+                &[
+                    cell('a', black),
+                    cell('b', black),
+                    cell('c', red),
+                    cell('\t', red)
+                ]
+            ),
+            "\x1b[1;1H\x1b[38;2;255;255;255m\x1b[48;2;0;0;0mab\
+             \x1b[2;1H\x1b[48;2;255;0;0mc \x1b[0m"
+        );
+
+        let underline = AnsiCell {
+            attributes: CellAttributes {
+                underline: true,
+                ..CellAttributes::default()
+            },
+            ..cell('d', black)
+        };
+        assert_eq!(
+            encode_frame(2, &[underline, cell('e', black)]),
+            "\x1b[1;1H\x1b[0m\x1b[4m\x1b[38;2;255;255;255m\x1b[48;2;0;0;0md\
+             \x1b[0m\x1b[38;2;255;255;255m\x1b[48;2;0;0;0me\x1b[0m"
+        );
+
+        // Continuation cells are covered by the wide glyph before them
+        let wide = AnsiCell {
+            glyph: Glyph::new("日"),
+            ..cell(' ', black)
+        };
+        let continuation = AnsiCell {
+            glyph: Glyph::CONTINUATION,
+            ..cell(' ', black)
+        };
+        assert_eq!(
+            encode_frame(3, &[wide, continuation, cell('f', black)]),
+            "\x1b[1;1H\x1b[38;2;255;255;255m\x1b[48;2;0;0;0m日f\x1b[0m"
+        );
+    }
+}
@@ -0,0 +1,312 @@
+use antigen::{
+    core::{
+        events::AntigenInputEvent,
+        keyboard::{IntoKey, Key},
+    },
+    primitive_types::Vector2I,
+};
+
+const ESC: u8 = 0x1b;
+
+// SGR mouse report button flags
+const MOUSE_BUTTON_BITS: usize = 0b11;
+const MOUSE_MOTION: usize = 32;
+const MOUSE_WHEEL: usize = 64;
+
+/// Converts raw terminal input bytes into antigen input events
+///
+/// Escape sequences split across reads are buffered until the rest arrives.
+/// A lone escape byte at the end of a read is taken to be the escape key,
+/// as terminals send each sequence in a single write.
+///
+/// Input events carry no modifier state, so Alt is dropped from Alt-modified keys.
+#[derive(Debug, Default)]
+pub struct AnsiInputParser {
+    buffer: Vec<u8>,
+    mouse_position: Option<Vector2I>,
+}
+
+/// Result of parsing a single sequence from the start of a buffer
+enum Parsed {
+    Events(Vec<AntigenInputEvent>, usize),
+    Incomplete,
+}
+
+impl AnsiInputParser {
+    pub fn new() -> Self {
+        AnsiInputParser::default()
+    }
+
+    pub fn parse(&mut self, bytes: &[u8]) -> Vec<AntigenInputEvent> {
+        self.buffer.extend_from_slice(bytes);
+
+        let mut events = Vec::new();
+        let mut offset = 0;
+        while offset < self.buffer.len() {
+            match self.parse_sequence(offset) {
+                Parsed::Events(sequence_events, length) => {
+                    events.extend(sequence_events);
+                    offset += length;
+                }
+                Parsed::Incomplete => break,
+            }
+        }
+
+        self.buffer.drain(..offset);
+        events
+    }
+
+    fn parse_sequence(&mut self, offset: usize) -> Parsed {
+        let bytes = &self.buffer[offset..];
+
+        if bytes[0] != ESC {
+            return Self::parse_char(bytes);
+        }
+
+        match bytes.get(1) {
+            None => Parsed::Events(key_events(Key::Escape), 1),
+            Some(b'[') => {
+                // Control sequence: parameter and intermediate bytes, then a final byte in 0x40..=0x7e
+                let final_index = match bytes[2..].iter().position(|b| (0x40..=0x7e).contains(b)) {
+                    Some(index) => index + 2,
+                    None => return Parsed::Incomplete,
+                };
+
+                let params = &bytes[2..final_index];
+                let final_byte = bytes[final_index];
+                let events = if params.first() == Some(&b'<') {
+                    let params = parse_params(&params[1..]);
+                    self.mouse_events(&params, final_byte)
+                } else {
+                    key_events(csi_key(params, final_byte))
+                };
+
+                Parsed::Events(events, final_index + 1)
+            }
+            Some(b'O') => match bytes.get(2) {
+                Some(final_byte) => Parsed::Events(key_events(ss3_key(*final_byte)), 3),
+                None => Parsed::Incomplete,
+            },
+            // Alt-modified keys arrive as escape followed by the key, and are reported as the bare key
+            Some(_) => match Self::parse_char(&bytes[1..]) {
+                Parsed::Events(events, length) => Parsed::Events(events, length + 1),
+                Parsed::Incomplete => Parsed::Incomplete,
+            },
+        }
+    }
+
+    fn parse_char(bytes: &[u8]) -> Parsed {
+        let length = match bytes[0] {
+            0x00..=0x7f => 1,
+            0xc0..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf7 => 4,
+            _ => return Parsed::Events(vec![], 1),
+        };
+
+        if bytes.len() < length {
+            return Parsed::Incomplete;
+        }
+
+        let events = match std::str::from_utf8(&bytes[..length]) {
+            Ok(string) => string
+                .chars()
+                .flat_map(|c| key_events(c.into_key()))
+                .collect(),
+            Err(_) => vec![],
+        };
+
+        Parsed::Events(events, length)
+    }
+
+    /// Decode an SGR mouse report of the form `button;x;y` terminated by M (press) or m (release)
+    fn mouse_events(&mut self, params: &[usize], final_byte: u8) -> Vec<AntigenInputEvent> {
+        let (button, x, y) = match *params {
+            [button, x, y] if x > 0 && y > 0 => (button, x as i64 - 1, y as i64 - 1),
+            _ => return vec![],
+        };
+
+        let mut events = Vec::new();
+
+        let position = Vector2I(x, y);
+        let prev_position = self.mouse_position.unwrap_or(position);
+        if self.mouse_position != Some(position) {
+            events.push(AntigenInputEvent::MouseMove {
+                position,
+                delta: position - prev_position,
+            });
+            self.mouse_position = Some(position);
+        }
+
+        if button & MOUSE_WHEEL > 0 {
+            let delta = if button & MOUSE_BUTTON_BITS == 0 {
+                -1
+            } else {
+                1
+            };
+            events.push(AntigenInputEvent::MouseScroll { delta });
+            return events;
+        }
+
+        if button & MOUSE_MOTION > 0 {
+            return events;
+        }
+
+        // Match the curses backend's masks: left 1, right 2, middle 4
+        let button_mask = match button & MOUSE_BUTTON_BITS {
+            0 => 1,
+            1 => 4,
+            2 => 2,
+            _ => return events,
+        };
+
+        events.push(match final_byte {
+            b'M' => AntigenInputEvent::MousePress { button_mask },
+            _ => AntigenInputEvent::MouseRelease { button_mask },
+        });
+
+        events
+    }
+}
+
+/// Parse semicolon-separated numeric parameters, skipping any that are malformed
+fn parse_params(params: &[u8]) -> Vec<usize> {
+    std::str::from_utf8(params)
+        .unwrap_or_default()
+        .split(';')
+        .filter_map(|param| param.parse().ok())
+        .collect()
+}
+
+fn key_events(key_code: Key) -> Vec<AntigenInputEvent> {
+    if key_code == Key::Unknown {
+        return vec![];
+    }
+
+    vec![
+        AntigenInputEvent::KeyPress { key_code },
+        AntigenInputEvent::KeyRelease { key_code },
+    ]
+}
+
+fn csi_key(params: &[u8], final_byte: u8) -> Key {
+    match final_byte {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'Z' => Key::BackTab,
+        b'~' => {
+            // Modifiers follow the key number after a semicolon, ex. 5;2~ for Shift+PageUp
+            match parse_params(params).first() {
+                Some(1) | Some(7) => Key::Home,
+                Some(2) => Key::Insert,
+                Some(3) => Key::Delete,
+                Some(4) | Some(8) => Key::End,
+                Some(5) => Key::PageUp,
+                Some(6) => Key::PageDown,
+                Some(11) => Key::F1,
+                Some(12) => Key::F2,
+                Some(13) => Key::F3,
+                Some(14) => Key::F4,
+                Some(15) => Key::F5,
+                Some(17) => Key::F6,
+                Some(18) => Key::F7,
+                Some(19) => Key::F8,
+                Some(20) => Key::F9,
+                Some(21) => Key::F10,
+                Some(23) => Key::F11,
+                Some(24) => Key::F12,
+                _ => Key::Unknown,
+            }
+        }
+        _ => Key::Unknown,
+    }
+}
+
+fn ss3_key(final_byte: u8) -> Key {
+    match final_byte {
+        b'A' => Key::Up,
+        b'B' => Key::Down,
+        b'C' => Key::Right,
+        b'D' => Key::Left,
+        b'H' => Key::Home,
+        b'F' => Key::End,
+        b'P' => Key::F1,
+        b'Q' => Key::F2,
+        b'R' => Key::F3,
+        b'S' => Key::F4,
+        _ => Key::Unknown,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use antigen::{
+        core::{events::AntigenInputEvent, keyboard::Key},
+        primitive_types::Vector2I,
+    };
+
+    use super::AnsiInputParser;
+
+    fn key_press(key_code: Key) -> Vec<AntigenInputEvent> {
+        vec![
+            AntigenInputEvent::KeyPress { key_code },
+            AntigenInputEvent::KeyRelease { key_code },
+        ]
+    }
+
+    #[test]
+    fn ansi_input_parser() {
+        let mut parser = AnsiInputParser::new();
+
+        assert_eq!(
+            parser.parse(b"q\r"),
+            [key_press(Key::Q), key_press(Key::Enter)].concat()
+        );
+        assert_eq!(parser.parse(b"\x1b"), key_press(Key::Escape));
+        assert_eq!(
+            parser.parse(b"\x1b[A\x1bOP\x1b[5;2~"),
+            [
+                key_press(Key::Up),
+                key_press(Key::F1),
+                key_press(Key::PageUp)
+            ]
+            .concat()
+        );
+
+        // Sequences split across reads are completed by the next one
+        assert_eq!(parser.parse(b"\x1b[<0;5"), vec![]);
+        assert_eq!(
+            parser.parse(b";3M"),
+            vec![
+                AntigenInputEvent::MouseMove {
+                    position: Vector2I(4, 2),
+                    delta: Vector2I(0, 0),
+                },
+                AntigenInputEvent::MousePress { button_mask: 1 },
+            ]
+        );
+
+        assert_eq!(
+            parser.parse(b"\x1b[<34;6;3M\x1b[<2;6;3m\x1b[<65;6;3M"),
+            vec![
+                AntigenInputEvent::MouseMove {
+                    position: Vector2I(5, 2),
+                    delta: Vector2I(1, 0),
+                },
+                AntigenInputEvent::MouseRelease { button_mask: 2 },
+                AntigenInputEvent::MouseScroll { delta: 1 },
+            ]
+        );
+
+        // Alt+key is reported as the key alone rather than escape followed by the key
+        assert_eq!(parser.parse(b"\x1bq"), key_press(Key::Q));
+
+        // Multi-byte characters may also be split
+        assert_eq!(parser.parse(&"é".as_bytes()[..1]), vec![]);
+        assert_eq!(parser.parse(&"é".as_bytes()[1..]), vec![]);
+    }
+}
@@ -0,0 +1,15 @@
+// The terminal is driven through termios, so only the platform-independent parsing and encoding is available elsewhere
+#[cfg(unix)]
+pub mod components;
+#[cfg(unix)]
+pub mod systems;
+
+mod escape_sequences;
+mod input_parser;
+#[cfg(unix)]
+mod terminal;
+
+pub use escape_sequences::*;
+pub use input_parser::*;
+#[cfg(unix)]
+pub use terminal::*;
@@ -0,0 +1,72 @@
+use antigen::{
+    components::{EventQueue, Window},
+    core::events::AntigenInputEvent,
+    entity_component_system::{
+        system_interface::SystemInterface, ComponentStorage, EntityComponentDirectory, SystemError,
+        SystemTrait,
+    },
+};
+
+use crate::{components::AnsiTerminalData, AnsiInputParser};
+
+/// Reads raw input from the window's terminal and pushes the decoded events into the input event queue
+#[derive(Debug, Default)]
+pub struct AnsiInput {
+    parser: AnsiInputParser,
+}
+
+impl AnsiInput {
+    pub fn new() -> Self {
+        AnsiInput::default()
+    }
+}
+
+impl<CS, CD> SystemTrait<CS, CD> for AnsiInput
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        let window_entity = db
+            .entity_component_directory
+            .get_entity_by_predicate(|entity_id| {
+                db.entity_component_directory
+                    .entity_has_component::<Window>(entity_id)
+                    && db
+                        .entity_component_directory
+                        .entity_has_component::<AnsiTerminalData>(entity_id)
+            });
+
+        let window_entity = match window_entity {
+            Some(window_entity) => window_entity,
+            None => return Ok(()),
+        };
+
+        let bytes = match &**db.get_entity_component::<AnsiTerminalData>(window_entity)? {
+            Some(terminal) => terminal
+                .read()
+                .map_err(|err| format!("Failed to read ANSI terminal input: {}", err))?,
+            None => return Ok(()),
+        };
+
+        let events = self.parser.parse(&bytes);
+
+        let event_queue_entity =
+            db.entity_component_directory
+                .get_entity_by_predicate(|entity_id| {
+                    db.entity_component_directory
+                        .entity_has_component::<EventQueue<AntigenInputEvent>>(entity_id)
+                });
+
+        if let Some(event_queue_entity) = event_queue_entity {
+            db.get_entity_component_mut::<EventQueue<AntigenInputEvent>>(event_queue_entity)?
+                .extend(events);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use antigen::{
+    components::{CellFramebuffer, Size, TextColorMode, Window},
+    entity_component_system::{
+        system_interface::SystemInterface, ComponentStorage, EntityComponentDirectory, SystemError,
+        SystemTrait,
+    },
+    primitive_types::{ColorRGB8, Vector2I},
+};
+
+use crate::{components::AnsiTerminalData, encode_frame, AnsiCell};
+
+/// Writes the cell framebuffer to the window's terminal in 24-bit color
+///
+/// Colors are sent as-is, so unlike the curses backend no palette is involved.
+#[derive(Debug)]
+pub struct AnsiRenderer {
+    text_color_mode: TextColorMode,
+}
+
+impl AnsiRenderer {
+    pub fn new(text_color_mode: TextColorMode) -> Self {
+        AnsiRenderer { text_color_mode }
+    }
+}
+
+impl<CS, CD> SystemTrait<CS, CD> for AnsiRenderer
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        // Fetch window entity
+        let window_entity = db
+            .entity_component_directory
+            .get_entity_by_predicate(|entity_id| {
+                db.entity_component_directory
+                    .entity_has_component::<Window>(entity_id)
+                    && db
+                        .entity_component_directory
+                        .entity_has_component::<AnsiTerminalData>(entity_id)
+                    && db
+                        .entity_component_directory
+                        .entity_has_component::<Size>(entity_id)
+            })
+            .ok_or("No window entity")?;
+
+        let Vector2I(window_width, window_height) =
+            **db.get_entity_component::<Size>(window_entity)?;
+        let cell_count = (window_width * window_height) as usize;
+
+        // Fetch cell framebuffer
+        let framebuffer_cells = db.query_single::<&CellFramebuffer>()?.get_cells();
+
+        // The framebuffer lags a frame behind window resizes, so skip mismatched frames
+        if framebuffer_cells.len() != cell_count {
+            return Ok(());
+        }
+
+        let cells: Vec<AnsiCell> = framebuffer_cells
+            .into_iter()
+            .map(|cell| {
+                let foreground = cell
+                    .foreground
+                    .unwrap_or_else(|| self.text_color_mode.get_text_color(cell.background));
+
+                AnsiCell {
+                    glyph: cell.glyph,
+                    foreground: ColorRGB8::from(foreground),
+                    background: ColorRGB8::from(cell.background),
+                    attributes: cell.attributes,
+                }
+            })
+            .collect();
+
+        let frame = encode_frame(window_width as usize, &cells);
+
+        match &**db.get_entity_component::<AnsiTerminalData>(window_entity)? {
+            Some(terminal) => terminal
+                .write(frame.as_bytes())
+                .map_err(|err| format!("Failed to write to ANSI terminal: {}", err).into()),
+            None => Err("Error fetching terminal handle".into()),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use antigen::{
+    components::{Size, Window},
+    entity_component_system::{
+        system_interface::SystemInterface, ComponentStorage, EntityComponentDirectory, EntityID,
+        SystemError, SystemTrait,
+    },
+};
+
+use crate::{components::AnsiTerminalData, AnsiTerminal};
+
+/// Opens the terminal for the window entity, then keeps the window's size in sync with it
+#[derive(Debug)]
+pub struct AnsiWindow;
+
+impl Default for AnsiWindow {
+    fn default() -> Self {
+        AnsiWindow
+    }
+}
+
+impl AnsiWindow {
+    pub fn new() -> Self {
+        AnsiWindow
+    }
+
+    fn try_create_terminal<CS, CD>(
+        &mut self,
+        db: &mut SystemInterface<CS, CD>,
+        entity_id: EntityID,
+    ) -> Result<(), SystemError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        if db
+            .get_entity_component::<AnsiTerminalData>(entity_id)?
+            .is_some()
+        {
+            return Ok(());
+        }
+
+        let terminal =
+            AnsiTerminal::new().map_err(|err| format!("Failed to open ANSI terminal: {}", err))?;
+
+        **db.get_entity_component_mut::<AnsiTerminalData>(entity_id)? = Some(terminal);
+
+        Ok(())
+    }
+}
+
+impl<CS, CD> SystemTrait<CS, CD> for AnsiWindow
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        let window_entity = db
+            .entity_component_directory
+            .get_entity_by_predicate(|entity_id| {
+                db.entity_component_directory
+                    .entity_has_component::<Window>(entity_id)
+                    && db
+                        .entity_component_directory
+                        .entity_has_component::<AnsiTerminalData>(entity_id)
+                    && db
+                        .entity_component_directory
+                        .entity_has_component::<Size>(entity_id)
+            });
+
+        if let Some(window_entity) = window_entity {
+            self.try_create_terminal(db, window_entity)?;
+
+            // Terminal size is polled rather than waiting on SIGWINCH, as the query is cheap
+            let size = match &**db.get_entity_component::<AnsiTerminalData>(window_entity)? {
+                Some(terminal) => terminal
+                    .get_size()
+                    .map_err(|err| format!("Failed to get ANSI terminal size: {}", err))?,
+                None => return Ok(()),
+            };
+
+            **db.get_entity_component_mut::<Size>(window_entity)? = size;
+        }
+
+        Ok(())
+    }
+}
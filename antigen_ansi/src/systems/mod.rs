@@ -0,0 +1,7 @@
+mod ansi_input;
+mod ansi_renderer;
+mod ansi_window;
+
+pub use ansi_input::*;
+pub use ansi_renderer::*;
+pub use ansi_window::*;
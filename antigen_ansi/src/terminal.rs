@@ -0,0 +1,100 @@
+use std::io::{Error, Write};
+
+use antigen::primitive_types::Vector2I;
+
+use crate::escape_sequences::{ENTER_TERMINAL_MODE, LEAVE_TERMINAL_MODE};
+
+/// Raw-mode session on the process' controlling terminal
+///
+/// Opening one switches stdin to unbuffered, non-blocking raw input
+/// and the output to the alternate screen with mouse reporting enabled.
+/// The original terminal state is restored on drop.
+pub struct AnsiTerminal {
+    original_termios: libc::termios,
+}
+
+impl std::fmt::Debug for AnsiTerminal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnsiTerminal").finish()
+    }
+}
+
+impl AnsiTerminal {
+    pub fn new() -> Result<Self, Error> {
+        let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Err(Error::last_os_error());
+        }
+        let original_termios = termios;
+
+        // Signal keys are left enabled so Ctrl+C still interrupts the process
+        termios.c_iflag &= !(libc::BRKINT | libc::ICRNL | libc::INPCK | libc::ISTRIP | libc::IXON);
+        termios.c_oflag &= !libc::OPOST;
+        termios.c_cflag |= libc::CS8;
+        termios.c_lflag &= !(libc::ECHO | libc::ICANON | libc::IEXTEN);
+        termios.c_cc[libc::VMIN] = 0;
+        termios.c_cc[libc::VTIME] = 0;
+
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &termios) } != 0 {
+            return Err(Error::last_os_error());
+        }
+
+        let terminal = AnsiTerminal { original_termios };
+        terminal.write(ENTER_TERMINAL_MODE.as_bytes())?;
+        Ok(terminal)
+    }
+
+    /// Returns the terminal's size in cells
+    pub fn get_size(&self) -> Result<Vector2I, Error> {
+        let mut winsize: libc::winsize = unsafe { std::mem::zeroed() };
+        if unsafe { libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut winsize) } != 0 {
+            return Err(Error::last_os_error());
+        }
+        Ok(Vector2I(winsize.ws_col as i64, winsize.ws_row as i64))
+    }
+
+    /// Returns all input bytes received since the last read, without blocking
+    pub fn read(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        let mut buffer = [0u8; 1024];
+        loop {
+            let count = unsafe {
+                libc::read(
+                    libc::STDIN_FILENO,
+                    buffer.as_mut_ptr() as *mut libc::c_void,
+                    buffer.len(),
+                )
+            };
+
+            match count {
+                count if count > 0 => bytes.extend_from_slice(&buffer[..count as usize]),
+                0 => break,
+                _ => {
+                    let err = Error::last_os_error();
+                    match err.kind() {
+                        std::io::ErrorKind::Interrupted => continue,
+                        std::io::ErrorKind::WouldBlock => break,
+                        _ => return Err(err),
+                    }
+                }
+            }
+        }
+        Ok(bytes)
+    }
+
+    pub fn write(&self, bytes: &[u8]) -> Result<(), Error> {
+        let stdout = std::io::stdout();
+        let mut stdout = stdout.lock();
+        stdout.write_all(bytes)?;
+        stdout.flush()
+    }
+}
+
+impl Drop for AnsiTerminal {
+    fn drop(&mut self) {
+        let _ = self.write(LEAVE_TERMINAL_MODE.as_bytes());
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSAFLUSH, &self.original_termios);
+        }
+    }
+}
@@ -5,6 +5,11 @@ use pancurses::Window;
 #[derive(Debug, Default)]
 pub struct CursesWindowData(pub Option<Window>);
 
+// Safety: The curses systems are the only users of the window, and they declare no component access,
+// so runners always call them one at a time from the thread that owns the window
+unsafe impl Send for CursesWindowData {}
+unsafe impl Sync for CursesWindowData {}
+
 impl Deref for CursesWindowData {
     type Target = Option<Window>;
 
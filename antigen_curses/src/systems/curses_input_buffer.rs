@@ -49,7 +49,7 @@ where
                 if let Some(window) = window {
                     if let Some(input) = window.getch() {
                         // Fetch the entity queue component and push inputs into it
-                        let event_queue: &mut Vec<CursesEvent> = db
+                        let mut event_queue = db
                             .get_entity_component_mut::<EventQueue<CursesEvent>>(
                                 event_queue_entity,
                             )?;
@@ -59,7 +59,7 @@ where
                     });
 
             if let Some(event_queue_entity) = antigen_event_queue_entity {
-                let antigen_event_queue = db
+                let mut antigen_event_queue = db
                     .get_entity_component_mut::<EventQueue<AntigenInputEvent>>(
                         event_queue_entity,
                     )?;
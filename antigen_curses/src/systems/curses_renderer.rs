@@ -1,5 +1,8 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::components::CursesWindowData;
 use antigen::{
-    components::{Size, SoftwareFramebuffer, Window},
+    components::{CellAttributes, CellFramebuffer, Glyph, Size, TextColorMode, Window},
     core::palette::Palette,
     entity_component_system::{
         system_interface::SystemInterface, ComponentStorage, EntityComponentDirectory, SystemError,
@@ -7,18 +10,23 @@ use antigen::{
     },
     primitive_types::ColorRGB,
     primitive_types::ColorRGBF,
+    primitive_types::Vector2I,
 };
-use pancurses::ToChtype;
 
-use crate::components::CursesWindowData;
+/// A composited terminal cell: glyph, and color pair combined with attributes
+type CursesCell = (Glyph, pancurses::chtype);
 
-#[derive(Debug, Copy, Clone)]
-pub enum TextColorMode {
-    BlackWhite,
-    Invert,
-    Color(ColorRGBF),
-}
+/// Cell drawn by erase(), and by any position with nothing rendered to it
+const EMPTY_CELL: CursesCell = (Glyph::SPACE, 0);
+
+/// COLOR_PAIR packs the pair index into 8 bits of a chtype
+const MAX_COLOR_PAIRS: i32 = 256;
 
+/// Renders the cell framebuffer into the window's curses screen
+///
+/// The previous frame's cells are kept so that only those which changed are written each frame.
+/// The palette is registered with curses on the first run,
+/// and color pairs are allocated as foreground / background combinations are first used.
 #[derive(Debug)]
 pub struct CursesRenderer<T>
 where
@@ -26,6 +34,12 @@ where
 {
     palette: T,
     text_color_mode: TextColorMode,
+    full_redraw_on_resize: bool,
+    prev_size: Vector2I,
+    prev_cells: Vec<CursesCell>,
+    color_pairs: HashMap<(i16, i16), i16>,
+    colors: Vec<ColorRGBF>,
+    indices: Vec<(i16, usize)>,
 }
 
 impl<T> CursesRenderer<T>
@@ -36,87 +50,26 @@ where
         CursesRenderer {
             palette,
             text_color_mode,
+            full_redraw_on_resize: true,
+            prev_size: Vector2I::default(),
+            prev_cells: Vec::new(),
+            color_pairs: HashMap::new(),
+            colors: Vec::new(),
+            indices: Vec::new(),
         }
     }
-}
-
-impl<CS, CD, T> SystemTrait<CS, CD> for CursesRenderer<T>
-where
-    CS: ComponentStorage,
-    CD: EntityComponentDirectory,
-    T: Palette<From = f32, To = f32>,
-{
-    fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
-    where
-        CS: ComponentStorage,
-        CD: EntityComponentDirectory,
-    {
-        // Fetch window entity
-        let window_entity = db
-            .entity_component_directory
-            .get_entity_by_predicate(|entity_id| {
-                db.entity_component_directory
-                    .entity_has_component::<Window>(entity_id)
-                    && db
-                        .entity_component_directory
-                        .entity_has_component::<CursesWindowData>(entity_id)
-                    && db
-                        .entity_component_directory
-                        .entity_has_component::<Size>(entity_id)
-            })
-            .ok_or("No window entity")?;
-
-        let window_width: i64;
-        let window_height: i64;
-        {
-            let window: &Option<pancurses::Window> =
-                db.get_entity_component::<CursesWindowData>(window_entity)?;
-            if let Some(window) = window {
-                let (height, width) = window.get_max_yx();
 
-                window_width = width as i64;
-                window_height = height as i64;
-            } else {
-                panic!("Error fetching window handle");
-            }
-        }
-
-        // Fetch CPU framebuffer entity
-        let cpu_framebuffer_entity = db
-            .entity_component_directory
-            .get_entity_by_predicate(|entity_id| {
-                db.entity_component_directory
-                    .entity_has_component::<SoftwareFramebuffer<ColorRGBF>>(entity_id)
-            })
-            .expect("CPU framebuffer entity does not exist");
-
-        let color_buffer = db
-            .get_entity_component::<SoftwareFramebuffer<ColorRGBF>>(cpu_framebuffer_entity)?
-            .get_color_buffer();
-
-        let color_z_buffer = db
-            .get_entity_component::<SoftwareFramebuffer<ColorRGBF>>(cpu_framebuffer_entity)?
-            .get_z_buffer();
-
-        // Fetch string framebuffer entity
-        let string_framebuffer_enity = db
-            .entity_component_directory
-            .get_entity_by_predicate(|entity_id| {
-                db.entity_component_directory
-                    .entity_has_component::<SoftwareFramebuffer<char>>(entity_id)
-            })
-            .expect("String framebuffer entity does not exist");
-
-        let char_buffer = db
-            .get_entity_component::<SoftwareFramebuffer<char>>(string_framebuffer_enity)?
-            .get_color_buffer();
-
-        let char_z_buffer = db
-            .get_entity_component::<SoftwareFramebuffer<char>>(string_framebuffer_enity)?
-            .get_z_buffer();
+    /// Only write the cells that differ from the previous frame when the window is resized,
+    /// instead of clearing and redrawing the whole terminal
+    pub fn without_full_redraw_on_resize(mut self) -> Self {
+        self.full_redraw_on_resize = false;
+        self
+    }
 
+    /// Register the palette's colors with curses, swapping the closest matches to the built-in colors onto their indices
+    fn register_palette(&mut self) {
         // Create pancurses > palette map to make sure built-in pancurses colors are respected
-        let indices = [
+        let indices = vec![
             (
                 pancurses::COLOR_BLACK,
                 self.palette.get_color_idx(ColorRGB(0.0f32, 0.0f32, 0.0f32)),
@@ -156,99 +109,241 @@ where
             colors.swap(*palette_idx, *pancurses_idx as usize);
         }
 
-        // Register colors
         for (i, color) in colors.iter().enumerate() {
             let ColorRGB(r, g, b) = color;
-            let i = i as i16;
 
             pancurses::init_color(
-                i,
+                i as i16,
                 (r * 1000.0) as i16,
                 (g * 1000.0) as i16,
                 (b * 1000.0) as i16,
             );
+        }
 
-            let foreground_color = match self.text_color_mode {
-                TextColorMode::Color(color) => self.palette.get_color_idx(color),
-                TextColorMode::BlackWhite => {
-                    if ColorRGB::distance(color, &ColorRGB(1.0f32, 1.0f32, 1.0f32))
-                        > ColorRGB::distance(color, &ColorRGB(0.0f32, 0.0f32, 0.0f32))
-                    {
-                        self.palette.get_color_idx(ColorRGB(1.0f32, 1.0f32, 1.0f32))
-                    } else {
-                        self.palette.get_color_idx(ColorRGB(0.0f32, 0.0f32, 0.0f32))
-                    }
-                }
-                TextColorMode::Invert => self
-                    .palette
-                    .get_color_idx(ColorRGB(1.0f32, 1.0f32, 1.0f32) - *color),
+        self.colors = colors;
+        self.indices = indices;
+    }
+
+    fn get_color_pair(&mut self, foreground: i16, background: i16, pair_count: usize) -> i16 {
+        if let Some(color_pair) = self.color_pairs.get(&(foreground, background)) {
+            return *color_pair;
+        }
+
+        // Pair 0 is reserved for the terminal's default colors
+        let color_pair = self.color_pairs.len() + 1;
+        if color_pair >= pair_count {
+            return 0;
+        }
+
+        let color_pair = color_pair as i16;
+        pancurses::init_pair(color_pair, foreground, background);
+        self.color_pairs
+            .insert((foreground, background), color_pair);
+        color_pair
+    }
+}
+
+fn get_attributes(attributes: CellAttributes) -> pancurses::chtype {
+    let mut chtype = 0;
+
+    if attributes.bold {
+        chtype |= pancurses::A_BOLD;
+    }
+
+    if attributes.underline {
+        chtype |= pancurses::A_UNDERLINE;
+    }
+
+    if attributes.reverse {
+        chtype |= pancurses::A_REVERSE;
+    }
+
+    if attributes.dim {
+        chtype |= pancurses::A_DIM;
+    }
+
+    chtype
+}
+
+/// Returns the position and contents of each cell that differs from the previous frame
+///
+/// Cells outside the previous frame's bounds are always treated as changed.
+fn changed_cells(
+    prev_size: Vector2I,
+    prev_cells: &[CursesCell],
+    size: Vector2I,
+    cells: &[CursesCell],
+) -> Vec<(i32, i32, CursesCell)> {
+    let Vector2I(prev_width, prev_height) = prev_size;
+    let Vector2I(width, height) = size;
+
+    let mut changed = Vec::new();
+    for y in 0..height {
+        for x in 0..width {
+            let cell = cells[(y * width + x) as usize];
+
+            let prev_cell = if x < prev_width && y < prev_height {
+                prev_cells.get((y * prev_width + x) as usize)
+            } else {
+                None
             };
 
-            let mut foreground_color = foreground_color as i16;
-            for (pancurses_idx, palette_idx) in indices.iter() {
-                if foreground_color == *pancurses_idx {
-                    foreground_color = *palette_idx as i16;
-                } else if foreground_color == *palette_idx as i16 {
-                    foreground_color = *pancurses_idx;
-                }
+            if prev_cell != Some(&cell) {
+                changed.push((x as i32, y as i32, cell));
             }
+        }
+    }
 
-            let background_color = i;
+    changed
+}
 
-            pancurses::init_pair(i, foreground_color, background_color);
-        }
+impl<CS, CD, T> SystemTrait<CS, CD> for CursesRenderer<T>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+    T: Palette<From = f32, To = f32> + Send,
+{
+    fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        // Fetch window entity
+        let window_entity = db
+            .entity_component_directory
+            .get_entity_by_predicate(|entity_id| {
+                db.entity_component_directory
+                    .entity_has_component::<Window>(entity_id)
+                    && db
+                        .entity_component_directory
+                        .entity_has_component::<CursesWindowData>(entity_id)
+                    && db
+                        .entity_component_directory
+                        .entity_has_component::<Size>(entity_id)
+            })
+            .ok_or("No window entity")?;
+
+        let window_width: i64;
+        let window_height: i64;
+        {
+            let window: &Option<pancurses::Window> =
+                db.get_entity_component::<CursesWindowData>(window_entity)?;
+            if let Some(window) = window {
+                let (height, width) = window.get_max_yx();
 
-        let mut cells: Vec<(i32, i32, char, i16)> = Vec::new();
-        let window_width = window_width as i32;
-        let window_height = window_height as i32;
-        for y in 0..window_height as i32 {
-            for x in 0..window_width as i32 {
-                let idx = (y * window_width + x) as usize;
+                window_width = width as i64;
+                window_height = height as i64;
+            } else {
+                panic!("Error fetching window handle");
+            }
+        }
 
-                let color = color_buffer[idx];
-                let color_z = color_z_buffer[idx];
+        // Fetch cell framebuffer
+        let framebuffer_cells = db.query_single::<&CellFramebuffer>()?.get_cells();
 
-                let char = char_buffer[idx];
-                let char_z = char_z_buffer[idx];
+        // The framebuffer lags a frame behind window resizes, so skip mismatched frames
+        if framebuffer_cells.len() != (window_width * window_height) as usize {
+            return Ok(());
+        }
 
-                if color == ColorRGB(0.0, 0.0, 0.0) && char == ' ' {
-                    continue;
-                }
+        // Colors only need registering with curses once, as palettes don't change after creation
+        if self.colors.is_empty() {
+            self.register_palette();
+        }
 
-                if color_z.is_none() && char_z.is_none() {
-                    continue;
+        let get_color_idx = |color: ColorRGBF| {
+            let mut color_idx = self.palette.get_color_idx(color) as i16;
+            for (pancurses_idx, palette_idx) in self.indices.iter() {
+                if color_idx == *pancurses_idx {
+                    color_idx = *palette_idx as i16;
+                } else if color_idx == *palette_idx as i16 {
+                    color_idx = *pancurses_idx;
                 }
+            }
+            color_idx
+        };
 
-                let mut color_pair = self.palette.get_color_idx(color) as i16;
-                for (pancurses_idx, palette_idx) in indices.iter() {
-                    if color_pair == *pancurses_idx {
-                        color_pair = *palette_idx as i16;
-                    } else if color_pair == *palette_idx as i16 {
-                        color_pair = *pancurses_idx;
-                    }
+        // Resolve the colors of each non-empty cell to pancurses color indices
+        let color_cells: Vec<Option<(Glyph, i16, i16, CellAttributes)>> = framebuffer_cells
+            .into_iter()
+            .map(|cell| {
+                if cell.glyph == ' '
+                    && cell.background == ColorRGB(0.0, 0.0, 0.0)
+                    && cell.attributes == CellAttributes::default()
+                {
+                    return None;
                 }
 
-                let char = match char_z.cmp(&color_z) {
-                    std::cmp::Ordering::Less => ' ',
-                    std::cmp::Ordering::Equal => char,
-                    std::cmp::Ordering::Greater => char,
+                let background = get_color_idx(cell.background);
+                let foreground = match cell.foreground {
+                    Some(foreground) => get_color_idx(foreground),
+                    None => get_color_idx(
+                        self.text_color_mode
+                            .get_text_color(self.colors[background as usize]),
+                    ),
                 };
 
-                cells.push((x, y, char, color_pair));
-            }
+                Some((cell.glyph, foreground, background, cell.attributes))
+            })
+            .collect();
+
+        // Start allocating color pairs over if this frame needs more than are available
+        let pair_count = pancurses::COLOR_PAIRS().min(MAX_COLOR_PAIRS) as usize;
+        let new_pairs = color_cells
+            .iter()
+            .flatten()
+            .map(|(_, foreground, background, _)| (*foreground, *background))
+            .filter(|color_pair| !self.color_pairs.contains_key(color_pair))
+            .collect::<HashSet<_>>();
+
+        if self.color_pairs.len() + new_pairs.len() >= pair_count {
+            // Redefined pairs would recolor cells already on screen, so redraw them all
+            self.color_pairs.clear();
+            self.prev_cells.clear();
         }
 
+        let cells: Vec<CursesCell> = color_cells
+            .into_iter()
+            .map(|cell| match cell {
+                Some((glyph, foreground, background, attributes)) => {
+                    let color_pair = self.get_color_pair(foreground, background, pair_count);
+                    (
+                        glyph,
+                        pancurses::COLOR_PAIR(color_pair as pancurses::chtype)
+                            | get_attributes(attributes),
+                    )
+                }
+                None => EMPTY_CELL,
+            })
+            .collect();
+
         let window: &Option<pancurses::Window> =
             db.get_entity_component::<CursesWindowData>(window_entity)?;
         if let Some(window) = window {
-            window.erase();
-            for (x, y, char, color_pair) in cells {
-                window.mvaddch(
-                    y as i32,
-                    x as i32,
-                    char.to_chtype() | pancurses::COLOR_PAIR(color_pair as u64),
-                );
+            let window_size = Vector2I(window_width, window_height);
+
+            if window_size != self.prev_size && self.full_redraw_on_resize {
+                // Forces curses to repaint the whole terminal on its next refresh
+                window.clear();
+                self.prev_size = window_size;
+                self.prev_cells = vec![EMPTY_CELL; cells.len()];
+            }
+
+            for (x, y, (glyph, attributes)) in
+                changed_cells(self.prev_size, &self.prev_cells, window_size, &cells)
+            {
+                // The right half of a wide glyph is filled in by curses when the glyph is written
+                if glyph.is_continuation() {
+                    continue;
+                }
+
+                window.attrset(attributes);
+                window.mvaddstr(y, x, glyph.as_str());
             }
+            window.attrset(pancurses::A_NORMAL);
+
+            self.prev_size = window_size;
+            self.prev_cells = cells;
 
             Ok(())
         } else {
@@ -256,3 +351,39 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use antigen::primitive_types::Vector2I;
+
+    use super::{changed_cells, EMPTY_CELL};
+
+    #[test]
+    fn changed_cells_diff() {
+        let prev_cells = vec![('a'.into(), 1), EMPTY_CELL, EMPTY_CELL, ('b'.into(), 2)];
+        let cells = vec![
+            ('a'.into(), 1),
+            ('c'.into(), 1),
+            EMPTY_CELL,
+            ('b'.into(), 3),
+        ];
+        assert_eq!(
+            changed_cells(Vector2I(2, 2), &prev_cells, Vector2I(2, 2), &cells),
+            vec![(1, 0, ('c'.into(), 1)), (1, 1, ('b'.into(), 3))]
+        );
+
+        // Only cells outside the previous bounds are written after growing
+        let grown_cells = vec![
+            ('a'.into(), 1),
+            ('c'.into(), 1),
+            EMPTY_CELL,
+            EMPTY_CELL,
+            ('b'.into(), 3),
+            EMPTY_CELL,
+        ];
+        assert_eq!(
+            changed_cells(Vector2I(2, 2), &cells, Vector2I(3, 2), &grown_cells),
+            vec![(2, 0, EMPTY_CELL), (2, 1, EMPTY_CELL)]
+        );
+    }
+}
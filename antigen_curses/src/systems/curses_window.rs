@@ -1,7 +1,7 @@
 use antigen::{
     components::{EventQueue, Size, Window},
     entity_component_system::{
-        system_interface::SystemInterface, ComponentStorage, ComponentTrait,
+        system_interface::SystemInterface, ComponentHooks, ComponentStorage, EcsError,
         EntityComponentDirectory, EntityID, SystemError, SystemTrait,
     },
     primitive_types::Vector2I,
@@ -14,17 +14,14 @@ use crate::components::{CursesEvent, CursesWindowData};
 #[derive(Debug)]
 pub struct CursesWindow;
 
-impl CursesWindow {
-    pub fn new<CS>(component_storage: &mut CS) -> Self
-    where
-        CS: ComponentStorage,
-    {
-        fn drop_callback(_: &mut dyn ComponentTrait) {
-            pancurses::endwin();
-        }
-
-        component_storage.register_component_drop_callback::<CursesWindowData>(drop_callback);
+impl Default for CursesWindow {
+    fn default() -> Self {
+        CursesWindow
+    }
+}
 
+impl CursesWindow {
+    pub fn new() -> Self {
         CursesWindow
     }
 
@@ -32,7 +29,7 @@ impl CursesWindow {
         &mut self,
         db: &mut SystemInterface<CS, CD>,
         entity_id: EntityID,
-    ) -> Result<(), String>
+    ) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
@@ -66,10 +63,8 @@ impl CursesWindow {
         window.keypad(true);
         window.timeout(0);
 
-        let curses_window: &mut Option<pancurses::Window> =
-            db.get_entity_component_mut::<CursesWindowData>(entity_id)?;
-
-        *curses_window = Some(window);
+        let mut curses_window = db.get_entity_component_mut::<CursesWindowData>(entity_id)?;
+        **curses_window = Some(window);
 
         Ok(())
     }
@@ -157,4 +152,10 @@ where
 
         Ok(())
     }
+
+    fn register_component_hooks(&self, component_hooks: &mut ComponentHooks<CS, CD>) {
+        component_hooks.on_remove::<CursesWindowData, _>(|_, _, _| {
+            pancurses::endwin();
+        });
+    }
 }
@@ -0,0 +1,24 @@
+use std::{
+    collections::VecDeque,
+    ops::{Deref, DerefMut},
+};
+
+use antigen::core::events::AntigenInputEvent;
+
+/// Scripted input events, one entry per frame, fed into the input event queue by the HeadlessInput system
+#[derive(Debug, Default, Clone)]
+pub struct HeadlessInputData(pub VecDeque<Vec<AntigenInputEvent>>);
+
+impl Deref for HeadlessInputData {
+    type Target = VecDeque<Vec<AntigenInputEvent>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for HeadlessInputData {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,327 @@
+use std::{fmt::Display, path::Path};
+
+use antigen::{
+    components::Glyph,
+    core::palette::Palette,
+    primitive_types::{ColorRGBF, Vector2I},
+};
+
+/// Environment variable that, when set, makes assert_golden_frame write golden files instead of comparing
+pub const UPDATE_GOLDEN_FRAMES: &str = "ANTIGEN_UPDATE_GOLDEN";
+
+/// Text form of a rendered frame, for comparison against a stored golden file
+///
+/// Glyphs are stored as-is, with wide glyphs spanning two columns, and colors as an index grid of the given palette
+/// so that small floating point differences don't cause spurious mismatches.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GoldenFrame {
+    size: Vector2I,
+    chars: Vec<String>,
+    colors: Vec<String>,
+}
+
+impl GoldenFrame {
+    pub fn new<P>(size: Vector2I, glyphs: &[Glyph], colors: &[ColorRGBF], palette: &P) -> Self
+    where
+        P: Palette<From = f32, To = f32>,
+    {
+        let Vector2I(width, _) = size;
+        let width = width.max(1) as usize;
+
+        // Indices are written as fixed-width hex, wide enough for the largest index in the palette
+        let digits = format!("{:x}", palette.get_colors().len().max(2) - 1).len();
+
+        let chars = glyphs
+            .chunks(width)
+            .map(|row| row.iter().map(Glyph::as_str).collect())
+            .collect();
+
+        let colors = colors
+            .chunks(width)
+            .map(|row| {
+                row.iter()
+                    .map(|color| {
+                        format!(
+                            "{:0digits$x}",
+                            palette.get_color_idx(*color),
+                            digits = digits
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        GoldenFrame {
+            size,
+            chars,
+            colors,
+        }
+    }
+}
+
+impl Display for GoldenFrame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Vector2I(width, height) = self.size;
+        writeln!(f, "size: {}x{}", width, height)?;
+
+        // Rows are fenced so trailing whitespace survives editors and diffs
+        writeln!(f, "chars:")?;
+        for row in &self.chars {
+            writeln!(f, "|{}|", row)?;
+        }
+
+        writeln!(f, "colors:")?;
+        for row in &self.colors {
+            writeln!(f, "|{}|", row)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Line-by-line diff between two frames' text, marking the differing columns of each changed line
+fn diff_lines(expected: &str, actual: &str) -> String {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    let mut diff = String::new();
+    for i in 0..expected.len().max(actual.len()) {
+        let expected_line = expected.get(i).copied();
+        let actual_line = actual.get(i).copied();
+        if expected_line == actual_line {
+            continue;
+        }
+
+        let expected_line = expected_line.unwrap_or_default();
+        let actual_line = actual_line.unwrap_or_default();
+
+        let expected_chars: Vec<char> = expected_line.chars().collect();
+        let actual_chars: Vec<char> = actual_line.chars().collect();
+        let markers: String = (0..expected_chars.len().max(actual_chars.len()))
+            .map(|x| {
+                if expected_chars.get(x) == actual_chars.get(x) {
+                    ' '
+                } else {
+                    '^'
+                }
+            })
+            .collect();
+
+        diff += &format!(
+            "line {}:\n  - {}\n  + {}\n    {}\n",
+            i + 1,
+            expected_line,
+            actual_line,
+            markers.trim_end()
+        );
+    }
+
+    diff
+}
+
+/// Compare a frame against the golden file at the given path, panicking with a readable diff on mismatch
+///
+/// Golden files are only created or overwritten while UPDATE_GOLDEN_FRAMES is set, so a missing one is a failure.
+/// On mismatch the diff is also written alongside the golden file, with a `.diff` extension.
+pub fn assert_golden_frame<P>(path: P, frame: &GoldenFrame)
+where
+    P: AsRef<Path>,
+{
+    let path = path.as_ref();
+    let actual = frame.to_string();
+
+    if std::env::var_os(UPDATE_GOLDEN_FRAMES).is_some() {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .unwrap_or_else(|err| panic!("Failed to create {}: {}", parent.display(), err));
+        }
+        std::fs::write(path, actual)
+            .unwrap_or_else(|err| panic!("Failed to write {}: {}", path.display(), err));
+        return;
+    }
+
+    compare_golden_frame(path, &actual);
+}
+
+/// Compare a frame's text against an existing golden file, without ever creating it
+fn compare_golden_frame(path: &Path, actual: &str) {
+    if !path.exists() {
+        panic!(
+            "Golden file {} does not exist\nRerun with {} set to create it from this frame:\n{}",
+            path.display(),
+            UPDATE_GOLDEN_FRAMES,
+            actual
+        );
+    }
+
+    let expected = std::fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("Failed to read {}: {}", path.display(), err));
+
+    let diff_path = path.with_extension("diff");
+    if expected == actual {
+        let _ = std::fs::remove_file(diff_path);
+        return;
+    }
+
+    let diff = diff_lines(&expected, actual);
+    std::fs::write(&diff_path, &diff)
+        .unwrap_or_else(|err| panic!("Failed to write {}: {}", diff_path.display(), err));
+
+    panic!(
+        "Frame does not match golden file {}\n{}\nRerun with {} set to accept the new frame",
+        path.display(),
+        diff,
+        UPDATE_GOLDEN_FRAMES
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use antigen::{
+        components::{
+            Control, EventQueue, Glyph, ListData, LocalMousePositionData, ParentEntity, Position,
+            Size,
+        },
+        core::{events::AntigenInputEvent, palette::RGBArrangementPalette},
+        entity_component_system::SystemStage,
+        primitive_types::{ColorRGB, Vector2I},
+        systems::{
+            AnchorsMargins, ChildEntities, GlobalPosition, List, ListEvent, LocalMousePosition,
+        },
+    };
+
+    use crate::HeadlessHarness;
+
+    use super::{assert_golden_frame, compare_golden_frame, diff_lines, GoldenFrame};
+
+    fn glyphs(string: &str) -> Vec<Glyph> {
+        string.chars().map(Glyph::from).collect()
+    }
+
+    #[test]
+    fn golden_frame() {
+        let palette = vec![ColorRGB(0.0f32, 0.0f32, 0.0f32), ColorRGB(1.0, 0.0, 0.0)];
+        let black = ColorRGB(0.1, 0.0, 0.0);
+        let red = ColorRGB(0.9, 0.1, 0.0);
+
+        let frame = GoldenFrame::new(
+            Vector2I(3, 2),
+            &glyphs("ab    "),
+            &[black, red, red, black, black, black],
+            &palette,
+        );
+        assert_eq!(
+            frame.to_string(),
+            "size: 3x2\nchars:\n|ab |\n|   |\ncolors:\n|011|\n|000|\n"
+        );
+
+        let changed = GoldenFrame::new(
+            Vector2I(3, 2),
+            &glyphs("ac    "),
+            &[black, red, red, black, black, black],
+            &palette,
+        );
+        assert_eq!(
+            diff_lines(&frame.to_string(), &changed.to_string()),
+            "line 3:\n  - |ab |\n  + |ac |\n      ^\n"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "does not exist")]
+    fn missing_golden_frame() {
+        let frame = GoldenFrame::new(
+            Vector2I(1, 1),
+            &glyphs("a"),
+            &[ColorRGB(0.0, 0.0, 0.0)],
+            &vec![ColorRGB(0.0f32, 0.0f32, 0.0f32)],
+        );
+
+        // Compared directly so that running in update mode doesn't create the file
+        let path = std::env::temp_dir()
+            .join(format!("antigen_missing_{}", std::process::id()))
+            .join("missing.frame");
+        compare_golden_frame(&path, &frame.to_string());
+    }
+
+    #[test]
+    fn list_golden_frame() {
+        let mut harness = HeadlessHarness::new(Vector2I(12, 6)).unwrap();
+        harness
+            .ecs
+            .push_system(LocalMousePosition::new())
+            .stage(SystemStage::Input)
+            .after("headless_input");
+        harness.ecs.push_system(List::new());
+        harness
+            .ecs
+            .push_system(AnchorsMargins::new())
+            .stage(SystemStage::Layout)
+            .label("anchors_margins");
+        harness
+            .ecs
+            .push_system(GlobalPosition::new())
+            .stage(SystemStage::Layout)
+            .after("anchors_margins");
+        harness
+            .ecs
+            .push_system(ChildEntities::new())
+            .stage(SystemStage::Layout);
+
+        let window_entity = harness.get_window_entity();
+        {
+            let mut db = harness.ecs.get_system_interface();
+
+            let string_list_entity = db.create_entity(Some("Strings")).unwrap();
+            db.insert_entity_component(
+                string_list_entity,
+                (0..8)
+                    .map(|i| format!("Item {}", i))
+                    .collect::<Vec<String>>(),
+            )
+            .unwrap();
+
+            let list_entity = db.create_entity(Some("List")).unwrap();
+            db.insert_entity_component(list_entity, Control).unwrap();
+            db.insert_entity_component(list_entity, Position(Vector2I(2, 1)))
+                .unwrap();
+            db.insert_entity_component(list_entity, Size(Vector2I(8, 4)))
+                .unwrap();
+            db.insert_entity_component(list_entity, ColorRGB(0.0f32, 0.0f32, 0.25f32))
+                .unwrap();
+            db.insert_entity_component(list_entity, ListData::new(Some(string_list_entity)))
+                .unwrap();
+            db.insert_entity_component(list_entity, LocalMousePositionData::default())
+                .unwrap();
+            db.insert_entity_component(list_entity, EventQueue::<ListEvent>::default())
+                .unwrap();
+            db.insert_entity_component(list_entity, ParentEntity(window_entity))
+                .unwrap();
+
+            db.apply_commands().unwrap();
+        }
+
+        // Scroll down two items, then click the third visible one
+        harness
+            .push_input(vec![AntigenInputEvent::MouseMove {
+                position: Vector2I(3, 3),
+                delta: Vector2I(0, 0),
+            }])
+            .unwrap();
+        harness
+            .push_input(vec![AntigenInputEvent::MouseScroll { delta: 2 }])
+            .unwrap();
+        harness
+            .push_input(vec![AntigenInputEvent::MousePress { button_mask: 1 }])
+            .unwrap();
+        harness.run_frames(5).unwrap();
+
+        let frame = harness
+            .get_golden_frame(&RGBArrangementPalette::new_884())
+            .unwrap();
+        assert_golden_frame(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/golden/list.frame"),
+            &frame,
+        );
+    }
+}
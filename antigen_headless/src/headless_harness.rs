@@ -0,0 +1,530 @@
+use antigen::{
+    components::{Cell, CellFramebuffer, EventQueue, Glyph, Position, Size, Window},
+    core::{events::AntigenInputEvent, palette::Palette},
+    entity_component_system::{
+        system_runner::SingleThreadedSystemRunner,
+        system_storage::{HeapSystemStorage, SystemStorage},
+        ComponentID, ComponentStorage, ComponentTrait, EcsError, EntityComponentDirectory,
+        EntityComponentSystem, EntityID, HeapComponentStorage, SingleThreadedDirectory,
+        SystemError, SystemRunner, SystemStage,
+    },
+    primitive_types::{ColorRGBF, Vector2I},
+    systems::{SoftwareRenderer, StringRenderer},
+};
+
+use crate::{components::HeadlessInputData, systems::HeadlessInput, GoldenFrame};
+
+pub type HeadlessEntityComponentSystem = EntityComponentSystem<
+    HeapComponentStorage,
+    SingleThreadedDirectory,
+    HeapSystemStorage<HeapComponentStorage, SingleThreadedDirectory>,
+    SingleThreadedSystemRunner,
+>;
+
+/// Runs an EntityComponentSystem against a virtual window, so rendered output can be inspected without a terminal
+///
+/// The harness owns the window, input queue and framebuffer entities, and pushes the systems that fill them.
+/// Controls to be rendered should be parented to the window entity, with layout systems pushed via `ecs`.
+pub struct HeadlessHarness<CS, CD, SS, SR>
+where
+    CS: ComponentStorage + 'static,
+    CD: EntityComponentDirectory + 'static,
+    SS: SystemStorage<CS, CD>,
+    SR: SystemRunner,
+{
+    pub ecs: EntityComponentSystem<CS, CD, SS, SR>,
+    window_entity: EntityID,
+    input_entity: EntityID,
+    framebuffer_entity: EntityID,
+}
+
+impl
+    HeadlessHarness<
+        HeapComponentStorage,
+        SingleThreadedDirectory,
+        HeapSystemStorage<HeapComponentStorage, SingleThreadedDirectory>,
+        SingleThreadedSystemRunner,
+    >
+{
+    pub fn new(window_size: Vector2I) -> Result<Self, EcsError> {
+        HeadlessHarness::with_ecs(HeadlessEntityComponentSystem::default(), window_size)
+    }
+}
+
+impl<CS, CD, SS, SR> HeadlessHarness<CS, CD, SS, SR>
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory + 'static,
+    SS: SystemStorage<CS, CD> + 'static,
+    SR: SystemRunner + 'static,
+{
+    pub fn with_ecs(
+        mut ecs: EntityComponentSystem<CS, CD, SS, SR>,
+        window_size: Vector2I,
+    ) -> Result<Self, EcsError> {
+        ecs.push_system(HeadlessInput::new())
+            .stage(SystemStage::Input)
+            .label("headless_input");
+        ecs.push_system(SoftwareRenderer)
+            .stage(SystemStage::Render)
+            .label("framebuffer");
+        ecs.push_system(StringRenderer)
+            .stage(SystemStage::Render)
+            .label("framebuffer");
+
+        let mut db = ecs.get_system_interface();
+
+        let input_entity = db.create_entity(Some("Global Event Queues"))?;
+        db.insert_entity_component(input_entity, EventQueue::<AntigenInputEvent>::default())?;
+        db.insert_entity_component(input_entity, HeadlessInputData::default())?;
+
+        let framebuffer_entity = db.create_entity(Some("Cell Framebuffer"))?;
+        db.insert_entity_component(framebuffer_entity, CellFramebuffer::default())?;
+
+        let window_entity = db.create_entity(Some("Headless Window"))?;
+        db.insert_entity_component(window_entity, Window)?;
+        db.insert_entity_component(window_entity, Position::default())?;
+        db.insert_entity_component(window_entity, Size(window_size))?;
+
+        db.apply_commands()?;
+
+        Ok(HeadlessHarness {
+            ecs,
+            window_entity,
+            input_entity,
+            framebuffer_entity,
+        })
+    }
+
+    pub fn get_window_entity(&self) -> EntityID {
+        self.window_entity
+    }
+
+    pub fn get_window_size(&self) -> Result<Vector2I, EcsError> {
+        Ok(**self.get_component::<Size>(self.window_entity)?)
+    }
+
+    /// Resize the virtual window, as a terminal resize would
+    pub fn set_window_size(&mut self, window_size: Vector2I) -> Result<(), EcsError> {
+        let window_entity = self.window_entity;
+        let mut db = self.ecs.get_system_interface();
+        **db.get_entity_component_mut::<Size>(window_entity)? = window_size;
+        Ok(())
+    }
+
+    /// Queue a frame's worth of input events, delivered after any frames already queued
+    pub fn push_input(&mut self, events: Vec<AntigenInputEvent>) -> Result<(), EcsError> {
+        let input_entity = self.input_entity;
+        let mut db = self.ecs.get_system_interface();
+        db.get_entity_component_mut::<HeadlessInputData>(input_entity)?
+            .push_back(events);
+        Ok(())
+    }
+
+    pub fn run_frames(&mut self, frames: usize) -> Result<(), SystemError> {
+        for _ in 0..frames {
+            self.ecs.run()?;
+        }
+        Ok(())
+    }
+
+    pub fn get_cells(&self) -> Result<Vec<Cell>, EcsError> {
+        Ok(self
+            .get_component::<CellFramebuffer>(self.framebuffer_entity)?
+            .get_cells())
+    }
+
+    /// Returns the background color of each cell
+    pub fn get_color_buffer(&self) -> Result<Vec<ColorRGBF>, EcsError> {
+        Ok(self
+            .get_cells()?
+            .into_iter()
+            .map(|cell| cell.background)
+            .collect())
+    }
+
+    /// Returns the glyph of each cell, with continuations following wide glyphs
+    pub fn get_glyph_buffer(&self) -> Result<Vec<Glyph>, EcsError> {
+        Ok(self
+            .get_cells()?
+            .into_iter()
+            .map(|cell| cell.glyph)
+            .collect())
+    }
+
+    /// Returns the string framebuffer as one string per row
+    pub fn get_lines(&self) -> Result<Vec<String>, EcsError> {
+        let Vector2I(width, _) = self.get_window_size()?;
+        Ok(self
+            .get_glyph_buffer()?
+            .chunks(width.max(1) as usize)
+            .map(|row| row.iter().map(Glyph::as_str).collect())
+            .collect())
+    }
+
+    /// Capture the current framebuffers for comparison via assert_golden_frame
+    pub fn get_golden_frame<P>(&self, palette: &P) -> Result<GoldenFrame, EcsError>
+    where
+        P: Palette<From = f32, To = f32>,
+    {
+        Ok(GoldenFrame::new(
+            self.get_window_size()?,
+            &self.get_glyph_buffer()?,
+            &self.get_color_buffer()?,
+            palette,
+        ))
+    }
+
+    fn get_component<T>(&self, entity_id: EntityID) -> Result<&T, EcsError>
+    where
+        T: ComponentTrait + 'static,
+    {
+        let component_data_id = self
+            .ecs
+            .entity_component_directory
+            .get_entity_component_data_id(&entity_id, &ComponentID::get::<T>())?;
+        self.ecs
+            .component_storage
+            .get_component_data::<T>(&component_data_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use antigen::{
+        components::{
+            Anchors, CellAttributes, Control, DebugComponentList, DebugEntityList, DebugExclude,
+            EventQueue, EventTargets, Glyph, IntRange, ListData, LocalMousePositionData, Margins,
+            ParentEntity, Position, Size, TextColor, TextStyle,
+        },
+        core::{events::AntigenInputEvent, keyboard::Key, palette::RGBArrangementPalette},
+        entity_component_system::{
+            system_runner::ParallelSystemRunner, system_storage::HeapSystemStorage,
+            EntityComponentSystem, EntityID, HeapComponentStorage, SingleThreadedDirectory,
+            SystemInterface, SystemRunner, SystemStage,
+        },
+        primitive_types::{ColorRGB, Vector2I},
+        systems::{
+            AnchorsMargins, ChildEntities, EntityInspectorEvent, EventConsumer, EventProcessor,
+            GlobalPosition, List, ListEvent, LocalMousePosition,
+        },
+    };
+
+    use super::HeadlessHarness;
+    use crate::assert_golden_frame;
+
+    /// Push layout systems, and parent a rect and a styled string to the window
+    fn create_layout_scene<SR>(
+        harness: &mut HeadlessHarness<
+            HeapComponentStorage,
+            SingleThreadedDirectory,
+            HeapSystemStorage<HeapComponentStorage, SingleThreadedDirectory>,
+            SR,
+        >,
+    ) where
+        SR: SystemRunner + 'static,
+    {
+        harness
+            .ecs
+            .push_system(AnchorsMargins::new())
+            .stage(SystemStage::Layout)
+            .label("anchors_margins");
+        harness
+            .ecs
+            .push_system(GlobalPosition::new())
+            .stage(SystemStage::Layout)
+            .after("anchors_margins");
+        harness
+            .ecs
+            .push_system(ChildEntities::new())
+            .stage(SystemStage::Layout);
+
+        let window_entity = harness.get_window_entity();
+        let mut db = harness.ecs.get_system_interface();
+
+        let rect_entity = db.create_entity(Some("Rect")).unwrap();
+        db.insert_entity_component(rect_entity, Control).unwrap();
+        db.insert_entity_component(rect_entity, Position::default())
+            .unwrap();
+        db.insert_entity_component(rect_entity, Size::default())
+            .unwrap();
+        db.insert_entity_component(rect_entity, Anchors::new(0.5..1.0, 0.0..1.0))
+            .unwrap();
+        db.insert_entity_component(rect_entity, Margins::new(0, 0, 1, 1))
+            .unwrap();
+        db.insert_entity_component(rect_entity, ColorRGB(1.0f32, 0.0f32, 0.0f32))
+            .unwrap();
+        db.insert_entity_component(rect_entity, ParentEntity(window_entity))
+            .unwrap();
+
+        let string_entity = db.create_entity(Some("String")).unwrap();
+        db.insert_entity_component(string_entity, Control).unwrap();
+        db.insert_entity_component(string_entity, Position(Vector2I(1, 0)))
+            .unwrap();
+        db.insert_entity_component(string_entity, "Hi".to_string())
+            .unwrap();
+        db.insert_entity_component(string_entity, TextColor(ColorRGB(0.0, 1.0, 0.0)))
+            .unwrap();
+        db.insert_entity_component(
+            string_entity,
+            TextStyle(CellAttributes {
+                bold: true,
+                ..CellAttributes::default()
+            }),
+        )
+        .unwrap();
+        db.insert_entity_component(string_entity, ParentEntity(window_entity))
+            .unwrap();
+
+        db.apply_commands().unwrap();
+    }
+
+    #[test]
+    fn headless_harness() {
+        let mut harness = HeadlessHarness::new(Vector2I(8, 4)).unwrap();
+        create_layout_scene(&mut harness);
+
+        harness
+            .push_input(vec![AntigenInputEvent::KeyPress {
+                key_code: Key::Space,
+            }])
+            .unwrap();
+        harness.run_frames(1).unwrap();
+
+        assert_eq!(
+            harness.get_lines().unwrap(),
+            vec![" Hi     ", "        ", "        ", "        "]
+        );
+
+        // Anchored to the right half of the window, inset by a row at the top and bottom
+        let color_buffer = harness.get_color_buffer().unwrap();
+        let red = ColorRGB(1.0, 0.0, 0.0);
+        let black = ColorRGB(0.0, 0.0, 0.0);
+        assert_eq!(color_buffer[8 + 4], red);
+        assert_eq!(color_buffer[16 + 7], red);
+        assert_eq!(color_buffer[8 + 3], black);
+        assert_eq!(color_buffer[4], black);
+        assert_eq!(color_buffer[24 + 4], black);
+
+        // Text color and style are carried through to the cells
+        let cells = harness.get_cells().unwrap();
+        assert_eq!(cells[1].foreground, Some(ColorRGB(0.0, 1.0, 0.0)));
+        assert!(cells[1].attributes.bold);
+        assert_eq!(cells[3].foreground, None);
+        assert!(!cells[3].attributes.bold);
+
+        // Scripted input is delivered for a single frame
+        let input_entity = harness.input_entity;
+        assert_eq!(
+            **harness
+                .get_component::<EventQueue<AntigenInputEvent>>(input_entity)
+                .unwrap(),
+            vec![AntigenInputEvent::KeyPress {
+                key_code: Key::Space
+            }]
+        );
+
+        harness.run_frames(1).unwrap();
+        assert!(harness
+            .get_component::<EventQueue<AntigenInputEvent>>(input_entity)
+            .unwrap()
+            .is_empty());
+
+        // Layout follows the virtual window's size
+        harness.set_window_size(Vector2I(4, 2)).unwrap();
+        harness.run_frames(1).unwrap();
+        assert_eq!(harness.get_lines().unwrap(), vec![" Hi ", "    "]);
+    }
+
+    #[test]
+    fn parallel_system_runner() {
+        let ecs = EntityComponentSystem::new(
+            HeapComponentStorage::new(),
+            SingleThreadedDirectory::new(),
+            HeapSystemStorage::new(),
+            ParallelSystemRunner::new(2).unwrap(),
+        )
+        .unwrap();
+        let mut harness = HeadlessHarness::with_ecs(ecs, Vector2I(8, 4)).unwrap();
+        create_layout_scene(&mut harness);
+
+        // The same scene renders the same frame when its systems run in concurrent batches
+        harness.run_frames(1).unwrap();
+
+        assert_eq!(
+            harness.get_lines().unwrap(),
+            vec![" Hi     ", "        ", "        ", "        "]
+        );
+
+        let color_buffer = harness.get_color_buffer().unwrap();
+        assert_eq!(color_buffer[8 + 4], ColorRGB(1.0, 0.0, 0.0));
+        assert_eq!(color_buffer[8 + 3], ColorRGB(0.0, 0.0, 0.0));
+
+        let cells = harness.get_cells().unwrap();
+        assert_eq!(cells[1].foreground, Some(ColorRGB(0.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn unicode_text() {
+        let mut harness = HeadlessHarness::new(Vector2I(6, 2)).unwrap();
+        harness
+            .ecs
+            .push_system(ChildEntities::new())
+            .stage(SystemStage::Layout);
+
+        let window_entity = harness.get_window_entity();
+        {
+            let mut db = harness.ecs.get_system_interface();
+
+            for (position, text) in &[(Vector2I(0, 0), "e\u{301}日x"), (Vector2I(2, 1), "│日本")]
+            {
+                let string_entity = db.create_entity(Some("String")).unwrap();
+                db.insert_entity_component(string_entity, Control).unwrap();
+                db.insert_entity_component(string_entity, Position(*position))
+                    .unwrap();
+                db.insert_entity_component(string_entity, text.to_string())
+                    .unwrap();
+                db.insert_entity_component(string_entity, ParentEntity(window_entity))
+                    .unwrap();
+            }
+
+            db.apply_commands().unwrap();
+        }
+
+        harness.run_frames(1).unwrap();
+
+        // Combining marks share a cell, wide glyphs take two,
+        // and those cut by the window edge are left blank
+        assert_eq!(
+            harness.get_lines().unwrap(),
+            vec!["e\u{301}日x  ", "  │日 "]
+        );
+
+        let glyphs = harness.get_glyph_buffer().unwrap();
+        assert_eq!(glyphs[1], Glyph::new("日"));
+        assert!(glyphs[2].is_continuation());
+        assert_eq!(glyphs[3], 'x');
+    }
+
+    /// Create a list control filling the given horizontal span of the window, excluded from the debug lists
+    fn create_debug_list(
+        db: &mut SystemInterface<HeapComponentStorage, SingleThreadedDirectory>,
+        window_entity: EntityID,
+        anchor_horizontal: std::ops::Range<f32>,
+    ) -> EntityID {
+        let list_entity = db.create_entity(Some("Debug List")).unwrap();
+        db.insert_entity_component(list_entity, Control).unwrap();
+        db.insert_entity_component(list_entity, DebugExclude)
+            .unwrap();
+        db.insert_entity_component(list_entity, Position::default())
+            .unwrap();
+        db.insert_entity_component(list_entity, Size::default())
+            .unwrap();
+        db.insert_entity_component(list_entity, Anchors::new(anchor_horizontal, 0.0..1.0))
+            .unwrap();
+        db.insert_entity_component(list_entity, Margins::new(1, 1, 0, 0))
+            .unwrap();
+        db.insert_entity_component(list_entity, ListData::new(Some(list_entity)))
+            .unwrap();
+        db.insert_entity_component(list_entity, Vec::<String>::new())
+            .unwrap();
+        db.insert_entity_component(list_entity, LocalMousePositionData::default())
+            .unwrap();
+        db.insert_entity_component(list_entity, ParentEntity(window_entity))
+            .unwrap();
+        list_entity
+    }
+
+    #[test]
+    fn inspector_golden_frame() {
+        let mut harness = HeadlessHarness::new(Vector2I(48, 8)).unwrap();
+        harness
+            .ecs
+            .push_system(LocalMousePosition::new())
+            .stage(SystemStage::Input)
+            .after("headless_input");
+        harness.ecs.push_system(List::new()).label("list");
+        harness
+            .ecs
+            .push_system(EventProcessor::<ListEvent, EntityInspectorEvent>::new(
+                |list_event| match list_event {
+                    ListEvent::Pressed(index) => {
+                        Some(EntityInspectorEvent::SetInspectedEntity(index))
+                    }
+                    _ => None,
+                },
+            ))
+            .after("list");
+        harness
+            .ecs
+            .push_system(EventConsumer::<ListEvent>::new())
+            .stage(SystemStage::Cleanup);
+        harness
+            .ecs
+            .push_system(AnchorsMargins::new())
+            .stage(SystemStage::Layout)
+            .label("anchors_margins");
+        harness
+            .ecs
+            .push_system(GlobalPosition::new())
+            .stage(SystemStage::Layout)
+            .after("anchors_margins");
+        harness
+            .ecs
+            .push_system(ChildEntities::new())
+            .stage(SystemStage::Layout);
+
+        // The entity debug system lists entities on the left,
+        // and the component debug system lists the inspected entity's components on the right
+        let window_entity = harness.get_window_entity();
+        {
+            let mut db = harness.ecs.get_system_interface();
+
+            let entity_inspector_entity = db.create_entity(Some("Entity Inspector")).unwrap();
+            db.insert_entity_component(
+                entity_inspector_entity,
+                EventQueue::<EntityInspectorEvent>::default(),
+            )
+            .unwrap();
+            db.insert_entity_component(entity_inspector_entity, IntRange::new(-1..0))
+                .unwrap();
+
+            let entity_list_entity = create_debug_list(&mut db, window_entity, 0.0..0.5);
+            db.insert_entity_component(entity_list_entity, DebugEntityList)
+                .unwrap();
+            db.insert_entity_component(entity_list_entity, EventQueue::<ListEvent>::default())
+                .unwrap();
+            db.insert_entity_component(
+                entity_list_entity,
+                EventTargets::new(vec![entity_inspector_entity]),
+            )
+            .unwrap();
+
+            let component_list_entity = create_debug_list(&mut db, window_entity, 0.5..1.0);
+            db.insert_entity_component(component_list_entity, DebugComponentList)
+                .unwrap();
+
+            db.apply_commands().unwrap();
+        }
+
+        // Inspect the window entity
+        harness
+            .push_input(vec![AntigenInputEvent::MouseMove {
+                position: Vector2I(2, 3),
+                delta: Vector2I(0, 0),
+            }])
+            .unwrap();
+        harness
+            .push_input(vec![AntigenInputEvent::MousePress { button_mask: 1 }])
+            .unwrap();
+        harness.run_frames(4).unwrap();
+
+        let frame = harness
+            .get_golden_frame(&RGBArrangementPalette::new_884())
+            .unwrap();
+        assert_golden_frame(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/golden/inspector.frame"),
+            &frame,
+        );
+    }
+}
@@ -0,0 +1,8 @@
+pub mod components;
+pub mod systems;
+
+mod golden_frame;
+mod headless_harness;
+
+pub use golden_frame::*;
+pub use headless_harness::*;
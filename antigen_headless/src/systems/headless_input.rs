@@ -0,0 +1,60 @@
+use antigen::{
+    components::EventQueue,
+    core::events::AntigenInputEvent,
+    entity_component_system::{
+        system_interface::SystemInterface, ComponentAccess, ComponentStorage,
+        EntityComponentDirectory, SystemError, SystemTrait,
+    },
+};
+
+use crate::components::HeadlessInputData;
+
+/// Stands in for terminal input, replacing the contents of the input event queue with the next scripted frame
+///
+/// The queue is left empty on frames with no scripted input,
+/// so an EventConsumer for AntigenInputEvent isn't needed alongside it.
+#[derive(Debug)]
+pub struct HeadlessInput;
+
+impl Default for HeadlessInput {
+    fn default() -> Self {
+        HeadlessInput
+    }
+}
+
+impl HeadlessInput {
+    pub fn new() -> Self {
+        HeadlessInput
+    }
+}
+
+impl<CS, CD> SystemTrait<CS, CD> for HeadlessInput
+where
+    CS: ComponentStorage,
+    CD: EntityComponentDirectory,
+{
+    fn run(&mut self, db: &mut SystemInterface<CS, CD>) -> Result<(), SystemError>
+    where
+        CS: ComponentStorage,
+        CD: EntityComponentDirectory,
+    {
+        for (mut event_queue, mut headless_input) in
+            db.query::<(&mut EventQueue<AntigenInputEvent>, &mut HeadlessInputData)>()?
+        {
+            event_queue.clear();
+            if let Some(events) = headless_input.pop_front() {
+                event_queue.extend(events);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn get_component_access(&self) -> Option<ComponentAccess> {
+        Some(
+            ComponentAccess::new()
+                .with_write::<EventQueue<AntigenInputEvent>>()
+                .with_write::<HeadlessInputData>(),
+        )
+    }
+}
@@ -0,0 +1,3 @@
+mod headless_input;
+
+pub use headless_input::*;
@@ -18,11 +18,11 @@ use antigen::{
 fn main() {
     if let Err(err) = main_internal() {
         match err {
-            SystemError::Err(err) => {
+            SystemError::Quit => std::process::exit(0),
+            err => {
                 eprintln!("{}", err);
                 std::process::exit(1)
             }
-            SystemError::Quit => std::process::exit(0),
         }
     }
 }
@@ -35,7 +35,17 @@ fn main_internal() -> Result<(), SystemError> {
         SingleThreadedSystemRunner,
     >::default();
 
-    scenes::AntigenDebugScene::load(&mut ecs)?;
+    // Pass --dependency-test [scene file] to load a hot-reloaded scene file instead of the debug scene,
+    // falling back to the ANTIGEN_SCENE_FILE environment variable and then the bundled scene
+    let mut args = std::env::args().skip_while(|arg| arg != "--dependency-test");
+    if args.next().is_some() {
+        if let Some(scene_file) = args.next() {
+            std::env::set_var(scenes::SCENE_FILE_VAR, scene_file);
+        }
+        scenes::DependencyTestScene::load(&mut ecs)?;
+    } else {
+        scenes::AntigenDebugScene::load(&mut ecs)?;
+    }
 
     // Main loop
     let frame_time_target = Duration::from_secs_f32(1.0 / 60.0);
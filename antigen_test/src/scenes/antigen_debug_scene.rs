@@ -6,10 +6,10 @@ use antigen::{
     core::palette::RGBArrangementPalette,
     entity_component_system::{
         system_interface::SystemInterface, system_storage::SystemStorage, Assemblage,
-        ComponentStorage, EntityComponentDirectory, EntityComponentSystem, EntityID, Scene,
-        SystemRunner,
+        ComponentStorage, EcsError, EntityComponentDirectory, EntityComponentSystem, EntityID,
+        Scene, SystemRunner, SystemStage,
     },
-    primitive_types::{ColorRGB, ColorRGBF, Vector2I},
+    primitive_types::{ColorRGB, Vector2I},
     systems as antigen_systems,
 };
 use antigen_curses::{components as curses_components, systems as curses_systems};
@@ -31,30 +31,49 @@ pub struct AntigenDebugScene;
 impl Scene for AntigenDebugScene {
     fn register_systems<CS, CD, SS, SR>(
         ecs: &mut EntityComponentSystem<CS, CD, SS, SR>,
-    ) -> Result<(), String>
+    ) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory + 'static,
         SS: SystemStorage<CS, CD> + 'static,
         SR: SystemRunner + 'static,
     {
-        ecs.push_system(antigen_systems::EventConsumer::<AntigenInputEvent>::new());
+        ecs.push_system(antigen_systems::EventConsumer::<AntigenInputEvent>::new())
+            .stage(SystemStage::Input)
+            .before("curses_input_buffer");
         ecs.push_system(antigen_systems::EventConsumer::<
             curses_components::CursesEvent,
-        >::new());
-
-        ecs.push_system(curses_systems::CursesInputBuffer);
-        ecs.push_system(curses_systems::CursesKeyboard);
-        ecs.push_system(curses_systems::CursesMouse::new());
-        let pancurses_window_system = curses_systems::CursesWindow::new(&mut ecs.component_storage);
-        ecs.push_system(pancurses_window_system);
+        >::new())
+            .stage(SystemStage::Input)
+            .before("curses_input_buffer");
+
+        ecs.push_system(curses_systems::CursesInputBuffer)
+            .stage(SystemStage::Input)
+            .label("curses_input_buffer");
+        ecs.push_system(curses_systems::CursesKeyboard)
+            .stage(SystemStage::Input)
+            .after("curses_input_buffer")
+            .label("curses_input");
+        ecs.push_system(curses_systems::CursesMouse::new())
+            .stage(SystemStage::Input)
+            .after("curses_input_buffer")
+            .label("curses_input");
+        ecs.push_system(antigen_systems::InputRecorder::new())
+            .stage(SystemStage::Input)
+            .after("curses_input")
+            .label("input_recorder");
+        let pancurses_window_system = curses_systems::CursesWindow::new();
+        ecs.push_system(pancurses_window_system)
+            .stage(SystemStage::Input);
 
         ecs.push_system(systems::QuitKey::new(antigen::core::keyboard::Key::Escape));
         ecs.push_system(systems::InputAxis);
         ecs.push_system(systems::DestructionTestInput::new());
-        ecs.push_system(antigen_systems::LocalMousePosition::new());
+        ecs.push_system(antigen_systems::LocalMousePosition::new())
+            .stage(SystemStage::Input)
+            .after("input_recorder");
 
-        ecs.push_system(antigen_systems::List::new());
+        ecs.push_system(antigen_systems::List::new()).label("list");
 
         ecs.push_system(antigen_systems::EventProcessor::<
             antigen_systems::ListEvent,
@@ -66,7 +85,8 @@ impl Scene for AntigenDebugScene {
                 ),
                 _ => None,
             },
-        ));
+        ))
+        .after("list");
 
         ecs.push_system(antigen_systems::EventProcessor::<
             antigen_systems::ListEvent,
@@ -78,7 +98,8 @@ impl Scene for AntigenDebugScene {
                 }
                 _ => None,
             },
-        ));
+        ))
+        .after("list");
 
         ecs.push_system(antigen_systems::EventProcessor::<
             antigen_systems::ListEvent,
@@ -90,27 +111,40 @@ impl Scene for AntigenDebugScene {
                 ),
                 _ => None,
             },
-        ));
+        ))
+        .after("list");
 
-        ecs.push_system(antigen_systems::EventConsumer::<antigen_systems::ListEvent>::new());
+        ecs.push_system(antigen_systems::EventConsumer::<antigen_systems::ListEvent>::new())
+            .stage(SystemStage::Cleanup);
 
         ecs.push_system(systems::InputVelocity::new());
 
         ecs.push_system(antigen_systems::PositionIntegrator::new());
-        ecs.push_system(antigen_systems::AnchorsMargins::new());
-        ecs.push_system(antigen_systems::GlobalPosition::new());
-        ecs.push_system(antigen_systems::ChildEntities::new());
-        ecs.push_system(antigen_systems::SoftwareRenderer);
-        ecs.push_system(antigen_systems::StringRenderer);
+        ecs.push_system(antigen_systems::AnchorsMargins::new())
+            .stage(SystemStage::Layout)
+            .label("anchors_margins");
+        ecs.push_system(antigen_systems::GlobalPosition::new())
+            .stage(SystemStage::Layout)
+            .after("anchors_margins");
+        ecs.push_system(antigen_systems::ChildEntities::new())
+            .stage(SystemStage::Layout);
+        ecs.push_system(antigen_systems::SoftwareRenderer)
+            .stage(SystemStage::Render)
+            .label("framebuffer");
+        ecs.push_system(antigen_systems::StringRenderer)
+            .stage(SystemStage::Render)
+            .label("framebuffer");
         ecs.push_system(curses_systems::CursesRenderer::new(
             RGBArrangementPalette::new_884(),
-            curses_systems::TextColorMode::BlackWhite,
-        ));
+            antigen_components::TextColorMode::BlackWhite,
+        ))
+        .stage(SystemStage::Render)
+        .after("framebuffer");
 
         Ok(())
     }
 
-    fn create_entities<CS, CD>(db: &mut SystemInterface<CS, CD>) -> Result<(), String>
+    fn create_entities<CS, CD>(db: &mut SystemInterface<CS, CD>) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
@@ -127,18 +161,16 @@ impl Scene for AntigenDebugScene {
             global_event_queues_entity,
             antigen_components::EventQueue::<AntigenInputEvent>::default(),
         )?;
-
-        // Create main window
-        let cpu_framebuffer_entity = db.create_entity("CPU Framebuffer".into())?;
         db.insert_entity_component(
-            cpu_framebuffer_entity,
-            antigen_components::SoftwareFramebuffer::new(ColorRGB(0.0f32, 0.0f32, 0.0f32)),
+            global_event_queues_entity,
+            antigen_components::InputRecordingData::new(),
         )?;
 
-        let string_framebuffer_entity = db.create_entity("String Framebuffer".into())?;
+        // Create main window
+        let framebuffer_entity = db.create_entity("Cell Framebuffer".into())?;
         db.insert_entity_component(
-            string_framebuffer_entity,
-            antigen_components::SoftwareFramebuffer::new(' '),
+            framebuffer_entity,
+            antigen_components::CellFramebuffer::default(),
         )?;
 
         let main_window_entity = create_window_entity(
@@ -158,6 +190,14 @@ impl Scene for AntigenDebugScene {
             entity_inspector_entity,
             antigen_components::IntRange::new(-1..0),
         )?;
+        // Page Up / Page Down step through inspected entities
+        db.insert_entity_component(
+            entity_inspector_entity,
+            components::InputAxisData::new(
+                antigen::core::keyboard::Key::PageUp,
+                antigen::core::keyboard::Key::PageDown,
+            ),
+        )?;
 
         let component_inspector_entity = db.create_entity(Some("Component Inspector"))?;
         db.insert_entity_component(
@@ -187,12 +227,7 @@ impl Scene for AntigenDebugScene {
             main_window_entity,
             entity_inspector_entity,
         )?;
-        create_scene_tree_window(
-            db,
-            &mut assemblages,
-            main_window_entity,
-            entity_inspector_entity,
-        )?;
+        create_scene_tree_window(db, &mut assemblages, main_window_entity)?;
         create_component_list_window(
             db,
             &mut assemblages,
@@ -212,7 +247,7 @@ impl Scene for AntigenDebugScene {
 
     fn load<'a, CS, CD, SS, SR>(
         ecs: &'a mut EntityComponentSystem<CS, CD, SS, SR>,
-    ) -> Result<(), String>
+    ) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory + 'static,
@@ -222,37 +257,18 @@ impl Scene for AntigenDebugScene {
         Self::register_systems(ecs)?;
         let mut entity_component_database = ecs.get_system_interface();
         Self::create_entities(&mut entity_component_database)?;
+        entity_component_database.apply_commands()?;
         Ok(())
     }
 }
 
-fn create_string_control<CS, CD>(
-    db: &mut SystemInterface<CS, CD>,
-    string_assemblage: &mut Assemblage<CS, CD>,
-    debug_label: Option<&str>,
-    text: &str,
-    (x, y): (i64, i64),
-) -> Result<EntityID, String>
-where
-    CS: ComponentStorage,
-    CD: EntityComponentDirectory,
-{
-    let entity_id = string_assemblage.create_and_assemble_entity(db, debug_label)?;
-
-    *db.get_entity_component_mut::<String>(entity_id)? = text.into();
-
-    **db.get_entity_component_mut::<antigen_components::Position>(entity_id)? = Vector2I(x, y);
-
-    Ok(entity_id)
-}
-
 fn create_window_entity<S, D>(
     db: &mut SystemInterface<S, D>,
     debug_label: Option<&str>,
     position: antigen_components::Position,
     size: antigen_components::Size,
     parent_window_entity_id: Option<EntityID>,
-) -> Result<EntityID, String>
+) -> Result<EntityID, EcsError>
 where
     S: ComponentStorage,
     D: EntityComponentDirectory,
@@ -271,7 +287,7 @@ where
     Ok(entity_id)
 }
 
-fn create_assemblages<S, D>() -> Result<HashMap<EntityAssemblage, Assemblage<S, D>>, String>
+fn create_assemblages<S, D>() -> Result<HashMap<EntityAssemblage, Assemblage<S, D>>, EcsError>
 where
     S: ComponentStorage,
     D: EntityComponentDirectory,
@@ -301,31 +317,26 @@ where
             .finish(),
     );
 
-    assemblages.insert(
-        EntityAssemblage::RectControl,
-        Assemblage::build("Rect Entity", "ASCII Rectangle control")
-            .add_component(antigen_components::Control)?
-            .add_component(antigen_components::Position::default())?
-            .add_component(antigen_components::Size::default())?
-            .add_component(char::default())?
-            .add_component(ColorRGB(0.753f32, 0.753f32, 0.753f32))?
-            .finish(),
-    );
+    let rect_assemblage = Assemblage::build("Rect Entity", "ASCII Rectangle control")
+        .add_component(antigen_components::Control)?
+        .add_component(antigen_components::Position::default())?
+        .add_component(antigen_components::Size::default())?
+        .add_component(char::default())?
+        .add_component(ColorRGB(0.753f32, 0.753f32, 0.753f32))?
+        .finish();
 
     assemblages.insert(
         EntityAssemblage::BorderControl,
         Assemblage::build("Border Entity", "ASCII Border control")
-            .add_component(antigen_components::Control)?
-            .add_component(antigen_components::Position::default())?
-            .add_component(antigen_components::Size::default())?
-            .add_component(char::default())?
+            .extend(&rect_assemblage)
             .add_component(antigen_components::CPUShader(
                 antigen_components::CPUShader::rect,
             ))?
-            .add_component(ColorRGB(0.753f32, 0.753f32, 0.753f32))?
             .finish(),
     );
 
+    assemblages.insert(EntityAssemblage::RectControl, rect_assemblage);
+
     assemblages.insert(
         EntityAssemblage::DestructionTest,
         Assemblage::build(
@@ -345,7 +356,7 @@ fn create_game_window<CS, CD>(
     db: &mut SystemInterface<CS, CD>,
     assemblages: &mut HashMap<EntityAssemblage, Assemblage<CS, CD>>,
     parent_window_entity: EntityID,
-) -> Result<EntityID, String>
+) -> Result<EntityID, EcsError>
 where
     CS: ComponentStorage,
     CD: EntityComponentDirectory,
@@ -364,63 +375,47 @@ where
     )?;
 
     // Create Test Rects
-    let test_rect_entity = assemblages
+    assemblages
         .get_mut(&EntityAssemblage::RectControl)
         .unwrap()
-        .create_and_assemble_entity(db, Some("Test Rect Control"))?;
-    {
-        db.insert_entity_component(
-            test_rect_entity,
-            antigen_components::ParentEntity(game_window_entity),
-        )?;
-        db.insert_entity_component(
-            test_rect_entity,
-            antigen_components::GlobalPositionData::default(),
-        )?;
-        db.insert_entity_component(
-            test_rect_entity,
-            antigen_components::Anchors::new(0.0..1.0, 0.0..1.0),
-        )?;
-        db.insert_entity_component(
-            test_rect_entity,
-            antigen_components::CPUShader(antigen_components::CPUShader::hsv),
+        .create_and_assemble_entity_with(
+            db,
+            Some("Test Rect Control"),
+            (
+                antigen_components::ParentEntity(game_window_entity),
+                antigen_components::GlobalPositionData::default(),
+                antigen_components::Anchors::new(0.0..1.0, 0.0..1.0),
+                antigen_components::CPUShader(antigen_components::CPUShader::hsv),
+            ),
         )?;
-    }
 
     // Create Test Player
     let test_player_entity = assemblages
         .get_mut(&EntityAssemblage::Player)
         .unwrap()
-        .create_and_assemble_entity(db, Some("Test Player"))?;
-    db.insert_entity_component(
-        test_player_entity,
-        antigen_components::ParentEntity(game_window_entity),
-    )?;
-    db.insert_entity_component(
-        test_player_entity,
-        antigen_components::GlobalPositionData::default(),
-    )?;
+        .create_and_assemble_entity_with(
+            db,
+            Some("Test Player"),
+            (
+                antigen_components::ParentEntity(game_window_entity),
+                antigen_components::GlobalPositionData::default(),
+            ),
+        )?;
 
     // Create Test String
-    let test_string_entity = assemblages
+    assemblages
         .get_mut(&EntityAssemblage::StringControl)
         .unwrap()
-        .create_and_assemble_entity(db, Some("Test String Control"))?;
-    {
-        **db.get_entity_component_mut::<antigen_components::Position>(test_string_entity)? =
-            Vector2I(1, 1);
-        *db.get_entity_component_mut::<String>(test_string_entity)? =
-            "Testing One Two Three".into();
-
-        db.insert_entity_component(
-            test_string_entity,
-            antigen_components::ParentEntity(test_player_entity),
-        )?;
-        db.insert_entity_component(
-            test_string_entity,
-            antigen_components::GlobalPositionData::default(),
+        .create_and_assemble_entity_with(
+            db,
+            Some("Test String Control"),
+            (
+                antigen_components::Position(Vector2I(1, 1)),
+                String::from("Testing One Two Three"),
+                antigen_components::ParentEntity(test_player_entity),
+                antigen_components::GlobalPositionData::default(),
+            ),
         )?;
-    }
 
     Ok(game_window_entity)
 }
@@ -432,7 +427,7 @@ fn create_debug_window<CS, CD>(
     window_name: &str,
     anchor_horizontal: Range<f32>,
     anchor_vertical: Range<f32>,
-) -> Result<EntityID, String>
+) -> Result<EntityID, EcsError>
 where
     CS: ComponentStorage,
     CD: EntityComponentDirectory,
@@ -440,65 +435,51 @@ where
     let entity_list_window_entity = assemblages
         .get_mut(&EntityAssemblage::RectControl)
         .unwrap()
-        .create_and_assemble_entity(db, Some(&format!("{} Window", window_name)))?;
-    {
-        *db.get_entity_component_mut::<ColorRGBF>(entity_list_window_entity)? =
-            ColorRGB(0.0, 0.0, 0.0);
-
-        db.insert_entity_component(
-            entity_list_window_entity,
-            antigen_components::Position::default(),
-        )?;
-        db.insert_entity_component(entity_list_window_entity, antigen_components::ZIndex(1))?;
-        db.insert_entity_component(
-            entity_list_window_entity,
-            antigen_components::Size::default(),
-        )?;
-        db.insert_entity_component(
-            entity_list_window_entity,
-            antigen_components::ParentEntity(parent_window_entity),
-        )?;
-        db.insert_entity_component(
-            entity_list_window_entity,
-            antigen_components::Anchors::new(anchor_horizontal, anchor_vertical),
+        .create_and_assemble_entity_with(
+            db,
+            Some(&format!("{} Window", window_name)),
+            (
+                ColorRGB(0.0f32, 0.0f32, 0.0f32),
+                antigen_components::ZIndex(1),
+                antigen_components::ParentEntity(parent_window_entity),
+                antigen_components::Anchors::new(anchor_horizontal, anchor_vertical),
+            ),
         )?;
-    }
 
     let entity_list_border_entity = assemblages
         .get_mut(&EntityAssemblage::BorderControl)
         .unwrap()
-        .create_and_assemble_entity(db, Some(&format!("{} Border", window_name)))?;
-    {
-        db.insert_entity_component(
-            entity_list_border_entity,
-            antigen_components::ParentEntity(entity_list_window_entity),
-        )?;
-        db.insert_entity_component(
-            entity_list_border_entity,
-            antigen_components::Anchors::new(0.0..1.0, 0.0..1.0),
+        .create_and_assemble_entity_with(
+            db,
+            Some(&format!("{} Border", window_name)),
+            (
+                antigen_components::ParentEntity(entity_list_window_entity),
+                antigen_components::Anchors::new(0.0..1.0, 0.0..1.0),
+            ),
         )?;
-    }
 
     // Create Debug Window Title
-    let entity_list_title_entity = create_string_control(
-        db,
-        assemblages
-            .get_mut(&EntityAssemblage::StringControl)
-            .unwrap(),
-        Some(&format!("{} Title", window_name)),
-        &format!("{}\n========", window_name),
-        (2, 1),
-    )?;
-    {
-        db.insert_entity_component(
-            entity_list_title_entity,
-            antigen_components::ParentEntity(entity_list_border_entity),
-        )?;
-        db.insert_entity_component(
-            entity_list_title_entity,
-            antigen_components::GlobalPositionData::default(),
+    assemblages
+        .get_mut(&EntityAssemblage::StringControl)
+        .unwrap()
+        .create_and_assemble_entity_with(
+            db,
+            Some(&format!("{} Title", window_name)),
+            (
+                format!("{}\n{}", window_name, "=".repeat(window_name.len())),
+                antigen_components::ParentEntity(entity_list_border_entity),
+                antigen_components::GlobalPositionData::default(),
+                antigen_components::Size::default(),
+                // Spans the top two rows inside the border
+                antigen_components::Anchors::new(0.0..1.0, 0.0..0.0),
+                antigen_components::Margins::new(2, 2, 1, -3),
+                antigen_components::TextLayout::new(
+                    antigen_components::TextOverflow::Ellipsis,
+                    antigen_components::HorizontalAlignment::Center,
+                    antigen_components::VerticalAlignment::Top,
+                ),
+            ),
         )?;
-    }
 
     // Create Entity List
     let entity_list_entity = db.create_entity(Some(window_name))?;
@@ -535,7 +516,7 @@ fn create_entity_list_window<S, D>(
     assemblages: &mut HashMap<EntityAssemblage, Assemblage<S, D>>,
     parent_window_entity: EntityID,
     entity_inspector_entity: EntityID,
-) -> Result<EntityID, String>
+) -> Result<EntityID, EcsError>
 where
     S: ComponentStorage,
     D: EntityComponentDirectory,
@@ -566,8 +547,7 @@ fn create_scene_tree_window<S, D>(
     db: &mut SystemInterface<S, D>,
     assemblages: &mut HashMap<EntityAssemblage, Assemblage<S, D>>,
     parent_window_entity: EntityID,
-    entity_inspector_entity: EntityID,
-) -> Result<EntityID, String>
+) -> Result<EntityID, EcsError>
 where
     S: ComponentStorage,
     D: EntityComponentDirectory,
@@ -589,7 +569,7 @@ fn create_component_list_window<S, D>(
     assemblages: &mut HashMap<EntityAssemblage, Assemblage<S, D>>,
     parent_window_entity: EntityID,
     component_inspector_entity: EntityID,
-) -> Result<EntityID, String>
+) -> Result<EntityID, EcsError>
 where
     S: ComponentStorage,
     D: EntityComponentDirectory,
@@ -623,7 +603,7 @@ fn create_component_data_list_window<S, D>(
     db: &mut SystemInterface<S, D>,
     assemblages: &mut HashMap<EntityAssemblage, Assemblage<S, D>>,
     parent_window_entity: EntityID,
-) -> Result<EntityID, String>
+) -> Result<EntityID, EcsError>
 where
     S: ComponentStorage,
     D: EntityComponentDirectory,
@@ -640,6 +620,8 @@ where
         component_list_entity,
         antigen_components::DebugComponentDataList,
     )?;
+    db.get_entity_component_mut::<antigen_components::ListData>(component_list_entity)?
+        .set_rich_text(true);
     Ok(component_list_entity)
 }
 
@@ -648,7 +630,7 @@ fn create_system_list_window<S, D>(
     assemblages: &mut HashMap<EntityAssemblage, Assemblage<S, D>>,
     parent_window_entity: EntityID,
     system_inspector_entity: EntityID,
-) -> Result<EntityID, String>
+) -> Result<EntityID, EcsError>
 where
     S: ComponentStorage,
     D: EntityComponentDirectory,
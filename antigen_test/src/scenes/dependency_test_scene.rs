@@ -1,16 +1,22 @@
+use std::path::PathBuf;
+
 use antigen::{
-    components::{Control, ParentEntity, Position, Size, Velocity, Window},
+    components as antigen_components,
+    core::events::AntigenInputEvent,
     core::palette::RGBArrangementPalette,
     entity_component_system::ComponentStorage,
+    entity_component_system::EcsError,
     entity_component_system::EntityComponentDirectory,
     entity_component_system::Scene,
     entity_component_system::SystemInterface,
     entity_component_system::{
-        system_storage::SystemStorage, Assemblage, EntityComponentSystem, SystemRunner,
+        system_storage::SystemStorage, EntityComponentSystem, SerializationRegistry, SystemRunner,
+        SystemStage,
+    },
+    systems::{
+        ChildEntities, EventConsumer, GlobalPosition, PositionIntegrator, SoftwareRenderer,
+        StringRenderer,
     },
-    primitive_types::ColorRGB,
-    primitive_types::Vector2I,
-    systems::PositionIntegrator,
 };
 use antigen_curses::components as curses_components;
 use antigen_curses::systems as curses_systems;
@@ -18,12 +24,20 @@ use antigen_curses::systems as curses_systems;
 use crate::systems::InputVelocity;
 use crate::systems::QuitKey;
 
+/// Environment variable overriding the scene file loaded by DependencyTestScene
+pub const SCENE_FILE_VAR: &str = "ANTIGEN_SCENE_FILE";
+
+const DEFAULT_SCENE_FILE: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/scenes/dependency_test_scene.ron"
+);
+
 pub struct DependencyTestScene;
 
 impl Scene for DependencyTestScene {
     fn register_systems<CS, CD, SS, SR>(
         ecs: &mut EntityComponentSystem<CS, CD, SS, SR>,
-    ) -> Result<(), String>
+    ) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory + 'static,
@@ -37,16 +51,41 @@ impl Scene for DependencyTestScene {
         //   If any other system takes a mutable reference to the same component, the relation should be ignored and dependencies inferred from other components
         //   Otherwise, non-mutable references to the same component should be checked and stored as a System > System map
 
+        // Entities are loaded from the scene file and reloaded whenever it changes
+        let scene_file = std::env::var_os(SCENE_FILE_VAR)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_SCENE_FILE));
+
+        let mut serialization_registry = SerializationRegistry::new();
+        serialization_registry
+            .register_antigen_components()
+            .register_default::<curses_components::CursesWindowData>();
+
+        ecs.load_scene_file(scene_file, serialization_registry)?;
+
         // pred: (WindowComponent, CursesWindowComponent, SizeComponent)
         // ref: CursesWindowComponent, SizeComponent, CharComponent, CursesColorPairComponent, StringComponent
         // mut: SizeComponent, CursesColorSetComponent, CursesWindowComponent
-        let pancurses_window_system = curses_systems::CursesWindow::new(&mut ecs.component_storage);
-        ecs.push_system(pancurses_window_system);
+        let pancurses_window_system = curses_systems::CursesWindow::new();
+        ecs.push_system(pancurses_window_system)
+            .stage(SystemStage::Input);
 
         // pred: (WindowComponent, CursesWindowComponent)
         // ref: CursesWindowComponent
         // mut: ?MouseComponent, EventQueueComponent<AntigenEvent>
-        ecs.push_system(curses_systems::CursesInputBuffer);
+        ecs.push_system(EventConsumer::<AntigenInputEvent>::new())
+            .stage(SystemStage::Input)
+            .before("curses_input_buffer");
+        ecs.push_system(EventConsumer::<curses_components::CursesEvent>::new())
+            .stage(SystemStage::Input)
+            .before("curses_input_buffer");
+
+        ecs.push_system(curses_systems::CursesInputBuffer)
+            .stage(SystemStage::Input)
+            .label("curses_input_buffer");
+        ecs.push_system(curses_systems::CursesKeyboard)
+            .stage(SystemStage::Input)
+            .after("curses_input_buffer");
 
         ecs.push_system(QuitKey::new(antigen::core::keyboard::Key::Escape));
 
@@ -59,6 +98,17 @@ impl Scene for DependencyTestScene {
         // ref: VelocityComponent
         // mut: PositionComponent
         ecs.push_system(PositionIntegrator::new());
+        ecs.push_system(GlobalPosition::new())
+            .stage(SystemStage::Layout);
+        ecs.push_system(ChildEntities::new())
+            .stage(SystemStage::Layout);
+
+        ecs.push_system(SoftwareRenderer)
+            .stage(SystemStage::Render)
+            .label("framebuffer");
+        ecs.push_system(StringRenderer)
+            .stage(SystemStage::Render)
+            .label("framebuffer");
 
         // pred: CursesColorSetComponent, (ControlComponent, ParentEntityComponent, PositionComponent), (WindowComponent, CursesWindowComponent, SizeComponent)
         // ref: ParentEntityComponent, ZIndexComponent, ChildEntitiesComponent, CursesWindowComponent, ParentEntityComponent, CursesWindowComponent,
@@ -66,45 +116,35 @@ impl Scene for DependencyTestScene {
         // mut: CursesColorSetComponent
         ecs.push_system(curses_systems::CursesRenderer::new(
             RGBArrangementPalette::new_884(),
-            curses_systems::TextColorMode::Color(ColorRGB(0.0, 0.0, 0.0)),
-        ));
+            antigen_components::TextColorMode::BlackWhite,
+        ))
+        .stage(SystemStage::Render)
+        .after("framebuffer");
 
         Ok(())
     }
 
-    fn create_entities<CS, CD>(db: &mut SystemInterface<CS, CD>) -> Result<(), String>
+    fn create_entities<CS, CD>(db: &mut SystemInterface<CS, CD>) -> Result<(), EcsError>
     where
         CS: ComponentStorage,
         CD: EntityComponentDirectory,
     {
-        // Create Main Window
-        let main_window_entity = db.create_entity(Some("Main Window"))?;
-        {
-            db.insert_entity_component(main_window_entity, Window)?;
-            db.insert_entity_component(
-                main_window_entity,
-                curses_components::CursesWindowData::default(),
-            )?;
-            db.insert_entity_component(main_window_entity, Size(Vector2I(64, 32)))?;
-        }
-
-        // Create Player
-        let mut player_assemblage = Assemblage::build(
-            "Player Entity",
-            "Controllable ASCII character with position and velocity",
-        )
-        .add_component(Control)?
-        .add_component(Position(Vector2I(1, 1)))?
-        .add_component(Velocity::default())?
-        .add_component('@')?
-        .add_component(ColorRGB(1.0f32, 0.6f32, 1.0f32))?
-        .finish();
-
-        let test_player_entity =
-            player_assemblage.create_and_assemble_entity(db, Some("Test Player"))?;
-        {
-            db.insert_entity_component(test_player_entity, ParentEntity(main_window_entity))?;
-        }
+        // Runtime-only entities are created here, the rest are spawned from the scene file by load_scene_file
+        let global_event_queues_entity = db.create_entity("Global Event Queues".into())?;
+        db.insert_entity_component(
+            global_event_queues_entity,
+            antigen_components::EventQueue::<curses_components::CursesEvent>::default(),
+        )?;
+        db.insert_entity_component(
+            global_event_queues_entity,
+            antigen_components::EventQueue::<AntigenInputEvent>::default(),
+        )?;
+
+        let framebuffer_entity = db.create_entity("Cell Framebuffer".into())?;
+        db.insert_entity_component(
+            framebuffer_entity,
+            antigen_components::CellFramebuffer::default(),
+        )?;
 
         Ok(())
     }
@@ -2,4 +2,4 @@ mod antigen_debug_scene;
 mod dependency_test_scene;
 
 pub use antigen_debug_scene::AntigenDebugScene;
-pub use dependency_test_scene::DependencyTestScene;
+pub use dependency_test_scene::{DependencyTestScene, SCENE_FILE_VAR};
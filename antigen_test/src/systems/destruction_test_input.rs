@@ -43,6 +43,8 @@ where
                         .entity_has_component::<DestructionTestInputData>(entity_id)
                 });
 
+            // Defer destruction so the entities stay valid for the rest of the frame
+            let commands = db.commands();
             for entity_id in destruction_test_entities {
                 let input_key: antigen::core::keyboard::Key =
                     **db.get_entity_component::<DestructionTestInputData>(entity_id)?;
@@ -53,7 +55,8 @@ where
                 for event in event_queue.clone() {
                     if let AntigenInputEvent::KeyPress { key_code } = event {
                         if key_code == input_key {
-                            db.destroy_entity(entity_id)?;
+                            commands.destroy_entity(entity_id);
+                            break;
                         }
                     }
                 }
@@ -63,9 +63,11 @@ where
                     }
                 }
 
-                let ui_tab_input_component = db.get_entity_component_mut::<IntRange>(entity_id)?;
+                let mut ui_tab_input_component =
+                    db.get_entity_component_mut::<IntRange>(entity_id)?;
 
-                ui_tab_input_component.set_index(ui_tab_input_component.get_index() + offset);
+                let index = ui_tab_input_component.get_index();
+                ui_tab_input_component.set_index(index + offset);
             }
         }
 